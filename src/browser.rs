@@ -9,7 +9,13 @@ pub enum LaunchResult {
     Existing { ws_url: String },
 }
 
+/// Serializes launches so concurrent sessions (http transport) share one browser
+/// instead of racing to kill and respawn it.
+static LAUNCH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
 pub async fn launch(config: &BrowserConfig) -> Result<LaunchResult, BrowserError> {
+    let _launching = LAUNCH_LOCK.lock().await;
+
     // Check if CDP is already available (browser already running with debugging port)
     if let Ok(ws_url) = try_connect_existing(config.port).await {
         tracing::info!("Found existing browser with CDP on port {}", config.port);
@@ -59,6 +65,14 @@ pub async fn launch(config: &BrowserConfig) -> Result<LaunchResult, BrowserError
         tracing::info!("Loading extensions: {paths}");
     }
 
+    // Route through a proxy. Credentials never go on the command line — they are
+    // answered from Fetch.authRequired once connected.
+    if let Some(proxy) = config.proxy.as_deref().filter(|p| !p.is_empty()) {
+        let settings = crate::config::parse_proxy(proxy);
+        tracing::info!("Proxy: {}", settings.server);
+        args.push(format!("--proxy-server={}", settings.server));
+    }
+
     tracing::info!("Launching browser: {}", config.executable);
     Command::new(&config.executable)
         .args(&args)
@@ -66,7 +80,7 @@ pub async fn launch(config: &BrowserConfig) -> Result<LaunchResult, BrowserError
         .map_err(|e| BrowserError::LaunchFailed(e.to_string()))?;
 
     // Poll until CDP is available and targets have stabilized (no more session restore churn)
-    let ws_url = poll_until_stable(config.port).await?;
+    let ws_url = poll_until_stable(config.port, Duration::from_millis(config.launch_timeout_ms)).await?;
     Ok(LaunchResult::Spawned { ws_url })
 }
 
@@ -138,10 +152,10 @@ pub async fn find_target_ws_url(port: u16, target_id: Option<&str>) -> Result<St
         .get(&url)
         .send()
         .await
-        .map_err(|_| BrowserError::Timeout)?
+        .map_err(|e| BrowserError::Unreachable(e.to_string()))?
         .json()
         .await
-        .map_err(|_| BrowserError::Timeout)?;
+        .map_err(|e| BrowserError::Unreachable(e.to_string()))?;
 
     for target in &targets {
         if target.get("type").and_then(|t| t.as_str()) != Some("page") {
@@ -160,7 +174,52 @@ pub async fn find_target_ws_url(port: u16, target_id: Option<&str>) -> Result<St
         }
     }
 
-    Err(BrowserError::Timeout)
+    Err(BrowserError::TargetNotFound)
+}
+
+/// The browser-level WebSocket URL from /json/version. Target.* calls that manage the
+/// browser itself (browser contexts, tabs for other sessions) go over this endpoint.
+pub async fn browser_ws_url(port: u16) -> Result<String, BrowserError> {
+    let url = format!("http://localhost:{port}/json/version");
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(3))
+        .build()
+        .map_err(|e| BrowserError::LaunchFailed(e.to_string()))?;
+
+    let version: serde_json::Value = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| BrowserError::Unreachable(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| BrowserError::Unreachable(e.to_string()))?;
+
+    version
+        .get("webSocketDebuggerUrl")
+        .and_then(|u| u.as_str())
+        .map(|u| u.to_owned())
+        .ok_or_else(|| BrowserError::Unreachable("/json/version has no webSocketDebuggerUrl".to_owned()))
+}
+
+/// Close a target through /json/close — usable without a CDP connection (e.g. from Drop).
+pub async fn close_target(port: u16, target_id: &str) -> Result<(), BrowserError> {
+    let url = format!("http://localhost:{port}/json/close/{target_id}");
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(3))
+        .build()
+        .map_err(|e| BrowserError::LaunchFailed(e.to_string()))?;
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| BrowserError::Unreachable(e.to_string()))?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(BrowserError::TargetNotFound)
+    }
 }
 
 async fn try_connect_existing(port: u16) -> Result<String, ()> {
@@ -170,7 +229,7 @@ async fn try_connect_existing(port: u16) -> Result<String, ()> {
 /// Poll until CDP is available AND page targets have stabilized.
 /// Returns the WS URL of the first stable page target.
 /// Handles both slow browser launches and session restore target churn.
-async fn poll_until_stable(port: u16) -> Result<String, BrowserError> {
+async fn poll_until_stable(port: u16, timeout: Duration) -> Result<String, BrowserError> {
     let url = format!("http://localhost:{port}/json");
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(3))
@@ -180,8 +239,9 @@ async fn poll_until_stable(port: u16) -> Result<String, BrowserError> {
     let mut last_page_count: Option<usize> = None;
     let mut stable_streak = 0u32;
 
-    // Poll for up to 60s (120 * 500ms) — covers slow machines
-    for _ in 0..120 {
+    // Poll every 500ms up to launch_timeout_ms (default 60s — covers slow machines)
+    let deadline = tokio::time::Instant::now() + timeout;
+    while tokio::time::Instant::now() < deadline {
         tokio::time::sleep(Duration::from_millis(500)).await;
 
         let response = match client.get(&url).send().await {
@@ -226,22 +286,51 @@ async fn poll_until_stable(port: u16) -> Result<String, BrowserError> {
         }
     }
 
-    Err(BrowserError::Timeout)
+    Err(BrowserError::Timeout(timeout))
 }
 
 #[derive(Debug)]
 pub enum BrowserError {
     LaunchFailed(String),
-    Timeout,
+    /// The launched browser's targets didn't settle within this long (launch_timeout_ms).
+    Timeout(Duration),
+    /// The /json endpoint couldn't be reached or returned something unreadable.
+    Unreachable(String),
+    /// /json answered but had no matching page target.
+    TargetNotFound,
 }
 
 impl std::fmt::Display for BrowserError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             BrowserError::LaunchFailed(msg) => write!(f, "Failed to launch browser: {msg}"),
-            BrowserError::Timeout => write!(f, "Browser did not become ready within 60 seconds"),
+            BrowserError::Timeout(waited) if waited.subsec_millis() == 0 => {
+                write!(f, "Browser did not become ready within {} seconds", waited.as_secs())
+            }
+            BrowserError::Timeout(waited) => {
+                write!(f, "Browser did not become ready within {}ms", waited.as_millis())
+            }
+            BrowserError::Unreachable(msg) => write!(f, "CDP endpoint unreachable: {msg}"),
+            BrowserError::TargetNotFound => write!(f, "No matching page target"),
         }
     }
 }
 
 impl std::error::Error for BrowserError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeout_message_uses_seconds_for_whole_seconds() {
+        let err = BrowserError::Timeout(Duration::from_millis(90_000));
+        assert_eq!(err.to_string(), "Browser did not become ready within 90 seconds");
+    }
+
+    #[test]
+    fn timeout_message_uses_millis_for_fractional_seconds() {
+        let err = BrowserError::Timeout(Duration::from_millis(1500));
+        assert_eq!(err.to_string(), "Browser did not become ready within 1500ms");
+    }
+}
@@ -14,6 +14,9 @@ struct CdpCommand {
     id: u64,
     method: String,
     params: Value,
+    /// Flat session the command is for (Target.attachToTarget with flatten). None = our own target.
+    #[serde(rename = "sessionId", skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -23,6 +26,8 @@ struct CdpMessage {
     result: Option<Value>,
     error: Option<CdpErrorData>,
     params: Option<Value>,
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -45,11 +50,18 @@ pub struct CdpConnection {
     pending: Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, CdpErrorData>>>>>,
     event_sender: broadcast::Sender<CdpEvent>,
     next_id: AtomicU64,
+    /// Target this connection is attached to, from the /devtools/page/<id> WebSocket path.
+    /// None for browser-level or non-standard endpoints.
+    target_id: Option<String>,
+    /// Frame ID → default (main world) execution context ID, kept current by the reader from
+    /// Runtime.executionContextCreated/Destroyed/Cleared. Filled once Runtime is enabled.
+    contexts: Arc<std::sync::Mutex<HashMap<String, i64>>>,
 }
 
 impl std::fmt::Debug for CdpConnection {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CdpConnection")
+            .field("target_id", &self.target_id)
             .field("next_id", &self.next_id.load(Ordering::Relaxed))
             .finish()
     }
@@ -62,6 +74,8 @@ pub enum CdpError {
     ResponseError { code: i64, message: String },
     ResponseDropped,
     Timeout,
+    /// Runtime.evaluate threw or its promise rejected. Carries the exception description.
+    JsException(String),
 }
 
 impl std::fmt::Display for CdpError {
@@ -74,6 +88,7 @@ impl std::fmt::Display for CdpError {
             }
             CdpError::ResponseDropped => write!(f, "CDP response channel dropped"),
             CdpError::Timeout => write!(f, "CDP command timed out"),
+            CdpError::JsException(msg) => write!(f, "JavaScript exception: {msg}"),
         }
     }
 }
@@ -83,6 +98,14 @@ impl std::error::Error for CdpError {}
 // --- Free functions operating on connection data ---
 
 pub async fn connect(ws_url: &str) -> Result<CdpConnection, CdpError> {
+    connect_with_keepalive(ws_url, None).await
+}
+
+/// Connect, optionally sending a WebSocket ping every `keepalive` interval. A ping still
+/// unanswered at the next tick means the socket is dead: both tasks shut down, pending
+/// callers get ResponseDropped and later sends fail fast with SendFailed, so the reconnect
+/// path kicks in immediately instead of after a 30s command timeout.
+pub async fn connect_with_keepalive(ws_url: &str, keepalive: Option<std::time::Duration>) -> Result<CdpConnection, CdpError> {
     use futures_util::{SinkExt, StreamExt};
 
     let (ws_stream, _) = tokio::time::timeout(
@@ -98,24 +121,70 @@ pub async fn connect(ws_url: &str) -> Result<CdpConnection, CdpError> {
     let (event_sender, _) = broadcast::channel::<CdpEvent>(256);
     let pending: Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, CdpErrorData>>>>> =
         Arc::new(Mutex::new(HashMap::new()));
+    let contexts: Arc<std::sync::Mutex<HashMap<String, i64>>> = Arc::new(std::sync::Mutex::new(HashMap::new()));
 
-    // Writer task: takes commands from channel, serializes to WebSocket
+    // Set by the reader on Pong, cleared by the writer when it pings
+    let pong_received = Arc::new(std::sync::atomic::AtomicBool::new(true));
+    // Fired by the writer when keepalive declares the socket dead
+    let dead = Arc::new(tokio::sync::Notify::new());
+
+    // Writer task: takes commands from channel, serializes to WebSocket, pings on keepalive
+    let writer_pong = pong_received.clone();
+    let writer_dead = dead.clone();
     let writer_handle = tokio::spawn(async move {
-        while let Some(cmd) = cmd_receiver.recv().await {
-            let json = serde_json::to_string(&cmd).unwrap();
-            if ws_write.send(Message::Text(json.into())).await.is_err() {
-                break;
+        let mut ticker = keepalive.map(|period| {
+            let mut t = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            t.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            t
+        });
+        loop {
+            let tick = async {
+                match ticker.as_mut() {
+                    Some(t) => { t.tick().await; }
+                    None => std::future::pending::<()>().await,
+                }
+            };
+            tokio::select! {
+                cmd = cmd_receiver.recv() => {
+                    let Some(cmd) = cmd else { break };
+                    let json = serde_json::to_string(&cmd).unwrap();
+                    if ws_write.send(Message::Text(json.into())).await.is_err() {
+                        break;
+                    }
+                }
+                _ = tick => {
+                    if !writer_pong.swap(false, Ordering::Relaxed) {
+                        tracing::warn!("CDP keepalive: no pong since last ping, treating connection as dead");
+                        break;
+                    }
+                    if ws_write.send(Message::Ping(Vec::new().into())).await.is_err() {
+                        break;
+                    }
+                }
             }
         }
+        writer_dead.notify_one();
     });
 
     // Reader task: reads WebSocket, routes responses and events
     let pending_clone = pending.clone();
     let event_sender_clone = event_sender.clone();
+    let contexts_clone = contexts.clone();
     let reader_handle = tokio::spawn(async move {
-        while let Some(Ok(msg)) = ws_read.next().await {
+        loop {
+            let msg = tokio::select! {
+                msg = ws_read.next() => match msg {
+                    Some(Ok(m)) => m,
+                    _ => break,
+                },
+                _ = dead.notified() => break,
+            };
             let text = match msg {
                 Message::Text(t) => t,
+                Message::Pong(_) => {
+                    pong_received.store(true, Ordering::Relaxed);
+                    continue;
+                }
                 _ => continue,
             };
 
@@ -137,6 +206,15 @@ pub async fn connect(ws_url: &str) -> Result<CdpConnection, CdpError> {
             }
             // Event (has method, no id) → broadcast
             else if let Some(method) = parsed.method {
+                // Events from flat-attached side sessions belong to other tabs
+                if parsed.session_id.is_some() {
+                    continue;
+                }
+                // Track contexts here rather than in a subscriber: Runtime.enable replays existing
+                // contexts immediately, before anyone has had a chance to subscribe
+                if method.starts_with("Runtime.executionContext") {
+                    track_execution_context(&contexts_clone, &method, parsed.params.as_ref());
+                }
                 let _ = event_sender_clone.send(CdpEvent {
                     method,
                     params: parsed.params.unwrap_or(Value::Null),
@@ -166,11 +244,71 @@ pub async fn connect(ws_url: &str) -> Result<CdpConnection, CdpError> {
         pending,
         event_sender,
         next_id: AtomicU64::new(1),
+        target_id: target_id_from_ws_url(ws_url),
+        contexts,
     })
 }
 
-/// Send a CDP command and wait for its response.
-pub async fn send(conn: &CdpConnection, method: &str, params: Value) -> Result<Value, CdpError> {
+/// Apply a Runtime.executionContext* event to the frame → context cache.
+/// Only default contexts count — isolated worlds (extensions, our own) are skipped.
+fn track_execution_context(contexts: &std::sync::Mutex<HashMap<String, i64>>, method: &str, params: Option<&Value>) {
+    let mut map = contexts.lock().unwrap_or_else(|e| e.into_inner());
+    match method {
+        "Runtime.executionContextCreated" => {
+            let Some(context) = params.and_then(|p| p.get("context")) else { return };
+            let aux = context.get("auxData");
+            let is_default = aux.and_then(|a| a.get("isDefault")).and_then(|v| v.as_bool()).unwrap_or(false);
+            let frame_id = aux.and_then(|a| a.get("frameId")).and_then(|v| v.as_str());
+            if let (true, Some(frame_id), Some(id)) = (is_default, frame_id, context.get("id").and_then(|v| v.as_i64())) {
+                map.insert(frame_id.to_owned(), id);
+            }
+        }
+        "Runtime.executionContextDestroyed" => {
+            if let Some(id) = params.and_then(|p| p.get("executionContextId")).and_then(|v| v.as_i64()) {
+                map.retain(|_, context_id| *context_id != id);
+            }
+        }
+        "Runtime.executionContextsCleared" => map.clear(),
+        _ => {}
+    }
+}
+
+/// The main frame's default execution context, if known. A page target's main frame
+/// shares the target's ID.
+pub fn main_context_id(conn: &CdpConnection) -> Option<i64> {
+    let frame_id = conn.target_id.as_deref()?;
+    conn.contexts.lock().unwrap_or_else(|e| e.into_inner()).get(frame_id).copied()
+}
+
+/// Send a CDP command and wait for its response. Runtime.evaluate without an explicit
+/// context is pinned to the cached main-frame context, so it can't resolve to another
+/// frame's or a stale context; if that context vanished mid-navigation, it is retried
+/// with implicit resolution.
+pub async fn send(conn: &CdpConnection, method: &str, mut params: Value) -> Result<Value, CdpError> {
+    let pin = method == "Runtime.evaluate"
+        && params.get("contextId").is_none()
+        && params.get("uniqueContextId").is_none();
+    if let (true, Some(context_id)) = (pin, main_context_id(conn)) {
+        params["contextId"] = serde_json::json!(context_id);
+        match send_raw(conn, None, method, params.clone()).await {
+            Err(CdpError::ResponseError { message, .. }) if message.contains("Cannot find context") => {
+                if let Some(obj) = params.as_object_mut() {
+                    obj.remove("contextId");
+                }
+            }
+            other => return other,
+        }
+    }
+    send_raw(conn, None, method, params).await
+}
+
+/// Send a command to a session attached over this connection with Target.attachToTarget
+/// (flatten: true). Sent as-is: the context cache only covers this connection's own target.
+pub async fn send_to_session(conn: &CdpConnection, session_id: &str, method: &str, params: Value) -> Result<Value, CdpError> {
+    send_raw(conn, Some(session_id), method, params).await
+}
+
+async fn send_raw(conn: &CdpConnection, session_id: Option<&str>, method: &str, params: Value) -> Result<Value, CdpError> {
     let id = conn.next_id.fetch_add(1, Ordering::Relaxed);
     let (response_tx, response_rx) = oneshot::channel();
 
@@ -182,6 +320,7 @@ pub async fn send(conn: &CdpConnection, method: &str, params: Value) -> Result<V
         id,
         method: method.to_owned(),
         params,
+        session_id: session_id.map(|s| s.to_owned()),
     };
     conn.cmd_sender
         .send(cmd)
@@ -202,6 +341,54 @@ pub async fn send(conn: &CdpConnection, method: &str, params: Value) -> Result<V
     }
 }
 
+/// Turn a Runtime.evaluate result carrying exceptionDetails (a throw or a rejected promise)
+/// into JsException, so callers can't mistake it for a resolved null/undefined.
+pub fn check_exception(result: Value) -> Result<Value, CdpError> {
+    let Some(details) = result.get("exceptionDetails") else { return Ok(result) };
+    let exception = details.get("exception");
+    let message = exception
+        .and_then(|e| e.get("description"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_owned())
+        // Rejections with a non-Error value (reject("nope")) only carry the value
+        .or_else(|| exception.and_then(|e| e.get("value")).map(|v| match v.as_str() {
+            Some(s) => s.to_owned(),
+            None => v.to_string(),
+        }))
+        .or_else(|| details.get("text").and_then(|v| v.as_str()).map(|s| s.to_owned()))
+        .unwrap_or_else(|| "Unknown JS error".to_owned());
+    Err(CdpError::JsException(message))
+}
+
+/// The value out of a Runtime.evaluate result — Null when the script returned undefined.
+pub fn take_value(mut result: Value) -> Value {
+    result
+        .get_mut("result")
+        .and_then(|r| r.get_mut("value"))
+        .map(Value::take)
+        .unwrap_or(Value::Null)
+}
+
+/// Evaluate an expression and return its unwrapped value. Throws and rejected promises
+/// come back as JsException rather than a Null that looks like "not found".
+pub async fn eval_value(conn: &CdpConnection, expression: &str) -> Result<Value, CdpError> {
+    let result = execute(conn, crate::commands::evaluate(expression)).await?;
+    check_exception(result).map(take_value)
+}
+
+/// Target ID from a page WebSocket URL (ws://host:port/devtools/page/<id>) — the same id
+/// /json discovery reports for the target.
+fn target_id_from_ws_url(ws_url: &str) -> Option<String> {
+    let (_, rest) = ws_url.split_once("/devtools/page/")?;
+    let id = rest.split(['/', '?', '#']).next().unwrap_or("");
+    if id.is_empty() { None } else { Some(id.to_owned()) }
+}
+
+/// The target this connection is attached to, if known.
+pub fn target_id(conn: &CdpConnection) -> Option<&str> {
+    conn.target_id.as_deref()
+}
+
 /// Subscribe to CDP events (console messages, network requests, etc.).
 pub fn subscribe_events(conn: &CdpConnection) -> broadcast::Receiver<CdpEvent> {
     conn.event_sender.subscribe()
@@ -252,6 +439,11 @@ impl LiveConnection {
         self.inner.read().await.clone()
     }
 
+    /// Target ID of the current connection, or None if not connected or unknown.
+    pub async fn target_id(&self) -> Option<String> {
+        self.inner.read().await.as_deref().and_then(target_id).map(|id| id.to_owned())
+    }
+
     /// Swap to a new connection (tab switch or reconnect).
     /// Drains all pending responses on the old connection so in-flight
     /// callers get an immediate error instead of waiting for the 30s timeout.
@@ -275,8 +467,8 @@ impl LiveConnection {
 }
 
 /// Connect to a target and enable required CDP domains.
-pub async fn connect_to_target(ws_url: &str) -> Result<CdpConnection, CdpError> {
-    let conn = connect(ws_url).await?;
+pub async fn connect_to_target(ws_url: &str, keepalive: Option<std::time::Duration>) -> Result<CdpConnection, CdpError> {
+    let conn = connect_with_keepalive(ws_url, keepalive).await?;
     execute(&conn, crate::commands::enable_page()).await?;
     execute(&conn, crate::commands::enable_dom()).await?;
     execute(&conn, crate::commands::enable_runtime()).await?;
@@ -285,3 +477,37 @@ pub async fn connect_to_target(ws_url: &str) -> Result<CdpConnection, CdpError>
     execute(&conn, crate::commands::add_stealth_script()).await?;
     Ok(conn)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_id_from_page_url() {
+        assert_eq!(
+            target_id_from_ws_url("ws://127.0.0.1:9222/devtools/page/ABC123").as_deref(),
+            Some("ABC123")
+        );
+    }
+
+    #[test]
+    fn target_id_ignores_query() {
+        assert_eq!(
+            target_id_from_ws_url("ws://127.0.0.1:9222/devtools/page/ABC123?token=x").as_deref(),
+            Some("ABC123")
+        );
+    }
+
+    #[test]
+    fn target_id_none_for_browser_url() {
+        assert_eq!(target_id_from_ws_url("ws://127.0.0.1:9222/devtools/browser/0f1e2d"), None);
+    }
+
+    #[test]
+    fn session_id_sent_only_for_side_sessions() {
+        let cmd = CdpCommand { id: 1, method: "Page.enable".to_owned(), params: Value::Null, session_id: None };
+        assert!(serde_json::to_value(&cmd).unwrap().get("sessionId").is_none());
+        let cmd = CdpCommand { session_id: Some("S1".to_owned()), ..cmd };
+        assert_eq!(serde_json::to_value(&cmd).unwrap()["sessionId"], "S1");
+    }
+}
@@ -2,17 +2,37 @@ use serde_json::{Value, json};
 
 /// Navigate to a URL. Returns ("Page.navigate", params).
 pub fn navigate(url: &str) -> (&'static str, Value) {
-    ("Page.navigate", json!({ "url": url }))
+    navigate_with_referrer(url, None)
+}
+
+/// Navigate to a URL, sending the given Referer header.
+pub fn navigate_with_referrer(url: &str, referrer: Option<&str>) -> (&'static str, Value) {
+    match referrer {
+        Some(referrer) => ("Page.navigate", json!({ "url": url, "referrer": referrer })),
+        None => ("Page.navigate", json!({ "url": url })),
+    }
+}
+
+/// Bypass the HTTP cache for every request while disabled is true.
+pub fn set_cache_disabled(disabled: bool) -> (&'static str, Value) {
+    ("Network.setCacheDisabled", json!({ "cacheDisabled": disabled }))
 }
 
 /// Evaluate a JavaScript expression. Returns ("Runtime.evaluate", params).
 pub fn evaluate(expression: &str) -> (&'static str, Value) {
+    evaluate_with_gesture(expression, false)
+}
+
+/// Evaluate JavaScript, optionally as if triggered by a user gesture.
+/// Unlocks gesture-gated APIs (fullscreen, clipboard, autoplay).
+pub fn evaluate_with_gesture(expression: &str, user_gesture: bool) -> (&'static str, Value) {
     (
         "Runtime.evaluate",
         json!({
             "expression": expression,
             "returnByValue": true,
             "awaitPromise": true,
+            "userGesture": user_gesture,
         }),
     )
 }
@@ -53,46 +73,52 @@ pub fn key_event(event_type: &str, text: &str) -> (&'static str, Value) {
 
 /// Dispatch a special key (Enter, Tab, Escape, etc.) via keyDown + keyUp.
 pub fn press_key(key: &str) -> Vec<(&'static str, Value)> {
+    vec![named_key_event("keyDown", key, false), named_key_event("keyUp", key, false)]
+}
+
+/// A single keyDown or keyUp for a named key. `auto_repeat` marks a held key's repeated keyDown.
+pub fn named_key_event(kind: &str, key: &str, auto_repeat: bool) -> (&'static str, Value) {
     let (key_code, code, windows_vk) = match key {
-        "Enter" => (13, "Enter", 13),
-        "Tab" => (9, "Tab", 9),
-        "Escape" => (27, "Escape", 27),
-        "Backspace" => (8, "Backspace", 8),
-        "Delete" => (46, "Delete", 46),
-        "ArrowUp" => (38, "ArrowUp", 38),
-        "ArrowDown" => (40, "ArrowDown", 40),
-        "ArrowLeft" => (37, "ArrowLeft", 37),
-        "ArrowRight" => (39, "ArrowRight", 39),
-        "Home" => (36, "Home", 36),
-        "End" => (35, "End", 35),
-        "PageUp" => (33, "PageUp", 33),
-        "PageDown" => (34, "PageDown", 34),
-        "Space" => (32, "Space", 32),
-        _ => (0, key, 0),
+        "Enter" => (13, "Enter".to_owned(), 13),
+        "Tab" => (9, "Tab".to_owned(), 9),
+        "Escape" => (27, "Escape".to_owned(), 27),
+        "Backspace" => (8, "Backspace".to_owned(), 8),
+        "Delete" => (46, "Delete".to_owned(), 46),
+        "ArrowUp" => (38, "ArrowUp".to_owned(), 38),
+        "ArrowDown" => (40, "ArrowDown".to_owned(), 40),
+        "ArrowLeft" => (37, "ArrowLeft".to_owned(), 37),
+        "ArrowRight" => (39, "ArrowRight".to_owned(), 39),
+        "Home" => (36, "Home".to_owned(), 36),
+        "End" => (35, "End".to_owned(), 35),
+        "PageUp" => (33, "PageUp".to_owned(), 33),
+        "PageDown" => (34, "PageDown".to_owned(), 34),
+        "Space" => (32, "Space".to_owned(), 32),
+        "Shift" => (16, "ShiftLeft".to_owned(), 16),
+        "Control" => (17, "ControlLeft".to_owned(), 17),
+        "Alt" => (18, "AltLeft".to_owned(), 18),
+        // Letters and digits get their physical codes (KeyW, Digit1) — games listen on event.code
+        k if k.len() == 1 && k.as_bytes()[0].is_ascii_alphabetic() => {
+            let upper = k.as_bytes()[0].to_ascii_uppercase();
+            (upper as i64, format!("Key{}", upper as char), upper as i64)
+        }
+        k if k.len() == 1 && k.as_bytes()[0].is_ascii_digit() => {
+            let digit = k.as_bytes()[0];
+            (digit as i64, format!("Digit{}", digit as char), digit as i64)
+        }
+        _ => (0, key.to_owned(), 0),
     };
 
-    vec![
-        (
-            "Input.dispatchKeyEvent",
-            json!({
-                "type": "keyDown",
-                "key": key,
-                "code": code,
-                "windowsVirtualKeyCode": windows_vk,
-                "nativeVirtualKeyCode": key_code,
-            }),
-        ),
-        (
-            "Input.dispatchKeyEvent",
-            json!({
-                "type": "keyUp",
-                "key": key,
-                "code": code,
-                "windowsVirtualKeyCode": windows_vk,
-                "nativeVirtualKeyCode": key_code,
-            }),
-        ),
-    ]
+    (
+        "Input.dispatchKeyEvent",
+        json!({
+            "type": kind,
+            "key": key,
+            "code": code,
+            "windowsVirtualKeyCode": windows_vk,
+            "nativeVirtualKeyCode": key_code,
+            "autoRepeat": auto_repeat,
+        }),
+    )
 }
 
 /// Build a hover sequence (mouseMoved to coordinates).
@@ -100,6 +126,68 @@ pub fn hover(x: f64, y: f64) -> (&'static str, Value) {
     mouse_event("mouseMoved", x, y, "none", 0)
 }
 
+/// Dispatch a mouse wheel event at coordinates. Scrolls whatever scroll container is under the point.
+pub fn mouse_wheel(x: f64, y: f64, delta_x: f64, delta_y: f64) -> (&'static str, Value) {
+    (
+        "Input.dispatchMouseEvent",
+        json!({
+            "type": "mouseWheel",
+            "x": x,
+            "y": y,
+            "deltaX": delta_x,
+            "deltaY": delta_y,
+        }),
+    )
+}
+
+/// Dispatch a touch event. event_type: "touchStart", "touchMove", "touchEnd", or "touchCancel".
+/// touchEnd/touchCancel take an empty point list.
+pub fn touch_event(event_type: &str, points: &[(f64, f64)]) -> (&'static str, Value) {
+    let touch_points: Vec<Value> = points
+        .iter()
+        .enumerate()
+        .map(|(i, (x, y))| json!({ "x": x, "y": y, "id": i }))
+        .collect();
+    ("Input.dispatchTouchEvent", json!({ "type": event_type, "touchPoints": touch_points }))
+}
+
+/// Synthesize a pinch around (x, y). scale > 1 zooms in, < 1 zooms out.
+pub fn synthesize_pinch(x: f64, y: f64, scale: f64) -> (&'static str, Value) {
+    ("Input.synthesizePinchGesture", json!({
+        "x": x,
+        "y": y,
+        "scaleFactor": scale,
+        "gestureSourceType": "touch",
+    }))
+}
+
+/// Synthesize a scroll gesture at (x, y). Distances follow finger direction: positive y_distance
+/// scrolls up. prevent_fling=false lets the gesture end with momentum.
+pub fn synthesize_scroll(x: f64, y: f64, x_distance: f64, y_distance: f64, speed: u32, prevent_fling: bool) -> (&'static str, Value) {
+    ("Input.synthesizeScrollGesture", json!({
+        "x": x,
+        "y": y,
+        "xDistance": x_distance,
+        "yDistance": y_distance,
+        "speed": speed,
+        "preventFling": prevent_fling,
+        "gestureSourceType": "default",
+    }))
+}
+
+/// A single-finger tap: touchStart + touchEnd at one point.
+pub fn tap(x: f64, y: f64) -> Vec<(&'static str, Value)> {
+    vec![
+        touch_event("touchStart", &[(x, y)]),
+        touch_event("touchEnd", &[]),
+    ]
+}
+
+/// Insert text at the caret as if committed by an IME — no key events, one input event.
+pub fn insert_text(text: &str) -> (&'static str, Value) {
+    ("Input.insertText", json!({ "text": text }))
+}
+
 /// Build key events for typing a string (char + keyUp per character).
 pub fn type_text(text: &str) -> Vec<(&'static str, Value)> {
     text.chars()
@@ -127,7 +215,7 @@ pub fn navigate_to_history_entry(entry_id: i64) -> (&'static str, Value) {
 
 /// Scroll the page by pixels.
 pub fn scroll(x: f64, y: f64) -> (&'static str, Value) {
-    evaluate(&format!("window.scrollBy({x}, {y})"))
+    evaluate(&format!("window.scrollBy({x}, {y})", x = json!(x), y = json!(y)))
 }
 
 /// Evaluate an expression, returning the remote object reference (not the value).
@@ -165,6 +253,42 @@ pub fn enable_dom() -> (&'static str, Value) {
     ("DOM.enable", json!({}))
 }
 
+/// Get the frame tree. The root frame's `id` and `loaderId` identify the current main document.
+pub fn get_frame_tree() -> (&'static str, Value) {
+    ("Page.getFrameTree", json!({}))
+}
+
+/// Request the document root. Required before DOM.performSearch so result nodes get pushed to the client.
+pub fn get_document() -> (&'static str, Value) {
+    ("DOM.getDocument", json!({ "depth": 0 }))
+}
+
+/// Search the DOM by plain text, CSS selector, or XPath (CDP detects which).
+pub fn perform_search(query: &str) -> (&'static str, Value) {
+    ("DOM.performSearch", json!({ "query": query, "includeUserAgentShadowDOM": true }))
+}
+
+/// Fetch node IDs for a range of search results.
+pub fn get_search_results(search_id: &str, from: u64, to: u64) -> (&'static str, Value) {
+    ("DOM.getSearchResults", json!({ "searchId": search_id, "fromIndex": from, "toIndex": to }))
+}
+
+/// Release a search session.
+pub fn discard_search_results(search_id: &str) -> (&'static str, Value) {
+    ("DOM.discardSearchResults", json!({ "searchId": search_id }))
+}
+
+/// Describe a single node (name, attributes, value) without its subtree.
+pub fn describe_node(node_id: i64) -> (&'static str, Value) {
+    ("DOM.describeNode", json!({ "nodeId": node_id, "depth": 0 }))
+}
+
+/// Describe a node by Runtime objectId, one level deep and piercing shadow roots —
+/// reports closed shadow roots too, which page JS can't see.
+pub fn describe_object_node(object_id: &str) -> (&'static str, Value) {
+    ("DOM.describeNode", json!({ "objectId": object_id, "depth": 1, "pierce": true }))
+}
+
 pub fn enable_runtime() -> (&'static str, Value) {
     ("Runtime.enable", json!({}))
 }
@@ -215,6 +339,20 @@ pub fn set_viewport(width: u32, height: u32) -> (&'static str, Value) {
     }))
 }
 
+/// Set CSS zoom on the root element. Reflows layout like browser zoom (Ctrl +/-),
+/// unlike Emulation.setPageScaleFactor which is a pinch-style visual magnification.
+pub fn set_css_zoom(factor: f64) -> (&'static str, Value) {
+    evaluate(&format!(
+        "(() => {{ document.documentElement.style.zoom = {factor}; return parseFloat(getComputedStyle(document.documentElement).zoom) || 1; }})()",
+        factor = json!(factor)
+    ))
+}
+
+/// Read the effective CSS zoom of the root element.
+pub fn get_css_zoom() -> (&'static str, Value) {
+    evaluate("parseFloat(getComputedStyle(document.documentElement).zoom) || 1")
+}
+
 /// Enable the Accessibility CDP domain.
 pub fn enable_accessibility() -> (&'static str, Value) {
     ("Accessibility.enable", json!({}))
@@ -225,6 +363,20 @@ pub fn get_full_ax_tree() -> (&'static str, Value) {
     ("Accessibility.getFullAXTree", json!({}))
 }
 
+/// Enable the DOMSnapshot CDP domain.
+pub fn enable_dom_snapshot() -> (&'static str, Value) {
+    ("DOMSnapshot.enable", json!({}))
+}
+
+/// Capture a flattened DOM + layout snapshot, with the listed computed styles per layout node.
+pub fn capture_dom_snapshot(computed_styles: &[String], include_dom_rects: bool) -> (&'static str, Value) {
+    ("DOMSnapshot.captureSnapshot", json!({
+        "computedStyles": computed_styles,
+        "includeDOMRects": include_dom_rects,
+        "includePaintOrder": false,
+    }))
+}
+
 /// Handle a JavaScript dialog (alert/confirm/prompt/beforeunload).
 pub fn handle_dialog(accept: bool, prompt_text: Option<&str>) -> (&'static str, Value) {
     let mut params = json!({ "accept": accept });
@@ -280,6 +432,118 @@ pub fn print_to_pdf() -> (&'static str, Value) {
     }))
 }
 
+/// Grant permissions (e.g. "geolocation", "clipboardReadWrite") without prompting.
+/// origin None applies to all origins.
+pub fn grant_permissions(permissions: &[String], origin: Option<&str>) -> (&'static str, Value) {
+    let mut params = json!({ "permissions": permissions });
+    if let Some(o) = origin {
+        params["origin"] = json!(o);
+    }
+    ("Browser.grantPermissions", params)
+}
+
+/// Reset all permission overrides back to prompting.
+pub fn reset_permissions() -> (&'static str, Value) {
+    ("Browser.resetPermissions", json!({}))
+}
+
+/// Route downloads to a folder (or the browser default) and emit download progress events.
+pub fn set_download_behavior(download_path: Option<&str>) -> (&'static str, Value) {
+    let params = match download_path {
+        Some(path) => json!({ "behavior": "allow", "downloadPath": path, "eventsEnabled": true }),
+        None => json!({ "behavior": "default", "eventsEnabled": true }),
+    };
+    ("Browser.setDownloadBehavior", params)
+}
+
+/// Capture a screenshot. Lossy formats (jpeg, webp) always carry a quality — 80 unless given —
+/// since leaving it to CDP produces oversized images; png never does.
+/// `clip` is (x, y, width, height, scale); beyond_viewport lets the clip reach past the viewport.
+pub fn capture_screenshot(
+    format: &str,
+    quality: Option<u8>,
+    clip: Option<(f64, f64, f64, f64, f64)>,
+    beyond_viewport: bool,
+) -> (&'static str, Value) {
+    let mut params = json!({ "format": format });
+    if format != "png" {
+        params["quality"] = json!(quality.unwrap_or(80));
+    }
+    if let Some((x, y, width, height, scale)) = clip {
+        params["clip"] = json!({ "x": x, "y": y, "width": width, "height": height, "scale": scale });
+    }
+    if beyond_viewport {
+        params["captureBeyondViewport"] = json!(true);
+    }
+    ("Page.captureScreenshot", params)
+}
+
+/// Capture the page as a single-file archive. Only "mhtml" is supported by CDP today.
+pub fn capture_snapshot(format: &str) -> (&'static str, Value) {
+    ("Page.captureSnapshot", json!({ "format": format }))
+}
+
+/// Fetch a response body by network request ID. Only works while the browser still holds it.
+pub fn get_response_body(request_id: &str) -> (&'static str, Value) {
+    ("Network.getResponseBody", json!({ "requestId": request_id }))
+}
+
+/// Enable Fetch interception for the given URL patterns (`*` and `?` wildcards).
+/// Replaces any previously enabled patterns. all_documents also pauses every document
+/// (page and iframe) request; handle_auth also pauses on auth challenges (Fetch.authRequired).
+pub fn fetch_enable(patterns: &[String], all_documents: bool, handle_auth: bool) -> (&'static str, Value) {
+    let mut patterns: Vec<Value> = patterns
+        .iter()
+        .map(|p| json!({ "urlPattern": p, "requestStage": "Request" }))
+        .collect();
+    if all_documents {
+        patterns.push(json!({ "urlPattern": "*", "resourceType": "Document", "requestStage": "Request" }));
+    }
+    ("Fetch.enable", json!({ "patterns": patterns, "handleAuthRequests": handle_auth }))
+}
+
+/// Answer an auth challenge. None defers to the browser's default handling (usually a prompt).
+pub fn fetch_continue_with_auth(request_id: &str, credentials: Option<(&str, &str)>) -> (&'static str, Value) {
+    let response = match credentials {
+        Some((username, password)) => json!({
+            "response": "ProvideCredentials",
+            "username": username,
+            "password": password,
+        }),
+        None => json!({ "response": "Default" }),
+    };
+    ("Fetch.continueWithAuth", json!({ "requestId": request_id, "authChallengeResponse": response }))
+}
+
+/// Disable Fetch interception — paused requests are released.
+pub fn fetch_disable() -> (&'static str, Value) {
+    ("Fetch.disable", json!({}))
+}
+
+/// Answer a paused request with a synthetic response. body_base64 must already be base64-encoded.
+pub fn fetch_fulfill(request_id: &str, status: u16, headers: &[(String, String)], body_base64: &str) -> (&'static str, Value) {
+    let headers: Vec<Value> = headers
+        .iter()
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect();
+    ("Fetch.fulfillRequest", json!({
+        "requestId": request_id,
+        "responseCode": status,
+        "responseHeaders": headers,
+        "body": body_base64,
+    }))
+}
+
+/// Fail a paused request with a network error reason (e.g. "BlockedByClient", "TimedOut").
+pub fn fetch_fail(request_id: &str, reason: &str) -> (&'static str, Value) {
+    ("Fetch.failRequest", json!({ "requestId": request_id, "errorReason": reason }))
+}
+
+/// Let a paused request proceed to the network unchanged.
+pub fn fetch_continue(request_id: &str) -> (&'static str, Value) {
+    ("Fetch.continueRequest", json!({ "requestId": request_id }))
+}
+
 /// Enable the Performance CDP domain.
 pub fn enable_performance() -> (&'static str, Value) {
     ("Performance.enable", json!({}))
@@ -290,11 +554,56 @@ pub fn get_metrics() -> (&'static str, Value) {
     ("Performance.getMetrics", json!({}))
 }
 
+/// Start block-level JS coverage. Profiler must be enabled first.
+pub fn start_js_coverage() -> Vec<(&'static str, Value)> {
+    vec![
+        ("Profiler.enable", json!({})),
+        ("Profiler.startPreciseCoverage", json!({ "callCount": false, "detailed": true })),
+    ]
+}
+
+/// Collect JS coverage gathered since start_js_coverage.
+pub fn take_js_coverage() -> (&'static str, Value) {
+    ("Profiler.takePreciseCoverage", json!({}))
+}
+
+/// Stop JS coverage and release the profiler.
+pub fn stop_js_coverage() -> Vec<(&'static str, Value)> {
+    vec![
+        ("Profiler.stopPreciseCoverage", json!({})),
+        ("Profiler.disable", json!({})),
+    ]
+}
+
+/// Start CSS rule usage tracking. CSS requires DOM to be enabled.
+pub fn start_css_coverage() -> Vec<(&'static str, Value)> {
+    vec![
+        ("DOM.enable", json!({})),
+        ("CSS.enable", json!({})),
+        ("CSS.startRuleUsageTracking", json!({})),
+    ]
+}
+
+/// Stop CSS rule usage tracking and return the rule usage list.
+pub fn stop_css_coverage() -> (&'static str, Value) {
+    ("CSS.stopRuleUsageTracking", json!({}))
+}
+
 /// Clear browser HTTP cache.
 pub fn clear_browser_cache() -> (&'static str, Value) {
     ("Network.clearBrowserCache", json!({}))
 }
 
+/// Toggle offline mode. Throughput -1 and zero latency mean no throttling while online.
+pub fn emulate_offline(offline: bool) -> (&'static str, Value) {
+    ("Network.emulateNetworkConditions", json!({
+        "offline": offline,
+        "latency": 0,
+        "downloadThroughput": -1,
+        "uploadThroughput": -1,
+    }))
+}
+
 /// Clear storage data for an origin.
 pub fn clear_data_for_origin(origin: &str, storage_types: &str) -> (&'static str, Value) {
     ("Storage.clearDataForOrigin", json!({
@@ -310,6 +619,16 @@ pub fn set_user_agent(user_agent: &str) -> (&'static str, Value) {
     }))
 }
 
+/// Block or allow page script execution (inline, external, and event handlers).
+pub fn set_script_execution_disabled(disabled: bool) -> (&'static str, Value) {
+    ("Emulation.setScriptExecutionDisabled", json!({ "value": disabled }))
+}
+
+/// Reload the current page.
+pub fn reload(ignore_cache: bool) -> (&'static str, Value) {
+    ("Page.reload", json!({ "ignoreCache": ignore_cache }))
+}
+
 /// Enable or disable touch event emulation.
 pub fn set_touch_emulation(enabled: bool) -> (&'static str, Value) {
     ("Emulation.setTouchEmulationEnabled", json!({
@@ -332,6 +651,16 @@ pub fn clear_device_override() -> (&'static str, Value) {
     ("Emulation.clearDeviceMetricsOverride", json!({}))
 }
 
+/// Register a script to run before page scripts on every new document. Returns an `identifier`.
+pub fn add_init_script(source: &str) -> (&'static str, Value) {
+    ("Page.addScriptToEvaluateOnNewDocument", json!({ "source": source }))
+}
+
+/// Unregister a script added with add_init_script.
+pub fn remove_init_script(identifier: &str) -> (&'static str, Value) {
+    ("Page.removeScriptToEvaluateOnNewDocument", json!({ "identifier": identifier }))
+}
+
 /// Stealth: inject script before any page JS to hide CDP detection signals.
 /// Runs via Page.addScriptToEvaluateOnNewDocument so it executes before page scripts.
 pub fn add_stealth_script() -> (&'static str, Value) {
@@ -372,3 +701,24 @@ pub fn add_stealth_script() -> (&'static str, Value) {
         "#
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screenshot_quality_set_for_lossy_formats() {
+        for format in ["jpeg", "webp"] {
+            let (_, params) = capture_screenshot(format, Some(55), None, false);
+            assert_eq!(params["quality"], json!(55), "{format}");
+        }
+        let (_, params) = capture_screenshot("jpeg", None, None, false);
+        assert_eq!(params["quality"], json!(80));
+    }
+
+    #[test]
+    fn screenshot_quality_omitted_for_png() {
+        let (_, params) = capture_screenshot("png", Some(55), None, false);
+        assert!(params.get("quality").is_none());
+    }
+}
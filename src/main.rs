@@ -10,11 +10,6 @@ use cdp::LiveConnection;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::fmt()
-        .with_writer(std::io::stderr)
-        .with_env_filter("causeway=debug")
-        .init();
-
     // Config search: local_causeway.toml (personal overrides, gitignored) → causeway.toml (defaults)
     // At each location: cwd → source root (two up from exe in target/debug/) → next to exe
     let search_dirs: Vec<std::path::PathBuf> = [
@@ -32,19 +27,202 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .find(|p| p.exists())
         .unwrap_or_else(|| std::path::PathBuf::from("causeway.toml"));
     let config = config::load_config(config_path.to_str().unwrap_or("causeway.toml"))?;
+    init_tracing(&config.log)?;
+    tracing::info!("Causeway config: {}", config_path.display());
     tracing::info!("Causeway loaded config: {:?}", config.browser);
 
     // Lazy init: start MCP server immediately, browser launches on first tool call
+    match config.server.transport {
+        config::Transport::Stdio => serve_stdio(config.browser).await,
+        config::Transport::Http => serve_http(&config.server, config.browser).await,
+    }
+}
+
+async fn serve_stdio(browser: config::BrowserConfig) -> Result<(), Box<dyn std::error::Error>> {
     let live = Arc::new(LiveConnection::empty());
-    let mcp_server = server::CausewayServer::new(live, config.browser.port, config.browser);
+    let mcp_server = server::CausewayServer::new(live, browser.port, browser);
 
     let service = mcp_server
+        .clone()
         .serve(rmcp::transport::io::stdio())
         .await
         .inspect_err(|e| tracing::error!("Causeway MCP error: {e}"))?;
 
     tracing::info!("Causeway running on stdio (browser will launch on first tool call)");
-    service.waiting().await?;
+    let quit = service.waiting().await;
+    // Incognito contexts outlive the connection — dispose them before exiting
+    mcp_server.shutdown().await;
+    quit?;
 
     Ok(())
 }
+
+/// Streamable HTTP with SSE responses at /mcp. Each client session gets its own
+/// CausewayServer (new_session) — own connection, own tab, own logs — against the one shared
+/// browser. Session tabs and contexts are closed when the session ends or the server shuts down.
+async fn serve_http(server_config: &config::ServerConfig, browser: config::BrowserConfig) -> Result<(), Box<dyn std::error::Error>> {
+    use rmcp::transport::streamable_http_server::{
+        session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
+    };
+
+    // Weak handles on every session's tabs and contexts, to close them at shutdown
+    let sessions: Arc<std::sync::Mutex<Vec<server::SessionHandle>>> = Arc::default();
+    let http_config = StreamableHttpServerConfig::default();
+    let cancel = http_config.cancellation_token.clone();
+    let service = StreamableHttpService::new(
+        {
+            let sessions = sessions.clone();
+            move || {
+                let server = server::CausewayServer::new_session(Arc::new(LiveConnection::empty()), browser.port, browser.clone());
+                if let Some(handle) = server.session_handle() {
+                    let mut sessions = sessions.lock().unwrap_or_else(|e| e.into_inner());
+                    sessions.retain(server::SessionHandle::is_open);
+                    sessions.push(handle);
+                }
+                Ok(server)
+            }
+        },
+        Arc::new(LocalSessionManager::default()),
+        http_config,
+    );
+    let router = axum::Router::new().nest_service("/mcp", service);
+
+    let bind = (server_config.host.as_str(), server_config.port);
+    let listener = tokio::net::TcpListener::bind(bind)
+        .await
+        .inspect_err(|e| tracing::error!("Causeway could not bind {}:{}: {e}", bind.0, bind.1))?;
+    let addr = listener.local_addr()?;
+    let remote = !addr.ip().is_loopback();
+    let auth_token = server_config.auth_token.clone().filter(|t| !t.is_empty());
+    // Anyone who can reach the endpoint drives the browser — going remote takes an opt-in and a token
+    if remote && !(server_config.allow_remote && auth_token.is_some()) {
+        return Err(format!(
+            "Refusing to listen on non-loopback address {addr}: set allow_remote = true and auth_token in [server] first"
+        )
+        .into());
+    }
+    if remote {
+        tracing::warn!("Causeway is listening on non-loopback address {addr} — anyone with the token controls the browser");
+    }
+    let guard = Arc::new(RequestGuard { auth_token, loopback_only: !remote });
+    let router = router.layer(axum::middleware::from_fn_with_state(guard, guard_request));
+
+    tracing::info!("Causeway listening on http://{addr}/mcp (browser will launch on first tool call)");
+    let shutdown = async move {
+        let _ = tokio::signal::ctrl_c().await;
+        // Clean up before ending the sessions: the cleanup their Drop spawns would not get to run
+        let open = std::mem::take(&mut *sessions.lock().unwrap_or_else(|e| e.into_inner()));
+        for session in open {
+            session.close().await;
+        }
+        cancel.cancel();
+    };
+    axum::serve(listener, router)
+        .with_graceful_shutdown(shutdown)
+        .await?;
+
+    Ok(())
+}
+
+/// What every HTTP request must satisfy before it reaches MCP.
+#[derive(Debug)]
+struct RequestGuard {
+    /// Required as `Authorization: Bearer <token>` when set.
+    auth_token: Option<String>,
+    /// On a loopback bind the Host header must name the loopback too — a DNS-rebound
+    /// web page arrives with its own hostname.
+    loopback_only: bool,
+}
+
+async fn guard_request(
+    axum::extract::State(guard): axum::extract::State<Arc<RequestGuard>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::{http::{header, StatusCode}, response::IntoResponse};
+
+    let headers = request.headers();
+    let header_str = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    let host = header_str(header::HOST).unwrap_or("");
+    if guard.loopback_only && !is_loopback_host(host) {
+        return (StatusCode::FORBIDDEN, "Host not allowed").into_response();
+    }
+    // Browsers send Origin on cross-site requests; MCP clients send none, or their own host
+    if let Some(origin) = header_str(header::ORIGIN) {
+        let origin_host = origin.split_once("://").map_or("", |(_, authority)| authority);
+        if !(is_loopback_host(origin_host) || origin_host.eq_ignore_ascii_case(host)) {
+            return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+        }
+    }
+    if let Some(token) = &guard.auth_token {
+        let presented = header_str(header::AUTHORIZATION).and_then(|v| v.strip_prefix("Bearer ")).unwrap_or("");
+        if !constant_time_eq(presented.as_bytes(), token.as_bytes()) {
+            return (StatusCode::UNAUTHORIZED, "Missing or wrong bearer token").into_response();
+        }
+    }
+    next.run(request).await
+}
+
+/// Whether a Host/Origin authority (`name[:port]`) names this machine's loopback interface.
+fn is_loopback_host(authority: &str) -> bool {
+    let name = match authority.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or(""),
+        None => authority.rsplit_once(':').map_or(authority, |(name, _)| name),
+    };
+    name.eq_ignore_ascii_case("localhost") || name.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Token comparison that doesn't stop at the first differing byte.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Filter precedence: CAUSEWAY_LOG → RUST_LOG → [log] level → causeway=debug.
+/// A bare level in config ("info") applies to causeway only; env vars use full filter syntax.
+/// Logs go to stderr unless [log] file is set — never stdout, which carries MCP.
+fn init_tracing(log: &config::LogConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let filter = std::env::var("CAUSEWAY_LOG")
+        .or_else(|_| std::env::var("RUST_LOG"))
+        .ok()
+        .or_else(|| log.level.as_ref().map(|l| {
+            if l.contains('=') { l.clone() } else { format!("causeway={l}") }
+        }))
+        .unwrap_or_else(|| "causeway=debug".to_owned());
+    let filter = tracing_subscriber::EnvFilter::try_new(&filter)?;
+
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match log.file.as_deref().filter(|f| !f.is_empty()) {
+        Some(path) => {
+            let file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+            builder.with_writer(std::sync::Mutex::new(file)).with_ansi(false).init();
+        }
+        None => builder.with_writer(std::io::stderr).init(),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_hosts_accepted() {
+        for host in ["localhost:3100", "LOCALHOST", "127.0.0.1:3100", "127.0.0.2", "[::1]:3100"] {
+            assert!(is_loopback_host(host), "{host}");
+        }
+    }
+
+    #[test]
+    fn other_hosts_rejected() {
+        for host in ["evil.example:3100", "localhost.evil.example", "10.0.0.5:3100", "[::2]:3100", ""] {
+            assert!(!is_loopback_host(host), "{host}");
+        }
+    }
+
+    #[test]
+    fn token_comparison() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+    }
+}
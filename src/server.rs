@@ -17,18 +17,52 @@ use crate::config::BrowserConfig;
 pub struct NavigateParams {
     #[schemars(description = "The URL to navigate to")]
     pub url: String,
+    #[schemars(description = "Referer header to send with the navigation request")]
+    pub referrer: Option<String>,
+    #[schemars(description = "Bypass the HTTP cache for this navigation — every resource is fetched fresh (default: false)")]
+    pub bypass_cache: Option<bool>,
+    #[schemars(description = "Read back the final URL and title after loading (default: true). false skips two round-trips and just confirms the load finished.")]
+    pub return_info: Option<bool>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct EvaluateJsParams {
     #[schemars(description = "JavaScript expression to evaluate in the page context")]
     pub expression: String,
+    #[schemars(description = "Run as if triggered by a user gesture, for APIs like fullscreen, clipboard, or autoplay that otherwise throw NotAllowedError (default: false)")]
+    pub user_gesture: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct PollExpressionParams {
+    #[schemars(description = "JavaScript expression to sample, e.g. \"document.querySelector('.countdown').textContent\". Promises are awaited.")]
+    pub expression: String,
+    #[schemars(description = "Number of samples to take (default: 10, max: 500)")]
+    pub count: Option<u32>,
+    #[schemars(description = "Milliseconds between samples (default: 500, max: 10000)")]
+    pub interval_ms: Option<u64>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct ClickParams {
     #[schemars(description = "CSS selector of the element to click")]
     pub selector: String,
+    #[schemars(description = "Add a randomized 30-120ms pause between mouse move, press, and release. Default: human_like from config (false)")]
+    pub human_like: Option<bool>,
+    #[schemars(description = "Wait up to this many ms for a visible match before clicking, instead of failing immediately (default: 0)")]
+    pub wait_ms: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ClickAtParams {
+    #[schemars(description = "CSS selector of the element to click within (e.g. a canvas, slider track, or map)")]
+    pub selector: String,
+    #[schemars(description = "Horizontal offset from the element's left edge — pixels, or a 0-1 fraction of its width when relative=true")]
+    pub offset_x: f64,
+    #[schemars(description = "Vertical offset from the element's top edge — pixels, or a 0-1 fraction of its height when relative=true")]
+    pub offset_y: f64,
+    #[schemars(description = "Treat offsets as fractions of the element's size (0 = left/top, 1 = right/bottom). Default: false")]
+    pub relative: Option<bool>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -39,6 +73,22 @@ pub struct TypeTextParams {
     pub text: String,
     #[schemars(description = "Clear the field before typing (select all + delete). Default: false")]
     pub clear: Option<bool>,
+    #[schemars(description = "Add a randomized 30-120ms pause between keystrokes. Default: human_like from config (false)")]
+    pub human_like: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct PasteIntoParams {
+    #[schemars(description = "CSS selector of the element to paste into")]
+    pub selector: String,
+    #[schemars(description = "The text to paste")]
+    pub text: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SubmitFormParams {
+    #[schemars(description = "CSS selector of the form, or of an element inside the form")]
+    pub selector: String,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -49,12 +99,174 @@ pub struct WaitForParams {
     pub timeout_ms: Option<u64>,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct WaitForConditionParams {
+    #[schemars(description = "JavaScript expression polled until it evaluates truthy, e.g. \"window.app && window.app.ready === true\" or \"document.querySelectorAll('.row').length >= 10\". Promises are awaited.")]
+    pub expression: String,
+    #[schemars(description = "Maximum time to wait in milliseconds (default: 5000)")]
+    pub timeout_ms: Option<u64>,
+    #[schemars(description = "Polling interval in milliseconds (default: 200)")]
+    pub interval_ms: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct WaitForAnimationParams {
+    #[schemars(description = "CSS selector of the element whose CSS animations/transitions should finish")]
+    pub selector: String,
+    #[schemars(description = "Maximum time to wait in milliseconds (default: 5000)")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct WaitForImagesParams {
+    #[schemars(description = "CSS selector of a subtree to check (default: the whole document)")]
+    pub selector: Option<String>,
+    #[schemars(description = "Maximum time to wait in milliseconds (default: 10000)")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct WatchMutationsParams {
+    #[schemars(description = "How long to record in milliseconds (default: 3000, max: 60000)")]
+    pub duration_ms: Option<u64>,
+    #[schemars(description = "CSS selector of the subtree to observe (default: the whole document)")]
+    pub selector: Option<String>,
+    #[schemars(description = "Maximum mutations to record; extras are only counted (default: 200)")]
+    pub max_records: Option<u32>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct ScrollParams {
     #[schemars(description = "Pixels to scroll horizontally (positive = right)")]
     pub x: Option<f64>,
     #[schemars(description = "Pixels to scroll vertically (positive = down)")]
     pub y: Option<f64>,
+    #[schemars(description = "Override the page's CSS scroll-behavior so the scroll jumps instantly instead of animating (default: false)")]
+    pub instant: Option<bool>,
+    #[schemars(description = "With instant, remove the override again after scrolling (default: true). Set false to leave it in place for follow-up scrolls and screenshots")]
+    pub restore: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetInstantScrollParams {
+    #[schemars(description = "true to force scroll-behavior: auto on the page, false to remove the override (default: true)")]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ScrollElementParams {
+    #[schemars(description = "CSS selector of the element to scroll over (an overflow container, map, or canvas)")]
+    pub selector: String,
+    #[schemars(description = "Horizontal wheel delta in pixels (positive = right)")]
+    pub delta_x: Option<f64>,
+    #[schemars(description = "Vertical wheel delta in pixels (positive = down)")]
+    pub delta_y: Option<f64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CaptureDomSnapshotParams {
+    #[schemars(description = "Computed style properties to include per layout node (e.g. [\"display\", \"color\", \"font-size\"]). Default: none")]
+    pub computed_styles: Option<Vec<String>>,
+    #[schemars(description = "Include offset, client, and scroll rects per layout node (default: true)")]
+    pub include_dom_rects: Option<bool>,
+    #[schemars(description = "Maximum characters of JSON to return (default: 100000)")]
+    pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetTitleParams {
+    #[schemars(description = "New document title")]
+    pub title: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GetValueParams {
+    #[schemars(description = "CSS selector of the input, textarea, or select element")]
+    pub selector: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetValueParams {
+    #[schemars(description = "CSS selector of the input, textarea, or select element")]
+    pub selector: String,
+    #[schemars(description = "Value to assign")]
+    pub value: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetSliderParams {
+    #[schemars(description = "CSS selector of the <input type=range> or role=\"slider\" element")]
+    pub selector: String,
+    #[schemars(description = "Target value, in the slider's own units (between its min and max)")]
+    pub value: f64,
+    #[schemars(description = "Skip setting the value directly and drag the thumb with the mouse (default: false — dragging is used automatically when the direct set doesn't stick or the slider isn't a native range input)")]
+    pub drag: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetCheckedParams {
+    #[schemars(description = "CSS selector of the checkbox or radio (or of the radio group, together with value)")]
+    pub selector: String,
+    #[schemars(description = "Desired state: true = checked, false = unchecked")]
+    pub checked: bool,
+    #[schemars(description = "For radio groups: pick the matching element whose value attribute equals this")]
+    pub value: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct TapParams {
+    #[schemars(description = "CSS selector of the element to tap")]
+    pub selector: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SwipeParams {
+    #[schemars(description = "Start X in viewport pixels (or use from_selector)")]
+    pub start_x: Option<f64>,
+    #[schemars(description = "Start Y in viewport pixels (or use from_selector)")]
+    pub start_y: Option<f64>,
+    #[schemars(description = "End X in viewport pixels (or use to_selector)")]
+    pub end_x: Option<f64>,
+    #[schemars(description = "End Y in viewport pixels (or use to_selector)")]
+    pub end_y: Option<f64>,
+    #[schemars(description = "CSS selector whose center is the swipe start")]
+    pub from_selector: Option<String>,
+    #[schemars(description = "CSS selector whose center is the swipe end")]
+    pub to_selector: Option<String>,
+    #[schemars(description = "Number of intermediate touchMove events (default: 10)")]
+    pub steps: Option<u32>,
+    #[schemars(description = "Total swipe duration in milliseconds (default: 300)")]
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct PinchParams {
+    #[schemars(description = "CSS selector whose center is the pinch center (or use x + y)")]
+    pub selector: Option<String>,
+    #[schemars(description = "Pinch center X in viewport pixels")]
+    pub x: Option<f64>,
+    #[schemars(description = "Pinch center Y in viewport pixels")]
+    pub y: Option<f64>,
+    #[schemars(description = "Scale factor: >1 zooms in (fingers apart), <1 zooms out (fingers together)")]
+    pub scale: f64,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SmoothScrollParams {
+    #[schemars(description = "\"down\", \"up\", \"left\", or \"right\" (default: \"down\")")]
+    pub direction: Option<String>,
+    #[schemars(description = "Distance to scroll in pixels (default: 500)")]
+    pub distance: Option<f64>,
+    #[schemars(description = "Speed in pixels per second (default: 800)")]
+    pub speed: Option<u32>,
+    #[schemars(description = "Let the gesture end with fling momentum (default: false)")]
+    pub momentum: Option<bool>,
+    #[schemars(description = "CSS selector to scroll over (its center is the gesture origin). Default: viewport center")]
+    pub selector: Option<String>,
+    #[schemars(description = "Gesture origin X in viewport pixels (with y, instead of selector)")]
+    pub x: Option<f64>,
+    #[schemars(description = "Gesture origin Y in viewport pixels (with x, instead of selector)")]
+    pub y: Option<f64>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -71,12 +283,34 @@ pub struct SwitchTabParams {
     pub target_id: String,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ScreenshotTabParams {
+    #[schemars(description = "The target ID of the tab to capture (from list_tabs)")]
+    pub target_id: String,
+    #[schemars(description = "Write the image to this file instead of returning it. Format from the extension: .png, .jpg/.jpeg, or .webp")]
+    pub save_path: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ReadTabTextParams {
+    #[schemars(description = "The target ID of the tab to read (from list_tabs)")]
+    pub target_id: String,
+    #[schemars(description = "Maximum characters of text to return (default: 10000)")]
+    pub max_chars: Option<usize>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct NewTabParams {
     #[schemars(description = "URL to open in the new tab (default: about:blank)")]
     pub url: Option<String>,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct NewIncognitoTabParams {
+    #[schemars(description = "URL to open in the incognito tab (default: about:blank)")]
+    pub url: Option<String>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct CloseTabParams {
     #[schemars(description = "The target ID of the tab to close (from list_tabs)")]
@@ -105,6 +339,84 @@ pub struct QueryElementsParams {
     pub selector: String,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct QueryXpathParams {
+    #[schemars(description = "XPath expression, e.g. \"//button[contains(., 'Save')]\" or \"count(//li)\"")]
+    pub xpath: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct QueryShadowParams {
+    #[schemars(description = "CSS selector of the shadow host element (the web component)")]
+    pub host_selector: String,
+    #[schemars(description = "CSS selector to match inside the shadow root")]
+    pub selector: String,
+    #[schemars(description = "Also search nested shadow roots inside the host's shadow tree (default: true)")]
+    pub recursive: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct FindOverlaysParams {
+    #[schemars(description = "Minimum share of the viewport an overlay must cover, in percent (default: 2)")]
+    pub min_area_pct: Option<f64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct DismissConsentParams {
+    #[schemars(description = "Which choice to click: \"accept\" (default) or \"reject\" (reject all / necessary only)")]
+    pub choice: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ExtractContentParams {
+    #[schemars(description = "Maximum characters of text to return (default: 20000)")]
+    pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GetRectParams {
+    #[schemars(description = "CSS selector of the element")]
+    pub selector: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct MapTextParams {
+    #[schemars(description = "Maximum entries to return, most prominent first (default: 100, max: 500)")]
+    pub limit: Option<u32>,
+    #[schemars(description = "Only include text currently in the viewport (default: false)")]
+    pub viewport_only: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GetLinksParams {
+    #[schemars(description = "Maximum number of links to return (default: 200)")]
+    pub limit: Option<u32>,
+    #[schemars(description = "Only return links to other origins (default: false)")]
+    pub external_only: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GetImagesParams {
+    #[schemars(description = "Maximum number of images to return (default: 100)")]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ExtractTableParams {
+    #[schemars(description = "CSS selector of the <table> element")]
+    pub selector: String,
+    #[schemars(description = "Maximum number of body rows to return (default: 500)")]
+    pub max_rows: Option<u32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SearchDomParams {
+    #[schemars(description = "Plain text, CSS selector, or XPath — CDP auto-detects the query type")]
+    pub query: String,
+    #[schemars(description = "Maximum number of matches to describe (default: 50)")]
+    pub limit: Option<u64>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct ClickTextParams {
     #[schemars(description = "The text to search for in element content (case-insensitive substring match)")]
@@ -127,12 +439,30 @@ pub struct HoverParams {
     pub selector: String,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct MoveMouseParams {
+    #[schemars(description = "X coordinate (viewport pixels from left)")]
+    pub x: f64,
+    #[schemars(description = "Y coordinate (viewport pixels from top)")]
+    pub y: f64,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct PressKeyParams {
     #[schemars(description = "Key to press (e.g. \"Enter\", \"Tab\", \"Escape\", \"ArrowDown\", \"Backspace\", \"Space\")")]
     pub key: String,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct HoldKeyParams {
+    #[schemars(description = "Key to hold (e.g. \"ArrowRight\", \"Space\", \"Shift\", or a letter/digit like \"w\" — sent with its physical code, e.g. KeyW)")]
+    pub key: String,
+    #[schemars(description = "How long to hold it in milliseconds (default: 1000, max: 30000)")]
+    pub duration_ms: Option<u64>,
+    #[schemars(description = "Send auto-repeat keyDown events while held, like a real keyboard: after 500ms, then every 50ms (default: false — a single keyDown)")]
+    pub repeat: Option<bool>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct GetAttributeParams {
     #[schemars(description = "CSS selector of the element")]
@@ -147,12 +477,50 @@ pub struct ReadTextParams {
     pub selector: String,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct AssertTextParams {
+    #[schemars(description = "CSS selector of the element whose visible text is checked")]
+    pub selector: String,
+    #[schemars(description = "Expected text, or a JavaScript regular expression source for matches_regex")]
+    pub value: String,
+    #[schemars(description = "Comparison: \"equals\" (trimmed text), \"contains\", or \"matches_regex\" (default: \"equals\")")]
+    pub mode: Option<String>,
+    #[schemars(description = "Return an error instead of { pass: false } when the text doesn't match (default: false)")]
+    pub fail_on_mismatch: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct AssertCountParams {
+    #[schemars(description = "CSS selector whose matches are counted")]
+    pub selector: String,
+    #[schemars(description = "Expected number of matches")]
+    pub expected: u64,
+    #[schemars(description = "Comparison of actual against expected: \"eq\", \"gte\", or \"lte\" (default: \"eq\")")]
+    pub op: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GetValidationParams {
+    #[schemars(description = "CSS selector of a form (checks all its fields) or of a single field")]
+    pub selector: String,
+    #[schemars(description = "Use reportValidity() so the browser also shows its validation bubble, as a submit attempt would (default: false — checkValidity(), no UI)")]
+    pub report: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GetTabOrderParams {
+    #[schemars(description = "CSS selector of a container to limit the listing to (default: the whole document)")]
+    pub selector: Option<String>,
+    #[schemars(description = "Maximum number of elements to return (default: 100)")]
+    pub limit: Option<u32>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct FillFormParams {
     #[schemars(description = "CSS selector of the form or container element")]
     pub selector: String,
-    #[schemars(description = "JSON object mapping field names/selectors to values, e.g. {\"#email\": \"test@example.com\", \"#name\": \"John\"}")]
-    pub fields: std::collections::HashMap<String, String>,
+    #[schemars(description = "JSON object mapping field selectors to values, e.g. {\"#email\": \"test@example.com\", \"[name=\\\"plan\\\"]\": \"pro\", \"#terms\": true}. Strings are typed into text fields and pick the matching option of a select or radio group; true/false sets a checkbox or radio; an array lists the checked boxes of a checkbox group or the chosen options of a multi-select. read_form's values_only output can be passed back as-is")]
+    pub fields: std::collections::HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -207,6 +575,44 @@ pub struct HandleDialogParams {
     pub prompt_text: Option<String>,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct AddInitScriptParams {
+    #[schemars(description = "JavaScript source to run before page scripts on every new document")]
+    pub source: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct FreezeTimeParams {
+    #[schemars(description = "Time to freeze at: ISO 8601 date-time (e.g. \"2030-01-01T09:00:00Z\") or Unix epoch milliseconds. Default: the current time")]
+    pub time: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct AdvanceTimeParams {
+    #[schemars(description = "Milliseconds to move the frozen clock forward. Timers that come due in that span fire in order")]
+    pub ms: u64,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct InjectCssParams {
+    #[schemars(description = "CSS to apply, e.g. \"[data-sensitive], .email { filter: blur(8px) !important; }\"")]
+    pub css: String,
+    #[schemars(description = "Keep applying it on every new document and after reconnects and tab switches, until remove_css (default: false — current page only)")]
+    pub persist: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct RemoveCssParams {
+    #[schemars(description = "Stylesheet ID returned by inject_css (e.g. \"css-1\")")]
+    pub id: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct RemoveInitScriptParams {
+    #[schemars(description = "Init script ID returned by add_init_script (e.g. \"init-1\")")]
+    pub id: String,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct KeyboardChordParams {
     #[schemars(description = "Key chord to press, e.g. \"Ctrl+A\", \"Ctrl+Shift+T\", \"Alt+F4\". Modifier names: Ctrl, Alt, Shift, Meta.")]
@@ -245,6 +651,20 @@ pub struct SetViewportParams {
     pub height: u32,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetJavascriptEnabledParams {
+    #[schemars(description = "true to allow page scripts, false to block them")]
+    pub enabled: bool,
+    #[schemars(description = "Reload the page so the change applies immediately (default: false)")]
+    pub reload: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetZoomParams {
+    #[schemars(description = "Zoom factor, e.g. 1.0 = 100%, 1.5 = 150%, 0.5 = 50%. Range: 0.1 to 10")]
+    pub factor: f64,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct GetConsoleMessagesParams {
     #[schemars(description = "Filter by level: \"log\", \"info\", \"warn\", \"error\", \"debug\". Omit for all.")]
@@ -253,6 +673,64 @@ pub struct GetConsoleMessagesParams {
     pub clear: Option<bool>,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct MockResponseParams {
+    #[schemars(description = "URL pattern to mock, with * and ? wildcards (e.g. \"*/api/users*\")")]
+    pub url_pattern: String,
+    #[schemars(description = "HTTP status code (default: 200)")]
+    pub status: Option<u16>,
+    #[schemars(description = "Response headers as a name → value map")]
+    pub headers: Option<std::collections::HashMap<String, String>>,
+    #[schemars(description = "Response body text (default: empty)")]
+    pub body: Option<String>,
+    #[schemars(description = "Content-Type when not given in headers (default: \"application/json\")")]
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct UnmockParams {
+    #[schemars(description = "Pattern passed to mock_response. Omit to remove all mocks.")]
+    pub url_pattern: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct FailRequestsParams {
+    #[schemars(description = "URL patterns to fail, with * and ? wildcards (e.g. [\"*google-analytics.com*\"])")]
+    pub url_patterns: Vec<String>,
+    #[schemars(description = "Network error reason (default: \"BlockedByClient\"). Others: \"Failed\", \"TimedOut\", \"ConnectionRefused\", \"NameNotResolved\", \"InternetDisconnected\", etc.")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct UnfailRequestsParams {
+    #[schemars(description = "Patterns passed to fail_requests. Omit to remove all failure rules.")]
+    pub url_patterns: Option<Vec<String>>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GetResponseBodyParams {
+    #[schemars(description = "Request ID from list_network_requests (the value in parentheses)")]
+    pub request_id: String,
+    #[schemars(description = "Maximum characters of body to return (default: 20000)")]
+    pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct WaitForResponseParams {
+    #[schemars(description = "URL substring (e.g. \"/api/save\"), or a glob with * and ? wildcards matched against the full URL")]
+    pub url_pattern: String,
+    #[schemars(description = "Only match this HTTP method, e.g. \"POST\"")]
+    pub method: Option<String>,
+    #[schemars(description = "Only match this HTTP status, e.g. 200")]
+    pub status: Option<u16>,
+    #[schemars(description = "Also return the response body (default: false)")]
+    pub include_body: Option<bool>,
+    #[schemars(description = "Maximum characters of body to return (default: 20000)")]
+    pub max_chars: Option<usize>,
+    #[schemars(description = "Maximum time to wait in milliseconds (default: 10000)")]
+    pub timeout_ms: Option<u64>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct ListNetworkRequestsParams {
     #[schemars(description = "Filter by URL substring (case-insensitive). Omit for all.")]
@@ -273,6 +751,48 @@ pub struct DownloadFileParams {
 pub struct ElementScreenshotParams {
     #[schemars(description = "CSS selector of the element to screenshot")]
     pub selector: String,
+    #[schemars(description = "Wait for web fonts to finish loading (document.fonts.ready) before capturing, so text doesn't render in fallback fonts (default: false)")]
+    pub wait_for_fonts: Option<bool>,
+    #[schemars(description = "Absolute local path to write the image to (.png, .jpg/.jpeg, or .webp — the extension picks the format). Returns the path instead of base64 image content.")]
+    pub save_path: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ScreenshotParams {
+    #[schemars(description = "Wait for web fonts to finish loading (document.fonts.ready) before capturing, so text doesn't render in fallback fonts (default: false)")]
+    pub wait_for_fonts: Option<bool>,
+    #[schemars(description = "Absolute local path to write the image to (.png, .jpg/.jpeg, or .webp — the extension picks the format). Returns the path instead of base64 image content, at full resolution.")]
+    pub save_path: Option<String>,
+    #[schemars(description = "Capture at devicePixelRatio 1 (temporary Emulation.setDeviceMetricsOverride, restored afterwards) so image pixels map 1:1 to the CSS pixels click(x, y) uses. On HiDPI screens a normal screenshot is DPR× larger, so image coordinates must be divided by the DPR. The image is never downscaled to fit 2000px, so very large viewports return bigger payloads. Default: false")]
+    pub css_pixels: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct FilmstripParams {
+    #[schemars(description = "Number of frames to capture (default: 5, max: 20)")]
+    pub count: Option<u32>,
+    #[schemars(description = "Milliseconds between frame starts (default: 250, max: 10000)")]
+    pub interval_ms: Option<u64>,
+    #[schemars(description = "Image format: \"webp\" (default), \"jpeg\", or \"png\"")]
+    pub format: Option<String>,
+    #[schemars(description = "Absolute local directory to write frames to (frame-00.webp, frame-01.webp, ...) at full resolution instead of returning them inline")]
+    pub save_dir: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ScreenshotTextParams {
+    #[schemars(description = "Text to find (case-insensitive substring match, same as click_text)")]
+    pub text: String,
+    #[schemars(description = "HTML tag to limit search to (e.g. \"button\", \"label\"). Default: \"*\" (all elements)")]
+    pub tag: Option<String>,
+    #[schemars(description = "Pixels of surrounding context to include on each side (default: 16)")]
+    pub padding: Option<f64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct WaitForDownloadParams {
+    #[schemars(description = "Maximum time to wait in milliseconds (default: 30000)")]
+    pub timeout_ms: Option<u64>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -281,10 +801,36 @@ pub struct SavePdfParams {
     pub save_path: String,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CaptureMhtmlParams {
+    #[schemars(description = "Absolute local path to save the .mhtml file. Omit to return the MHTML text inline (truncated at max_chars).")]
+    pub save_path: Option<String>,
+    #[schemars(description = "Maximum characters to return inline when save_path is omitted (default: 50000)")]
+    pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct DiffTextParams {
+    #[schemars(description = "Replace the snapshot with the current text after diffing (default: true)")]
+    pub update_snapshot: Option<bool>,
+    #[schemars(description = "Maximum changed lines to list (default: 200)")]
+    pub max_lines: Option<usize>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct ReadFormParams {
     #[schemars(description = "CSS selector for the form or container (default: entire page)")]
     pub selector: Option<String>,
+    #[schemars(description = "Return just a JSON object of field selector → current value instead of field descriptions: strings for text fields and selects, true/false for a lone checkbox, arrays for checkbox groups and multi-selects, the chosen value (or null) for radio groups. Fields are keyed by [name=\"…\"], or #id when unnamed, so the object can be passed straight to fill_form to restore the form (default: false)")]
+    pub values_only: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GrantPermissionsParams {
+    #[schemars(description = "Permission names, e.g. [\"geolocation\", \"clipboardReadWrite\"]")]
+    pub permissions: Vec<String>,
+    #[schemars(description = "Origin to grant for (e.g. \"https://example.com\"). Omit for all origins.")]
+    pub origin: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -295,6 +841,12 @@ pub struct ClearStorageParams {
     pub clear_cache: Option<bool>,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct UnregisterServiceWorkersParams {
+    #[schemars(description = "Only unregister registrations whose scope contains this string. Default: all for the current origin")]
+    pub scope: Option<String>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct ExtensionEvalParams {
     #[schemars(description = "Action: 'list' to show running extensions, 'eval' to run JS in an extension's service worker")]
@@ -339,26 +891,413 @@ pub struct EmulateDeviceParams {
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct ChainParams {
-    #[schemars(description = "Array of actions to execute sequentially with natural delays between them. Each action is an object with an \"action\" field and the parameters for that action.\n\nSupported actions and their parameters:\n- click: { selector } — click by CSS selector\n- click_text: { text, tag? } — click by visible text\n- click_link: { text, index? } — click interactive element by text\n- type_text: { selector, text, clear? } — type into a field\n- press_key: { key } — press a key (Enter, Tab, Escape, etc.)\n- keyboard_chord: { chord } — key combo (Ctrl+A, Ctrl+Shift+T, etc.)\n- select_option: { selector, value } — select dropdown option\n- scroll: { x?, y? } — scroll by pixels\n- wait_for: { selector, timeout_ms? } — wait for element to appear\n- wait_for_text: { text, selector?, timeout_ms? } — wait for text to appear\n- navigate: { url } — navigate to URL\n- evaluate_js: { expression } — run JavaScript\n- toggle: { label, checked? } — toggle checkbox/radio by label text\n\nExample: [{\"action\":\"click_text\",\"text\":\"Email\"},{\"action\":\"type_text\",\"selector\":\"#email\",\"text\":\"hi@example.com\"},{\"action\":\"press_key\",\"key\":\"Tab\"},{\"action\":\"type_text\",\"selector\":\"#password\",\"text\":\"secret\"},{\"action\":\"click_text\",\"text\":\"Sign in\"}]")]
+    #[schemars(description = "Array of actions to execute sequentially with natural delays between them. Each action is an object with an \"action\" field and the parameters for that action.\n\nSupported actions and their parameters:\n- click: { selector, human_like?, wait_ms? } — click by CSS selector\n- click_text: { text, tag? } — click by visible text\n- click_link: { text, index? } — click interactive element by text\n- type_text: { selector, text, clear?, human_like? } — type into a field\n- press_key: { key } — press a key (Enter, Tab, Escape, etc.)\n- keyboard_chord: { chord } — key combo (Ctrl+A, Ctrl+Shift+T, etc.)\n- select_option: { selector, value } — select dropdown option\n- scroll: { x?, y? } — scroll by pixels\n- wait_for: { selector, timeout_ms? } — wait for element to appear\n- wait_for_text: { text, selector?, timeout_ms? } — wait for text to appear\n- navigate: { url } — navigate to URL\n- evaluate_js: { expression } — run JavaScript\n- toggle: { label, checked? } — toggle checkbox/radio by label text\n\nExample: [{\"action\":\"click_text\",\"text\":\"Email\"},{\"action\":\"type_text\",\"selector\":\"#email\",\"text\":\"hi@example.com\"},{\"action\":\"press_key\",\"key\":\"Tab\"},{\"action\":\"type_text\",\"selector\":\"#password\",\"text\":\"secret\"},{\"action\":\"click_text\",\"text\":\"Sign in\"}]")]
     pub steps: Vec<serde_json::Value>,
     #[schemars(description = "Base delay between steps in milliseconds. Each step sleeps for this duration ±100ms (randomized). Default: 1000")]
     pub delay_ms: Option<u64>,
 }
 
-// -- Shared JS helpers --
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct BatchCall {
+    #[schemars(description = "Tool name, e.g. \"navigate\", \"wait_for\", \"click\"")]
+    pub name: String,
+    #[schemars(description = "Parameters for the tool, same shape as calling it directly. Omit for tools without parameters.")]
+    pub params: Option<serde_json::Value>,
+}
 
-/// Build JS that finds the first visible, in-viewport element matching a selector.
-/// Returns JS that resolves to `{ x, y }` or `null`.
-fn js_find_visible_element(selector: &str) -> String {
-    format!(
-        r#"(async () => {{
-            const els = document.querySelectorAll({sel});
-            if (!els.length) return null;
-            const vw = window.innerWidth;
-            const vh = window.innerHeight;
-            for (const el of els) {{
-                const r = el.getBoundingClientRect();
-                if (r.width === 0 || r.height === 0) continue;
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct BatchParams {
+    #[schemars(description = "Ordered list of tool calls to execute sequentially, e.g. [{\"name\":\"navigate\",\"params\":{\"url\":\"https://example.com\"}},{\"name\":\"wait_for\",\"params\":{\"selector\":\"#login\"}},{\"name\":\"click\",\"params\":{\"selector\":\"#login\"}}]")]
+    pub calls: Vec<BatchCall>,
+    #[schemars(description = "What to do when a call fails: \"stop\" (default) or \"continue\"")]
+    pub on_error: Option<String>,
+}
+
+// -- Batch dispatch --
+
+/// Deserialize a batch call's params into a tool's parameter struct.
+fn batch_params<T: serde::de::DeserializeOwned>(tool: &str, params: serde_json::Value) -> Result<Parameters<T>, McpError> {
+    serde_json::from_value(params)
+        .map(Parameters)
+        .map_err(|e| McpError::invalid_params(format!("Invalid params for {tool}: {e}"), None))
+}
+
+// -- Shared JS helpers --
+//
+// Tool params reach generated JS only as JSON literals — serde_json::to_string for strings,
+// json! for numbers (non-finite floats become null rather than a bare `inf`). Never splice
+// a raw String into a format! template; a selector like `"]);alert(1)//` must stay a string.
+
+/// JS function declaration `uniqueSelector(el)` → a CSS selector that matches only `el`.
+/// Prefers #id, then stable attributes (data-testid, name, aria-label), then a
+/// tag:nth-of-type path trimmed to the shortest unique suffix. Returns null when even the
+/// full path isn't unique (e.g. inside shadow DOM). Embed inside generated JS.
+const JS_UNIQUE_SELECTOR: &str = r#"function uniqueSelector(el) {
+    const esc = (s) => (window.CSS && CSS.escape) ? CSS.escape(s) : s.replace(/[^a-zA-Z0-9_-]/g, '\\$&');
+    const unique = (sel) => { try { return document.querySelectorAll(sel).length === 1; } catch (e) { return false; } };
+    if (el.id && unique('#' + esc(el.id))) return '#' + esc(el.id);
+    const tag = el.tagName.toLowerCase();
+    for (const attr of ['data-testid', 'data-test', 'data-qa', 'name', 'aria-label']) {
+        const v = el.getAttribute(attr);
+        if (v) {
+            const sel = tag + '[' + attr + '="' + v.replace(/["\\]/g, '\\$&') + '"]';
+            if (unique(sel)) return sel;
+        }
+    }
+    const parts = [];
+    let cur = el;
+    while (cur && cur.nodeType === 1 && cur !== document.documentElement) {
+        let part = cur.tagName.toLowerCase();
+        if (cur.id && unique('#' + esc(cur.id))) {
+            parts.unshift('#' + esc(cur.id));
+            break;
+        }
+        const parent = cur.parentElement;
+        if (parent) {
+            const same = Array.from(parent.children).filter(c => c.tagName === cur.tagName);
+            if (same.length > 1) part += ':nth-of-type(' + (same.indexOf(cur) + 1) + ')';
+        }
+        parts.unshift(part);
+        cur = parent;
+    }
+    for (let i = parts.length - 1; i >= 0; i--) {
+        const sel = parts.slice(i).join(' > ');
+        if (unique(sel)) return sel;
+    }
+    return null;
+}"#;
+
+/// Base64 size cap for clipped screenshots (~3.75MB decoded).
+const CLIP_MAX_BASE64: usize = 5_000_000;
+
+/// Cap on a single command over a side-tab session (screenshot_tab, read_tab_text).
+/// Background tabs can stall rather than fail, so this is well under the 30s command timeout.
+const SIDE_TAB_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Init script for get_lcp: records largest-contentful-paint entries into `window.__causeway_lcp`
+/// from document start, so the value is ready whenever get_lcp asks.
+const JS_LCP_OBSERVER: &str = r#"(() => {
+    if (typeof PerformanceObserver === 'undefined') return;
+    try {
+        new PerformanceObserver(list => {
+            const entries = list.getEntries();
+            if (entries.length) window.__causeway_lcp = entries[entries.length - 1];
+        }).observe({ type: 'largest-contentful-paint', buffered: true });
+    } catch (e) {}
+})()"#;
+
+/// Known consent-platform buttons: (platform, accept selector, reject selector).
+const CONSENT_FRAMEWORKS: &[(&str, &str, &str)] = &[
+    ("OneTrust", "#onetrust-accept-btn-handler", "#onetrust-reject-all-handler"),
+    ("Cookiebot", "#CybotCookiebotDialogBodyLevelButtonLevelOptinAllowAll, #CybotCookiebotDialogBodyButtonAccept", "#CybotCookiebotDialogBodyButtonDecline"),
+    ("Quantcast", ".qc-cmp2-summary-buttons button[mode=\"primary\"]", ".qc-cmp2-summary-buttons button[mode=\"secondary\"]"),
+    ("Didomi", "#didomi-notice-agree-button", "#didomi-notice-disagree-button"),
+    ("TrustArc", "#truste-consent-button", "#truste-consent-required"),
+    ("Osano", ".osano-cm-accept-all", ".osano-cm-denyAll"),
+    ("Complianz", ".cmplz-accept", ".cmplz-deny"),
+];
+
+/// Button texts for consent banners without a known framework, most specific first.
+const CONSENT_ACCEPT_TEXTS: &[&str] = &[
+    "accept all cookies", "accept all", "allow all cookies", "allow all", "accept cookies", "allow cookies",
+    "i agree", "i accept", "agree", "accept", "got it", "ok", "okay",
+];
+const CONSENT_REJECT_TEXTS: &[&str] = &[
+    "reject all cookies", "reject all", "decline all", "refuse all", "only necessary cookies", "only necessary",
+    "necessary only", "necessary cookies only", "use necessary cookies only", "reject", "decline", "refuse",
+];
+
+/// Tools that run caller-supplied JavaScript. Removed from the router under safe_mode.
+const UNSAFE_TOOLS: &[&str] = &["evaluate_js", "extension_eval", "wait_for_condition", "add_init_script", "poll_expression"];
+
+/// Build JS that starts a MutationObserver recording into `window.__causeway_mutations`
+/// (capped at max_records; overflow is counted in `dropped`). Observes the selector's element,
+/// or the whole document when no selector is given or it doesn't exist yet (init-script runs).
+/// Returns `{ rooted }` — whether the selector matched.
+fn js_watch_mutations(selector: Option<&str>, max_records: u32, from_init: bool) -> String {
+    format!(
+        r#"(() => {{
+            const prev = window.__causeway_mutations;
+            if (prev && prev.observer) prev.observer.disconnect();
+            const sel = {sel};
+            const found = sel ? document.querySelector(sel) : null;
+            const root = found || document;
+            const MAX = {max_records};
+            const store = {{ records: [], dropped: 0, fromInit: {from_init} }};
+            const describe = (n) => {{
+                if (!n) return '?';
+                if (n.nodeType === 3) return '#text "' + n.textContent.trim().slice(0, 40) + '"';
+                if (n.nodeType !== 1) return n.nodeName.toLowerCase();
+                let d = n.tagName.toLowerCase();
+                if (n.id) d += '#' + n.id;
+                const cls = typeof n.className === 'string' ? n.className.trim().split(/\s+/).filter(Boolean).slice(0, 2) : [];
+                if (cls.length) d += '.' + cls.join('.');
+                return d;
+            }};
+            const push = (r) => {{ if (store.records.length < MAX) store.records.push(r); else store.dropped++; }};
+            store.observer = new MutationObserver(list => {{
+                for (const m of list) {{
+                    const target = describe(m.target);
+                    if (m.type === 'childList') {{
+                        // Whitespace-only text nodes are layout noise
+                        const keep = n => !(n.nodeType === 3 && !n.textContent.trim());
+                        for (const n of m.addedNodes) if (keep(n)) push({{ kind: 'added', node: describe(n), target }});
+                        for (const n of m.removedNodes) if (keep(n)) push({{ kind: 'removed', node: describe(n), target }});
+                    }} else if (m.type === 'attributes') {{
+                        push({{ kind: 'attribute', target, attr: m.attributeName, old: m.oldValue, new: m.target.getAttribute(m.attributeName) }});
+                    }} else if (m.type === 'characterData') {{
+                        push({{ kind: 'text', target: describe(m.target.parentNode), old: (m.oldValue || '').slice(0, 80), new: m.target.textContent.slice(0, 80) }});
+                    }}
+                }}
+            }});
+            store.observer.observe(root, {{ childList: true, subtree: true, attributes: true, attributeOldValue: true, characterData: true, characterDataOldValue: true }});
+            window.__causeway_mutations = store;
+            return {{ rooted: !sel || !!found }};
+        }})()"#,
+        sel = serde_json::to_string(&selector).unwrap(),
+    )
+}
+
+/// Build JS that adds (or removes) a stylesheet forcing `scroll-behavior: auto`, so
+/// programmatic scrolls land immediately instead of animating. Returns
+/// `{ was_active, page }` — whether the override was already present, and the page's own
+/// scroll-behavior on the root element with the override removed.
+fn js_instant_scroll(enabled: bool) -> String {
+    format!(
+        r#"(() => {{
+            const id = '__causeway_instant_scroll';
+            let style = document.getElementById(id);
+            const was_active = !!style;
+            if (style) style.remove();
+            const page = getComputedStyle(document.documentElement).scrollBehavior;
+            if ({enabled}) {{
+                style = document.createElement('style');
+                style.id = id;
+                style.textContent = 'html, html * {{ scroll-behavior: auto !important; }}';
+                (document.head || document.documentElement).appendChild(style);
+            }}
+            return {{ was_active, page }};
+        }})()"#
+    )
+}
+
+/// Another tab attached as a flat session on our own connection (Target.attachToTarget with
+/// flatten) for a one-off read. Nothing is enabled on it; detach when done.
+struct SideTab {
+    conn: Arc<cdp::CdpConnection>,
+    session_id: String,
+    target_id: String,
+}
+
+impl SideTab {
+    async fn execute(&self, command: (&str, serde_json::Value)) -> Result<serde_json::Value, cdp::CdpError> {
+        let (method, params) = command;
+        cdp::send_to_session(&self.conn, &self.session_id, method, params).await
+    }
+
+    async fn eval_value(&self, expression: &str) -> Result<serde_json::Value, cdp::CdpError> {
+        let result = self.execute(commands::evaluate(expression)).await?;
+        cdp::check_exception(result).map(cdp::take_value)
+    }
+
+    async fn detach(self) {
+        let params = serde_json::json!({ "sessionId": self.session_id });
+        if let Err(e) = cdp::send(&self.conn, "Target.detachFromTarget", params).await {
+            tracing::debug!("Detaching from tab {} failed: {e}", self.target_id);
+        }
+    }
+}
+
+/// Capture a screenshot of an attached side tab, returning the base64 data.
+async fn capture_side_tab(tab: &SideTab, format: &str, quality: u8) -> Result<String, McpError> {
+    let target_id = &tab.target_id;
+    let result = tokio::time::timeout(
+        SIDE_TAB_TIMEOUT,
+        tab.execute(commands::capture_screenshot(format, Some(quality), None, false)),
+    )
+    .await
+    .map_err(|_| McpError::internal_error(
+        format!("Screenshot of tab {target_id} timed out — the browser may not be painting it while it's in the background"),
+        None,
+    ))?
+    .map_err(|e| McpError::internal_error(format!("Screenshot of tab {target_id} failed: {e}"), None))?;
+
+    result
+        .get("data")
+        .and_then(|d| d.as_str())
+        .map(|d| d.to_owned())
+        .ok_or_else(|| McpError::internal_error("No screenshot data returned".to_owned(), None))
+}
+
+/// screenshot_tab's capture: to `save` (path, format) at q90, or inline as webp, stepping
+/// quality down until it fits.
+async fn screenshot_side_tab(tab: &SideTab, save: Option<(String, &str)>) -> Result<CallToolResult, McpError> {
+    let target_id = &tab.target_id;
+    if let Some((save_path, format)) = save {
+        let data = capture_side_tab(tab, format, 90).await?;
+        let size = write_base64_capture(&save_path, &data)?;
+        return Ok(CallToolResult::success(vec![Content::text(format!(
+            "Saved screenshot of tab {target_id} ({size} bytes, {format}) to: {save_path}"
+        ))]));
+    }
+
+    let mut final_data = String::new();
+    let mut used_quality = 80u8;
+    for q in [80u8, 50, 30, 15] {
+        final_data = capture_side_tab(tab, "webp", q).await?;
+        used_quality = q;
+        if final_data.len() <= CLIP_MAX_BASE64 { break; }
+    }
+
+    let size_kb = final_data.len() / 1024;
+    if final_data.len() > CLIP_MAX_BASE64 {
+        return Ok(CallToolResult::success(vec![Content::text(format!(
+            "Screenshot of tab {target_id} too large even at lowest quality ({size_kb}KB base64). Use save_path instead."
+        ))]));
+    }
+
+    Ok(CallToolResult::success(vec![
+        Content::image(final_data, "image/webp"),
+        Content::text(format!("Tab {target_id}: {size_kb}KB (q{used_quality})")),
+    ]))
+}
+
+/// Build JS that installs a frozen clock at `now_ms`: `Date`, `Date.now`, and `performance.now`
+/// stop advancing, while setTimeout/setInterval keep firing on real time and are also tracked
+/// against the fake clock so `__causeway_clock.advance(ms)` fires whatever comes due. Safe to
+/// run as an init script; on a page that already has the clock it just moves it to `now_ms`.
+fn js_fake_clock(now_ms: f64) -> String {
+    format!(
+        r#"(() => {{
+            if (window.__causeway_clock) {{ window.__causeway_clock.set({now_ms}); return true; }}
+            const NativeDate = Date;
+            const native = {{
+                setTimeout: window.setTimeout, clearTimeout: window.clearTimeout,
+                setInterval: window.setInterval, clearInterval: window.clearInterval,
+                perfNow: performance.now,
+            }};
+            const perfBase = native.perfNow.call(performance);
+            const epochBase = {now_ms};
+            let now = epochBase;
+            const timers = new Map();
+            const run = (fn, args) => {{
+                try {{ typeof fn === 'function' ? fn(...args) : (0, eval)(String(fn)); }}
+                catch (e) {{ native.setTimeout.call(window, () => {{ throw e; }}, 0); }}
+            }};
+
+            function FakeDate(...args) {{
+                if (!new.target) return new NativeDate(now).toString();
+                return Reflect.construct(NativeDate, args.length ? args : [now], new.target);
+            }}
+            FakeDate.prototype = NativeDate.prototype;
+            FakeDate.now = () => now;
+            FakeDate.parse = NativeDate.parse;
+            FakeDate.UTC = NativeDate.UTC;
+
+            const install = () => {{
+                window.Date = FakeDate;
+                Object.defineProperty(performance, 'now', {{
+                    value: () => perfBase + (now - epochBase), configurable: true, writable: true,
+                }});
+                window.setTimeout = function (fn, delay, ...args) {{
+                    const ms = Math.max(0, Number(delay) || 0);
+                    const id = native.setTimeout.call(window, () => {{ timers.delete(id); run(fn, args); }}, ms);
+                    timers.set(id, {{ due: now + ms, fn, args, interval: 0 }});
+                    return id;
+                }};
+                window.setInterval = function (fn, delay, ...args) {{
+                    const ms = Math.max(0, Number(delay) || 0);
+                    const id = native.setInterval.call(window, () => {{
+                        const t = timers.get(id);
+                        if (t) t.due = now + ms;
+                        run(fn, args);
+                    }}, ms);
+                    timers.set(id, {{ due: now + ms, fn, args, interval: Math.max(ms, 1) }});
+                    return id;
+                }};
+                window.clearTimeout = window.clearInterval = function (id) {{
+                    timers.delete(id);
+                    native.clearTimeout.call(window, id);
+                    native.clearInterval.call(window, id);
+                }};
+            }};
+            install();
+
+            window.__causeway_clock = {{
+                get now() {{ return now; }},
+                set(ms) {{ now = ms; }},
+                // Step forward, firing due timers in order with the clock set to each one's due time
+                advance(ms) {{
+                    const target = now + ms;
+                    let fired = 0;
+                    while (fired < 10000) {{
+                        let nextId = null, next = null;
+                        for (const [id, t] of timers) {{
+                            if (t.due <= target && (!next || t.due < next.due)) {{ next = t; nextId = id; }}
+                        }}
+                        if (!next) break;
+                        now = Math.max(now, next.due);
+                        if (next.interval) next.due += next.interval;
+                        else {{ timers.delete(nextId); native.clearTimeout.call(window, nextId); }}
+                        fired++;
+                        run(next.fn, next.args);
+                    }}
+                    now = target;
+                    return {{ now, fired, pending: timers.size }};
+                }},
+                release() {{
+                    window.Date = NativeDate;
+                    delete performance.now;
+                    Object.assign(window, {{
+                        setTimeout: native.setTimeout, clearTimeout: native.clearTimeout,
+                        setInterval: native.setInterval, clearInterval: native.clearInterval,
+                    }});
+                    delete window.__causeway_clock;
+                }},
+            }};
+            return true;
+        }})()"#,
+        now_ms = serde_json::json!(now_ms),
+    )
+}
+
+/// Build JS that adds (or replaces) a `<style>` with this id. Safe to run as an init script:
+/// before the document element exists it waits for it, so the style lands before first paint.
+fn js_inject_css(id: &str, css: &str) -> String {
+    format!(
+        r#"(() => {{
+            const apply = () => {{
+                let style = document.getElementById({id});
+                if (!style) {{
+                    style = document.createElement('style');
+                    style.id = {id};
+                    (document.head || document.documentElement).appendChild(style);
+                }}
+                style.textContent = {css};
+            }};
+            if (document.documentElement) apply();
+            else new MutationObserver((_, observer) => {{
+                if (document.documentElement) {{ observer.disconnect(); apply(); }}
+            }}).observe(document, {{ childList: true }});
+            return true;
+        }})()"#,
+        id = serde_json::to_string(&format!("causeway-{id}")).unwrap(),
+        css = serde_json::to_string(css).unwrap(),
+    )
+}
+
+/// Build JS that finds the first visible, in-viewport element matching a selector.
+/// Returns JS that resolves to `{ x, y }` or `null`.
+fn js_find_visible_element(selector: &str) -> String {
+    format!(
+        r#"(async () => {{
+            const els = document.querySelectorAll({sel});
+            if (!els.length) return null;
+            const vw = window.innerWidth;
+            const vh = window.innerHeight;
+            for (const el of els) {{
+                const r = el.getBoundingClientRect();
+                if (r.width === 0 || r.height === 0) continue;
                 el.scrollIntoView({{ block: 'center', behavior: 'instant' }});
                 await new Promise(resolve => requestAnimationFrame(() => requestAnimationFrame(resolve)));
                 const rect = el.getBoundingClientRect();
@@ -377,6 +1316,58 @@ fn js_find_visible_element(selector: &str) -> String {
     )
 }
 
+/// Build JS that finds the smallest visible element whose text (or value / aria-label) contains
+/// `text`, case-insensitive, scrolled into view. Resolves to
+/// `{ x, y, matched, box: { x, y, width, height } }` (box in page coordinates) or `null`.
+fn js_find_text_element(text: &str, tag: &str) -> String {
+    js_find_text_element_opts(text, tag, false)
+}
+
+/// js_find_text_element, optionally adding `selector` (from JS_UNIQUE_SELECTOR, may be null).
+fn js_find_text_element_opts(text: &str, tag: &str, with_selector: bool) -> String {
+    format!(
+        r#"(async () => {{
+            {unique_selector}
+            const searchText = {text}.toLowerCase();
+            const els = document.querySelectorAll({tag});
+            const vw = window.innerWidth;
+            const vh = window.innerHeight;
+            const candidates = [];
+            for (const el of els) {{
+                const elText = (el.textContent || '').trim().toLowerCase();
+                const elValue = (el.value || el.getAttribute('aria-label') || '').trim().toLowerCase();
+                if (!elText.includes(searchText) && !elValue.includes(searchText)) continue;
+                const r = el.getBoundingClientRect();
+                if (r.width === 0 || r.height === 0) continue;
+                const matchLen = elText.includes(searchText) ? elText.length : elValue.length;
+                candidates.push({{ el, len: matchLen }});
+            }}
+            candidates.sort((a, b) => a.len - b.len);
+            for (const {{ el }} of candidates) {{
+                el.scrollIntoView({{ block: 'center', behavior: 'instant' }});
+                await new Promise(resolve => requestAnimationFrame(() => requestAnimationFrame(resolve)));
+                const rect = el.getBoundingClientRect();
+                const cx = rect.x + rect.width / 2;
+                const cy = rect.y + rect.height / 2;
+                if (cx >= 0 && cy >= 0 && cx <= vw && cy <= vh) {{
+                    return {{
+                        x: cx,
+                        y: cy,
+                        matched: (el.textContent || el.value || el.getAttribute('aria-label') || '').trim().substring(0, 80),
+                        box: {{ x: rect.x + window.scrollX, y: rect.y + window.scrollY, width: rect.width, height: rect.height }},
+                        selector: {selector_expr},
+                    }};
+                }}
+            }}
+            return null;
+        }})()"#,
+        unique_selector = if with_selector { JS_UNIQUE_SELECTOR } else { "" },
+        selector_expr = if with_selector { "uniqueSelector(el)" } else { "undefined" },
+        text = serde_json::to_string(text).unwrap(),
+        tag = serde_json::to_string(tag).unwrap()
+    )
+}
+
 /// Build JS that finds the first visible element matching selector and focuses it.
 /// Optionally selects all text (for clearing). Returns JS that resolves to `true` or `false`.
 fn js_focus_visible_element(selector: &str, should_clear: bool) -> String {
@@ -419,11 +1410,149 @@ pub struct NetworkEntry {
     pub timestamp: f64,
 }
 
+/// What to do with a request matched by an intercept rule.
+#[derive(Debug, Clone)]
+pub enum InterceptAction {
+    Mock { status: u16, headers: Vec<(String, String)>, body: String },
+    Fail { reason: String },
+}
+
+/// A Fetch interception rule. First matching rule wins.
+#[derive(Debug, Clone)]
+pub struct InterceptRule {
+    pub pattern: String,
+    pub action: InterceptAction,
+}
+
+/// A user-registered init script. `id` is stable for the session; `cdp_identifier`
+/// is per-connection and gets refreshed when the script is re-registered after reconnect.
+#[derive(Debug, Clone)]
+pub struct InitScript {
+    pub id: String,
+    pub source: String,
+    pub cdp_identifier: String,
+}
+
+/// An Emulation.setDeviceMetricsOverride applied by emulate_device or set_viewport.
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceMetrics {
+    pub width: u32,
+    pub height: u32,
+    pub scale: f64,
+    pub mobile: bool,
+}
+
+/// A grant_permissions call. `origin` is None when the grant applies to all origins.
+#[derive(Debug, Clone)]
+pub struct PermissionGrant {
+    pub origin: Option<String>,
+    pub permissions: Vec<String>,
+}
+
+/// Tabs and incognito contexts belonging to one client session of a shared browser (http transport).
+/// When the session's last CausewayServer clone is dropped they are closed, so disconnected
+/// clients leave nothing behind.
+#[derive(Debug)]
+pub struct SessionResources {
+    port: u16,
+    tabs: std::sync::Mutex<Vec<String>>,
+    contexts: Arc<tokio::sync::Mutex<Vec<String>>>,
+}
+
+impl SessionResources {
+    /// Close the session's tabs and dispose its contexts now, rather than on a task spawned from Drop.
+    async fn close_all(&self) {
+        let tabs = std::mem::take(&mut *self.tabs.lock().unwrap_or_else(|e| e.into_inner()));
+        let contexts = std::mem::take(&mut *self.contexts.lock().await);
+        close_session_resources(self.port, tabs, contexts).await;
+    }
+}
+
+impl Drop for SessionResources {
+    fn drop(&mut self) {
+        let tabs = std::mem::take(self.tabs.get_mut().unwrap_or_else(|e| e.into_inner()));
+        let contexts = self.contexts.try_lock().map(|mut c| std::mem::take(&mut *c)).unwrap_or_default();
+        if tabs.is_empty() && contexts.is_empty() {
+            return;
+        }
+        let Ok(handle) = tokio::runtime::Handle::try_current() else { return };
+        handle.spawn(close_session_resources(self.port, tabs, contexts));
+    }
+}
+
+async fn close_session_resources(port: u16, tabs: Vec<String>, contexts: Vec<String>) {
+    for target_id in tabs {
+        match crate::browser::close_target(port, &target_id).await {
+            Ok(()) => tracing::info!("Closed session tab {target_id}"),
+            Err(e) => tracing::debug!("Session tab {target_id} not closed: {e}"),
+        }
+    }
+    dispose_browser_contexts(port, &contexts).await;
+}
+
+/// A weak reference to one session's resources. The http transport keeps these so it can close
+/// sessions still open at shutdown — Drop's spawned cleanup never runs once the process exits.
+#[derive(Debug, Clone)]
+pub struct SessionHandle(std::sync::Weak<SessionResources>);
+
+impl SessionHandle {
+    /// False once the session has ended (its resources were cleaned up on drop).
+    pub fn is_open(&self) -> bool {
+        self.0.strong_count() > 0
+    }
+
+    pub async fn close(&self) {
+        if let Some(resources) = self.0.upgrade() {
+            resources.close_all().await;
+        }
+    }
+}
+
+/// A stylesheet added by inject_css. Persistent ones carry the per-connection
+/// init-script identifier that re-adds them on each new document.
+#[derive(Debug, Clone)]
+pub struct InjectedCss {
+    pub id: String,
+    pub css: String,
+    pub cdp_identifier: Option<String>,
+}
+
+/// The clock pinned by freeze_time. `cdp_identifier` is the per-connection init script
+/// that installs the clock at `now_ms` on each new document.
+#[derive(Debug, Clone)]
+pub struct FrozenClock {
+    pub now_ms: f64,
+    pub cdp_identifier: Option<String>,
+}
+
+/// The response that delivered a frame's document, from Network.responseReceived (type Document).
+#[derive(Debug, Clone)]
+pub struct DocumentResponse {
+    pub loader_id: String,
+    pub url: String,
+    pub status: u16,
+    pub status_text: String,
+    pub mime_type: String,
+    pub headers: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadEntry {
+    pub guid: String,
+    pub url: String,
+    pub suggested_filename: String,
+    /// "inProgress", "completed", or "canceled"
+    pub state: String,
+    pub received_bytes: u64,
+    pub file_path: Option<String>,
+    /// Set once wait_for_download has returned this entry, so the next wait picks a fresh one.
+    pub reported: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct PendingDialog {
     pub dialog_type: String,
     pub message: String,
-    #[allow(dead_code)] // stored for prompt dialog forwarding
     pub default_prompt: String,
 }
 
@@ -562,6 +1691,306 @@ fn ax_walk(
     }
 }
 
+// -- Bot wall detection --
+
+/// Known CAPTCHA / bot-challenge markers: (label, kind, pattern).
+/// kind: "selector" = CSS match, "text" = case-insensitive body text, "title" = case-insensitive title.
+const BOT_WALL_MARKERS: &[(&str, &str, &str)] = &[
+    ("Cloudflare challenge", "selector", "iframe[src*='challenges.cloudflare.com'], #challenge-form, #cf-challenge-running, .cf-turnstile"),
+    ("Cloudflare challenge", "title", "just a moment"),
+    ("Cloudflare challenge", "text", "checking your browser before accessing"),
+    ("reCAPTCHA", "selector", "iframe[src*='google.com/recaptcha'], iframe[src*='recaptcha.net'], .g-recaptcha"),
+    ("hCaptcha", "selector", "iframe[src*='hcaptcha.com'], .h-captcha"),
+    ("DataDome", "selector", "iframe[src*='captcha-delivery.com']"),
+    ("PerimeterX", "selector", "#px-captcha"),
+    ("Human verification text", "text", "verify you are human"),
+    ("Human verification text", "text", "are you a robot"),
+    ("Human verification text", "text", "unusual traffic from your computer"),
+    ("Access denied", "title", "access denied"),
+];
+
+// -- URL pattern matching --
+
+/// Glob match with `*` (any run) and `?` (one char) — same syntax as Fetch.enable urlPattern.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            // Backtrack: let the last * swallow one more char
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    while pi < p.len() && p[pi] == '*' {
+        pi += 1;
+    }
+    pi == p.len()
+}
+
+/// Host of an absolute URL, lowercased, without userinfo or port. None for host-less URLs (about:, data:).
+fn url_host(url: &str) -> Option<String> {
+    let (_, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host_port = authority.rsplit_once('@').map(|(_, h)| h).unwrap_or(authority);
+    let host = if let Some(v6) = host_port.strip_prefix('[') {
+        v6.split(']').next().unwrap_or("")
+    } else {
+        host_port.split(':').next().unwrap_or("")
+    };
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.is_empty() { None } else { Some(host) }
+}
+
+/// A domain entry matches the host itself and its subdomains ("example.com" covers
+/// "www.example.com"). Entries may also use * and ? wildcards.
+fn domain_matches(entry: &str, host: &str) -> bool {
+    let entry = entry.trim().trim_end_matches('.').to_ascii_lowercase();
+    if entry.contains(['*', '?']) {
+        return wildcard_match(&entry, host);
+    }
+    host == entry || host.strip_suffix(&entry).is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// Check a URL against allowed_domains / blocked_domains. Blocked wins over allowed.
+/// With an allow list, host-less URLs are rejected except about:blank.
+fn check_domain_policy(config: &BrowserConfig, url: &str) -> Result<(), String> {
+    if config.allowed_domains.is_empty() && config.blocked_domains.is_empty() {
+        return Ok(());
+    }
+    let Some(host) = url_host(url) else {
+        if config.allowed_domains.is_empty() || url == "about:blank" {
+            return Ok(());
+        }
+        return Err(format!("{url} has no host, and navigation is restricted to allowed_domains"));
+    };
+    if let Some(entry) = config.blocked_domains.iter().find(|d| domain_matches(d, &host)) {
+        return Err(format!("{host} is blocked (blocked_domains: \"{entry}\")"));
+    }
+    if !config.allowed_domains.is_empty() && !config.allowed_domains.iter().any(|d| domain_matches(d, &host)) {
+        return Err(format!("{host} is not in allowed_domains"));
+    }
+    Ok(())
+}
+
+// -- Text diff --
+
+/// Lines removed from `old` and added in `new`, each in document order. Matching is by
+/// occurrence count, not position, so reordered lines aren't reported — cheap, and enough
+/// to answer "what changed" without an O(n·m) LCS over large pages.
+fn diff_lines<'a>(old: &'a [String], new: &'a [String]) -> (Vec<&'a str>, Vec<&'a str>) {
+    let mut counts: std::collections::HashMap<&str, i64> = std::collections::HashMap::new();
+    for line in old {
+        *counts.entry(line.as_str()).or_default() += 1;
+    }
+    let mut added = Vec::new();
+    for line in new {
+        match counts.get_mut(line.as_str()) {
+            Some(n) if *n > 0 => *n -= 1,
+            _ => added.push(line.as_str()),
+        }
+    }
+    // Whatever old lines weren't consumed are removed — walk old backwards to keep the last occurrences
+    let mut removed: Vec<&str> = Vec::new();
+    for line in old.iter().rev() {
+        if let Some(n) = counts.get_mut(line.as_str()) {
+            if *n > 0 {
+                *n -= 1;
+                removed.push(line.as_str());
+            }
+        }
+    }
+    removed.reverse();
+    (removed, added)
+}
+
+// -- Saving captures --
+
+/// CDP screenshot format for a save_path, picked by its extension.
+fn screenshot_format_for_path(save_path: &str) -> Result<&'static str, McpError> {
+    let ext = std::path::Path::new(save_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("png") => Ok("png"),
+        Some("jpg") | Some("jpeg") => Ok("jpeg"),
+        Some("webp") => Ok("webp"),
+        _ => Err(McpError::invalid_params(
+            format!("save_path must end in .png, .jpg, .jpeg, or .webp: {save_path}"),
+            None,
+        )),
+    }
+}
+
+/// Create save_path's directory and check it's writable, so a bad path fails before the capture.
+/// Relative paths are refused — they would land wherever the server happened to be started.
+fn prepare_save_path(save_path: &str) -> Result<(), McpError> {
+    let path = std::path::Path::new(save_path);
+    if !path.is_absolute() {
+        return Err(McpError::invalid_params(format!("save_path must be an absolute path: {save_path}"), None));
+    }
+    let Some(dir) = path.parent() else {
+        return Err(McpError::invalid_params(format!("save_path must name a file: {save_path}"), None));
+    };
+    std::fs::create_dir_all(dir)
+        .map_err(|e| McpError::internal_error(format!("Failed to create directory: {e}"), None))?;
+
+    // Permission bits don't tell the whole story (ACLs, read-only mounts) — probe with a real file
+    let probe = dir.join(format!(".causeway-write-test-{}", std::process::id()));
+    std::fs::File::create(&probe)
+        .map_err(|e| McpError::invalid_params(format!("Directory is not writable: {} ({e})", dir.display()), None))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Decode a base64 capture from CDP and write it to save_path. Returns the decoded size.
+fn write_base64_capture(save_path: &str, data: &str) -> Result<usize, McpError> {
+    use base64::Engine;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| McpError::internal_error(format!("Failed to decode capture: {e}"), None))?;
+    std::fs::write(save_path, &bytes)
+        .map_err(|e| McpError::internal_error(format!("Failed to write file: {e}"), None))?;
+    Ok(bytes.len())
+}
+
+// -- Coverage --
+
+/// Bytes covered by ranges with a non-zero count. V8 block coverage ranges nest,
+/// and the innermost range wins — an uncalled block inside a called function is unused.
+fn covered_bytes(ranges: &mut [(u64, u64, u64)]) -> u64 {
+    // Outer ranges first when starts tie, so the stack stays properly nested
+    ranges.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+    let mut used = 0u64;
+    let mut pos = 0u64;
+    let mut stack: Vec<(u64, u64)> = Vec::new(); // (end, count)
+    for &(start, end, count) in ranges.iter() {
+        while let Some(&(top_end, top_count)) = stack.last() {
+            if top_end > start {
+                break;
+            }
+            if top_count > 0 {
+                used += top_end.saturating_sub(pos);
+            }
+            pos = pos.max(top_end);
+            stack.pop();
+        }
+        if let Some(&(_, top_count)) = stack.last() {
+            if top_count > 0 {
+                used += start.saturating_sub(pos);
+            }
+        }
+        pos = pos.max(start);
+        stack.push((end, count));
+    }
+    while let Some((end, count)) = stack.pop() {
+        if count > 0 {
+            used += end.saturating_sub(pos);
+        }
+        pos = pos.max(end);
+    }
+    used
+}
+
+fn format_coverage_line(label: &str, used: u64, total: u64) -> String {
+    let unused_pct = ((total - used.min(total)) * 100).checked_div(total).unwrap_or(0);
+    format!("{label}: {used}/{total} bytes used ({unused_pct}% unused)")
+}
+
+// -- DOM node description --
+
+/// One-line summary of a CDP DOM.Node: `<tag id="..." class="...">` for elements, quoted text for text nodes.
+fn describe_dom_node(node: &serde_json::Value) -> String {
+    let node_type = node.get("nodeType").and_then(|v| v.as_u64()).unwrap_or(0);
+    let name = node.get("nodeName").and_then(|v| v.as_str()).unwrap_or("?");
+
+    match node_type {
+        1 => {
+            let tag = node.get("localName").and_then(|v| v.as_str()).unwrap_or(name);
+            let mut out = format!("<{tag}");
+            // attributes is a flat [name, value, name, value, ...] array
+            if let Some(attrs) = node.get("attributes").and_then(|v| v.as_array()) {
+                for pair in attrs.chunks(2) {
+                    let key = pair[0].as_str().unwrap_or("");
+                    if !matches!(key, "id" | "class" | "name" | "href" | "type" | "role" | "aria-label") {
+                        continue;
+                    }
+                    let val = pair.get(1).and_then(|v| v.as_str()).unwrap_or("");
+                    let val: String = val.chars().take(80).collect();
+                    out.push_str(&format!(" {key}=\"{val}\""));
+                }
+            }
+            out.push('>');
+            out
+        }
+        3 => {
+            let text = node.get("nodeValue").and_then(|v| v.as_str()).unwrap_or("").trim();
+            let text: String = text.chars().take(100).collect();
+            format!("#text \"{text}\"")
+        }
+        _ => name.to_owned(),
+    }
+}
+
+/// The CausewayServer state the event collector writes into (shared Arcs, cloned per connection).
+struct CollectorState {
+    browser_config: Arc<BrowserConfig>,
+    console_log: Arc<tokio::sync::Mutex<Vec<ConsoleEntry>>>,
+    network_log: Arc<tokio::sync::Mutex<Vec<NetworkEntry>>>,
+    pending_dialog: Arc<tokio::sync::Mutex<Option<PendingDialog>>>,
+    stylesheets: Arc<tokio::sync::Mutex<std::collections::HashMap<String, String>>>,
+    intercept_rules: Arc<tokio::sync::Mutex<Vec<InterceptRule>>>,
+    downloads: Arc<tokio::sync::Mutex<Vec<DownloadEntry>>>,
+    loading_frames: Arc<tokio::sync::Mutex<std::collections::HashSet<String>>>,
+    document_responses: Arc<tokio::sync::Mutex<std::collections::HashMap<String, DocumentResponse>>>,
+}
+
+/// Dispose browser contexts over the browser endpoint (page sessions may not). Contexts outlive
+/// the connection that created them, so the browser keeps them until told otherwise.
+async fn dispose_browser_contexts(port: u16, contexts: &[String]) {
+    if contexts.is_empty() {
+        return;
+    }
+    let browser = match crate::browser::browser_ws_url(port).await {
+        Ok(ws_url) => cdp::connect(&ws_url).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    let browser = match browser {
+        Ok(browser) => browser,
+        Err(e) => {
+            tracing::warn!("Cannot dispose browser contexts: {e}");
+            return;
+        }
+    };
+    for context in contexts {
+        if let Err(e) = cdp::send(&browser, "Target.disposeBrowserContext", serde_json::json!({ "browserContextId": context })).await {
+            tracing::warn!("Failed to dispose browser context {context}: {e}");
+        }
+    }
+}
+
+/// The text fill_form types for a field value: strings as-is, numbers in their JSON form.
+fn field_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
 // -- MCP Server --
 
 #[derive(Debug, Clone)]
@@ -571,6 +2000,8 @@ pub struct CausewayServer {
     browser_config: Arc<BrowserConfig>,
     /// The target ID of the tab we consider "ours". try_reconnect returns here.
     sticky_target: Arc<tokio::sync::Mutex<Option<String>>>,
+    /// Set for http sessions (new_session): connecting opens a tab of our own instead of attaching to the first one.
+    session_resources: Option<Arc<SessionResources>>,
     console_log: Arc<tokio::sync::Mutex<Vec<ConsoleEntry>>>,
     network_log: Arc<tokio::sync::Mutex<Vec<NetworkEntry>>>,
     pending_dialog: Arc<tokio::sync::Mutex<Option<PendingDialog>>>,
@@ -580,32 +2011,119 @@ pub struct CausewayServer {
     reconnect_guard: Arc<tokio::sync::Mutex<()>>,
     /// First navigate opens a new tab so concurrent sessions don't fight over tabs.
     first_navigate: Arc<std::sync::atomic::AtomicBool>,
+    /// Scripts from add_init_script — re-registered on every new connection.
+    init_scripts: Arc<tokio::sync::Mutex<Vec<InitScript>>>,
+    next_init_script_id: Arc<std::sync::atomic::AtomicU64>,
+    /// Set once get_lcp registers JS_LCP_OBSERVER — re-registered on every new connection.
+    lcp_observer: Arc<std::sync::atomic::AtomicBool>,
+    /// Stylesheets from inject_css — persistent ones are re-registered on every new connection.
+    injected_css: Arc<tokio::sync::Mutex<Vec<InjectedCss>>>,
+    /// Last device metrics override from emulate_device/set_viewport,
+    /// so a css_pixels screenshot can put it back after forcing DPR 1.
+    device_metrics: Arc<tokio::sync::Mutex<Option<DeviceMetrics>>>,
+    /// Set by freeze_time — the clock init script is re-registered on every new connection.
+    frozen_clock: Arc<tokio::sync::Mutex<Option<FrozenClock>>>,
+    next_css_id: Arc<std::sync::atomic::AtomicU64>,
+    /// styleSheetId → source URL, from CSS.styleSheetAdded. Used to label CSS coverage.
+    stylesheets: Arc<tokio::sync::Mutex<std::collections::HashMap<String, String>>>,
+    /// Fetch interception rules (mocks). Fetch.enable patterns are rebuilt from this list.
+    intercept_rules: Arc<tokio::sync::Mutex<Vec<InterceptRule>>>,
+    downloads: Arc<tokio::sync::Mutex<Vec<DownloadEntry>>>,
+    /// Frame IDs between Page.frameStartedLoading and frameStoppedLoading on the current connection.
+    loading_frames: Arc<tokio::sync::Mutex<std::collections::HashSet<String>>>,
+    /// Latest document response per frame ID, for get_status.
+    document_responses: Arc<tokio::sync::Mutex<std::collections::HashMap<String, DocumentResponse>>>,
+    /// Permission grants made this session.
+    granted_permissions: Arc<tokio::sync::Mutex<Vec<PermissionGrant>>>,
+    /// Set by go_offline — re-applied on reconnect since network emulation is per-session.
+    offline: Arc<std::sync::atomic::AtomicBool>,
+    /// Set by set_javascript_enabled(false) — re-applied on reconnect.
+    javascript_disabled: Arc<std::sync::atomic::AtomicBool>,
+    /// snapshot_text results keyed by target ID, so a snapshot survives the navigation it's meant to diff.
+    text_snapshots: Arc<tokio::sync::Mutex<std::collections::HashMap<String, Vec<String>>>>,
+    /// Incognito browser contexts created by new_incognito_tab. Disposed when their last tab closes.
+    browser_contexts: Arc<tokio::sync::Mutex<Vec<String>>>,
     tool_router: ToolRouter<Self>,
 }
 
 #[tool_router]
 impl CausewayServer {
     pub fn new(live: Arc<LiveConnection>, port: u16, browser_config: BrowserConfig) -> Self {
+        // Fixed ws_url means one known target — opening a tab we can't discover would strand us
+        let first_navigate = browser_config.ws_url.is_none();
+        let mut tool_router = Self::tool_router();
+        if browser_config.safe_mode {
+            for name in UNSAFE_TOOLS {
+                tool_router.remove_route(name);
+            }
+        }
         Self {
             live,
             port,
             browser_config: Arc::new(browser_config),
             sticky_target: Arc::new(tokio::sync::Mutex::new(None)),
+            session_resources: None,
             reconnect_guard: Arc::new(tokio::sync::Mutex::new(())),
             console_log: Arc::new(tokio::sync::Mutex::new(Vec::new())),
             network_log: Arc::new(tokio::sync::Mutex::new(Vec::new())),
             pending_dialog: Arc::new(tokio::sync::Mutex::new(None)),
             pre_nav_snapshot: Arc::new(tokio::sync::Mutex::new((String::new(), String::new()))),
-            first_navigate: Arc::new(std::sync::atomic::AtomicBool::new(true)),
-            tool_router: Self::tool_router(),
+            first_navigate: Arc::new(std::sync::atomic::AtomicBool::new(first_navigate)),
+            init_scripts: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            next_init_script_id: Arc::new(std::sync::atomic::AtomicU64::new(1)),
+            lcp_observer: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            injected_css: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            frozen_clock: Arc::new(tokio::sync::Mutex::new(None)),
+            device_metrics: Arc::new(tokio::sync::Mutex::new(None)),
+            next_css_id: Arc::new(std::sync::atomic::AtomicU64::new(1)),
+            stylesheets: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+            intercept_rules: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            downloads: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            loading_frames: Arc::new(tokio::sync::Mutex::new(std::collections::HashSet::new())),
+            document_responses: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+            granted_permissions: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            offline: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            javascript_disabled: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            browser_contexts: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            text_snapshots: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+            tool_router,
+        }
+    }
+
+    /// A server for one client session of a shared browser (http transport). Its first connection
+    /// opens a fresh tab and pins it as the sticky target, so sessions never drive the same tab;
+    /// the tab and any incognito contexts are closed when the session ends.
+    pub fn new_session(live: Arc<LiveConnection>, port: u16, browser_config: BrowserConfig) -> Self {
+        let mut server = Self::new(live, port, browser_config);
+        if server.browser_config.ws_url.is_none() {
+            server.session_resources = Some(Arc::new(SessionResources {
+                port,
+                tabs: std::sync::Mutex::new(Vec::new()),
+                contexts: server.browser_contexts.clone(),
+            }));
+            // The session tab replaces first-navigate's new tab
+            server.first_navigate = Arc::new(std::sync::atomic::AtomicBool::new(false));
         }
+        server
     }
 
-    #[tool(description = "Navigate the browser to a URL. Returns the page title after loading.")]
+    /// A handle for closing this session's tabs and contexts without keeping the session alive.
+    /// None for servers that open no tabs of their own.
+    pub fn session_handle(&self) -> Option<SessionHandle> {
+        self.session_resources.as_ref().map(|r| SessionHandle(Arc::downgrade(r)))
+    }
+
+    #[tool(description = "Navigate the browser to a URL. Returns the page title after loading. Optionally sends a Referer header or bypasses the HTTP cache for this load.")]
     async fn navigate(
         &self,
-        Parameters(NavigateParams { url }): Parameters<NavigateParams>,
+        Parameters(NavigateParams { url, referrer, bypass_cache, return_info }): Parameters<NavigateParams>,
     ) -> Result<CallToolResult, McpError> {
+        check_domain_policy(&self.browser_config, &url).map_err(|msg| McpError::invalid_params(msg, None))?;
+        let bypass_cache = bypass_cache.unwrap_or(false);
+        // Target.createTarget can't carry a referrer or cache setting — open blank, then Page.navigate
+        let direct_open = referrer.is_none() && !bypass_cache;
+        let mut navigated = false;
+
         // First navigate of this session: open a new tab so we don't hijack another session's tab.
         if self.first_navigate.swap(false, std::sync::atomic::Ordering::Relaxed) {
             // Ensure browser is running (lazy init triggers reconnect if needed)
@@ -613,24 +2131,35 @@ impl CausewayServer {
                 self.try_reconnect().await.map_err(|msg| McpError::internal_error(msg, None))?;
             }
             if let Some(conn) = self.live.get().await {
-                let result = cdp::send(&*conn, "Target.createTarget", serde_json::json!({ "url": &url })).await;
+                let open_url = if direct_open { url.as_str() } else { "about:blank" };
+                let result = cdp::send(&conn, "Target.createTarget", serde_json::json!({ "url": open_url })).await;
                 if let Ok(res) = result {
                     if let Some(target_id) = res.get("targetId").and_then(|v| v.as_str()) {
                         let tid = target_id.to_owned();
                         tokio::time::sleep(std::time::Duration::from_millis(500)).await;
                         if self.reconnect_to_target(&tid).await.is_ok() {
                             *self.sticky_target.lock().await = Some(tid.clone());
-                            // Fall through to the wait-for-load + title logic below
+                            navigated = direct_open;
                         }
                     }
                 }
                 // If new tab failed for any reason, fall through to normal navigate
             }
-        } else {
-            // Normal navigate: same tab
-            self.execute_reconnect(commands::navigate(&url))
-                .await
-                .map_err(|e| McpError::internal_error(format!("Navigate failed: {e}"), None))?;
+        }
+
+        if !navigated {
+            if bypass_cache {
+                self.execute_reconnect(commands::set_cache_disabled(true))
+                    .await
+                    .map_err(|e| McpError::internal_error(format!("Disable cache failed: {e}"), None))?;
+            }
+            let result = self.execute_reconnect(commands::navigate_with_referrer(&url, referrer.as_deref())).await;
+            if let Err(e) = result {
+                if bypass_cache {
+                    let _ = self.execute_reconnect(commands::set_cache_disabled(false)).await;
+                }
+                return Err(McpError::internal_error(format!("Navigate failed: {e}"), None));
+            }
         }
 
         // Wait for the page to fully load (readyState = 'complete'). 8s cap.
@@ -643,118 +2172,356 @@ impl CausewayServer {
         ))
         .await;
 
-        let title_result = self.execute_reconnect(commands::evaluate("document.title"))
-            .await
-            .map_err(|e| McpError::internal_error(format!("Failed to get title: {e}"), None))?;
+        // Cache stays disabled only for this load
+        if bypass_cache {
+            let _ = self.execute_reconnect(commands::set_cache_disabled(false)).await;
+        }
 
-        let title = title_result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("(unknown)");
+        let mut output = if return_info.unwrap_or(true) {
+            let title_result = self.eval_value("document.title")
+                .await
+                .map_err(|e| McpError::internal_error(format!("Failed to get title: {e}"), None))?;
 
-        let url_result = self.execute_reconnect(commands::evaluate("window.location.href"))
-            .await
-            .ok();
+            let title = title_result
+                .as_str()
+                .unwrap_or("(unknown)");
 
-        let current_url = url_result
-            .as_ref()
-            .and_then(|r| r.get("result"))
-            .and_then(|r| r.get("value"))
-            .and_then(|v| v.as_str())
-            .unwrap_or(&url);
+            let url_result = self.eval_value("window.location.href")
+                .await
+                .ok();
 
-        Ok(CallToolResult::success(vec![Content::text(format!(
-            "Navigated to: {current_url}\nTitle: {title}"
-        ))]))
+            let current_url = url_result
+                .as_ref()
+                .and_then(|v| v.as_str())
+                .unwrap_or(&url);
+
+            format!("Navigated to: {current_url}\nTitle: {title}")
+        } else {
+            format!("Navigated to: {url}")
+        };
+        if let Some(referrer) = &referrer {
+            output.push_str(&format!("\nReferrer: {referrer}"));
+        }
+        if bypass_cache {
+            output.push_str("\nCache: bypassed");
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[tool(description = "Take a screenshot of the current page. Returns the image as base64 WebP (smaller than PNG).")]
-    async fn screenshot(&self) -> Result<CallToolResult, McpError> {
-        // Cascade down quality until under 5MB base64 (~3.75MB decoded)
-        const MAX_BASE64: usize = 5_000_000;
-        const MAX_DIM: f64 = 2000.0;
-        let qualities: &[u8] = &[80, 50, 30, 15];
+    #[tool(description = "Take a screenshot of the current page. Returns the image as base64 WebP (smaller than PNG), or writes it to save_path and returns the path. On HiDPI screens image pixels are CSS pixels × devicePixelRatio; pass css_pixels=true to capture at DPR 1 so image coordinates can be passed straight to click.")]
+    async fn screenshot(
+        &self,
+        Parameters(ScreenshotParams { wait_for_fonts, save_path, css_pixels }): Parameters<ScreenshotParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if !css_pixels.unwrap_or(false) {
+            return self.capture_viewport(wait_for_fonts, save_path, true).await;
+        }
 
-        // Get viewport dimensions + devicePixelRatio (actual image pixels = logical × DPR)
-        let dims_result = self.execute_reconnect(commands::evaluate(
-            "JSON.stringify({ w: window.innerWidth, h: window.innerHeight, dpr: window.devicePixelRatio })"
-        )).await.ok();
-        let (vw, vh, dpr) = dims_result
-            .as_ref()
-            .and_then(|r| r.get("result")?.get("value")?.as_str())
-            .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
-            .map(|v| (
-                v.get("w").and_then(|n| n.as_f64()).unwrap_or(1280.0),
-                v.get("h").and_then(|n| n.as_f64()).unwrap_or(800.0),
-                v.get("dpr").and_then(|n| n.as_f64()).unwrap_or(1.0),
-            ))
-            .unwrap_or((1280.0, 800.0, 1.0));
+        let dpr = self.eval_value("window.devicePixelRatio")
+            .await
+            .ok()
+            .and_then(|v| v.as_f64())
+            .unwrap_or(1.0);
+        let previous = *self.device_metrics.lock().await;
+        let overridden = dpr != 1.0;
+        if overridden {
+            // Keep any emulated viewport size and mobile mode; only the scale factor changes
+            let (width, height, mobile) = previous.map_or((0, 0, false), |m| (m.width, m.height, m.mobile));
+            self.execute_reconnect(commands::emulate_device_metrics(width, height, 1.0, mobile))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Set device scale factor failed: {e}"), None))?;
+            // Let the page repaint at the new scale before capturing
+            let _ = self.eval_value(
+                "new Promise(r => requestAnimationFrame(() => requestAnimationFrame(() => r(true))))"
+            ).await;
+        }
 
-        // Actual output pixels = logical × DPR. Scale down if that exceeds 2000.
-        let max_output_dim = vw.max(vh) * dpr;
-        let scale = if max_output_dim > MAX_DIM { MAX_DIM / max_output_dim } else { 1.0 };
+        // No 2000px fit: downscaling would break the 1:1 mapping to click coordinates
+        let result = self.capture_viewport(wait_for_fonts, save_path, false).await;
 
-        let mut final_data = String::new();
-        let mut used_quality = 80u8;
-        for &q in qualities {
-            let params = if scale < 1.0 {
-                serde_json::json!({
-                    "format": "webp",
-                    "quality": q,
-                    "clip": { "x": 0, "y": 0, "width": vw, "height": vh, "scale": scale },
-                })
-            } else {
-                serde_json::json!({ "format": "webp", "quality": q })
+        if overridden {
+            let restore = match previous {
+                Some(m) => commands::emulate_device_metrics(m.width, m.height, m.scale, m.mobile),
+                None => commands::clear_device_override(),
             };
+            if let Err(e) = self.execute_reconnect(restore).await {
+                tracing::warn!("Failed to restore device scale factor after screenshot: {e}");
+            }
+        }
 
-            let result = self.exec_with_reconnect("Page.captureScreenshot", params)
+        let mut result = result?;
+        result.content.push(Content::text(format!(
+            "CSS pixels: 1 image pixel = 1 CSS pixel, so image (x, y) is the click(x, y) coordinate{}",
+            if overridden { format!(" (captured at DPR 1, page DPR {dpr} restored)") } else { String::new() }
+        )));
+        Ok(result)
+    }
+
+    #[tool(description = "Capture a timed sequence of viewport screenshots (a filmstrip) — count frames, interval_ms apart — for seeing animations, transitions, and loading sequences a single screenshot can't show. Inline frames are downscaled to 800px; use save_dir for full-size files.")]
+    async fn filmstrip(
+        &self,
+        Parameters(FilmstripParams { count, interval_ms, format, save_dir }): Parameters<FilmstripParams>,
+    ) -> Result<CallToolResult, McpError> {
+        const MAX_FRAMES: u32 = 20;
+        const MAX_DIM: f64 = 800.0;
+        let count = count.unwrap_or(5).clamp(1, MAX_FRAMES);
+        let interval = std::time::Duration::from_millis(interval_ms.unwrap_or(250).min(10_000));
+        let (format, ext, mime) = match format.as_deref().unwrap_or("webp") {
+            "webp" => ("webp", "webp", "image/webp"),
+            "jpeg" | "jpg" => ("jpeg", "jpg", "image/jpeg"),
+            "png" => ("png", "png", "image/png"),
+            other => return Err(McpError::invalid_params(
+                format!("Unknown format \"{other}\" — use webp, jpeg, or png"),
+                None,
+            )),
+        };
+        if let Some(dir) = &save_dir {
+            if !std::path::Path::new(dir).is_absolute() {
+                return Err(McpError::invalid_params(format!("save_dir must be an absolute path: {dir}"), None));
+            }
+            prepare_save_path(&std::path::Path::new(dir).join("frame-00").to_string_lossy())?;
+        }
+
+        // Inline frames share one response, so scale each down to keep the whole strip small
+        let clip = if save_dir.is_none() {
+            let dims = self.eval_value("[window.innerWidth, window.innerHeight, window.devicePixelRatio]")
                 .await
-                .map_err(|e| McpError::internal_error(format!("Screenshot failed: {e}"), None))?;
+                .ok()
+                .and_then(|v| v.as_array().cloned())
+                .unwrap_or_default();
+            let vw = dims.first().and_then(|v| v.as_f64()).unwrap_or(1280.0);
+            let vh = dims.get(1).and_then(|v| v.as_f64()).unwrap_or(800.0);
+            let dpr = dims.get(2).and_then(|v| v.as_f64()).unwrap_or(1.0);
+            let max_output_dim = vw.max(vh) * dpr;
+            (max_output_dim > MAX_DIM).then(|| (0.0, 0.0, vw, vh, MAX_DIM / max_output_dim))
+        } else {
+            None
+        };
+        let quality = if save_dir.is_some() { 90 } else { 60 };
 
+        let start = tokio::time::Instant::now();
+        let mut frames: Vec<(u64, String)> = Vec::with_capacity(count as usize);
+        let mut total_base64 = 0usize;
+        for i in 0..count {
+            if i > 0 {
+                let due = interval.checked_mul(i).and_then(|offset| start.checked_add(offset));
+                tokio::time::sleep_until(due.unwrap_or_else(tokio::time::Instant::now)).await;
+            }
+            let t_ms = start.elapsed().as_millis() as u64;
+            let result = self.execute_reconnect(commands::capture_screenshot(format, Some(quality), clip, false))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Frame {i} capture failed: {e}"), None))?;
             let data = result
                 .get("data")
                 .and_then(|d| d.as_str())
                 .ok_or_else(|| McpError::internal_error("No screenshot data returned".to_owned(), None))?;
-
-            final_data = data.to_owned();
-            used_quality = q;
-            if final_data.len() <= MAX_BASE64 {
+            total_base64 += data.len();
+            frames.push((t_ms, data.to_owned()));
+            if save_dir.is_none() && total_base64 > CLIP_MAX_BASE64 {
                 break;
             }
-            tracing::info!("Screenshot {:.1}MB at quality {q}, retrying lower", final_data.len() as f64 / 1_000_000.0);
         }
 
-        let size_kb = final_data.len() / 1024;
-
-        if final_data.len() > MAX_BASE64 {
+        if let Some(dir) = save_dir {
+            let mut lines = Vec::with_capacity(frames.len());
+            for (i, (t_ms, data)) in frames.iter().enumerate() {
+                let path = std::path::Path::new(&dir).join(format!("frame-{i:02}.{ext}"));
+                let size = write_base64_capture(&path.to_string_lossy(), data)?;
+                lines.push(format!("  +{t_ms}ms {} ({size} bytes)", path.display()));
+            }
             return Ok(CallToolResult::success(vec![Content::text(format!(
-                "Screenshot too large even at lowest quality ({size_kb}KB base64). Use accessibility_snapshot or read_page instead."
+                "Saved {} frame(s) ({format}) to {dir}:\n{}",
+                frames.len(),
+                lines.join("\n")
             ))]));
         }
 
-        let scale_note = if scale < 1.0 {
-            format!(" @{:.0}% ({}×{})", scale * 100.0, (vw * dpr * scale) as u32, (vh * dpr * scale) as u32)
-        } else {
-            String::new()
+        let captured = frames.len();
+        let mut contents = Vec::with_capacity(captured + 1);
+        let mut timeline = Vec::with_capacity(captured);
+        for (i, (t_ms, data)) in frames.into_iter().enumerate() {
+            timeline.push(format!("frame {i}: +{t_ms}ms"));
+            contents.push(Content::image(data, mime));
+        }
+        let mut summary = format!("{captured} frame(s), {}KB total — {}", total_base64 / 1024, timeline.join(", "));
+        if (captured as u32) < count {
+            summary.push_str(&format!("\nStopped after {captured} of {count} frames — size cap reached. Use save_dir for longer strips."));
+        }
+        contents.push(Content::text(summary));
+
+        Ok(CallToolResult::success(contents))
+    }
+
+    #[tool(description = "Get the HTTP status code, status text, and response headers of the current page's main document (e.g. to tell a 404 or 500 page from a real one after navigate). Taken from the network response that loaded the current document; unavailable for pages loaded before Causeway connected or served without a network request.")]
+    async fn get_status(&self) -> Result<CallToolResult, McpError> {
+        let tree = self.execute_reconnect(commands::get_frame_tree())
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get frame tree failed: {e}"), None))?;
+        let frame = tree.get("frameTree").and_then(|t| t.get("frame"));
+        let frame_id = frame.and_then(|f| f.get("id")).and_then(|v| v.as_str()).unwrap_or("");
+        let loader_id = frame.and_then(|f| f.get("loaderId")).and_then(|v| v.as_str()).unwrap_or("");
+        let page_url = frame.and_then(|f| f.get("url")).and_then(|v| v.as_str()).unwrap_or("");
+
+        // A response for an earlier document in this frame doesn't describe the current one
+        let response = self.document_responses
+            .lock()
+            .await
+            .get(frame_id)
+            .filter(|r| r.loader_id == loader_id)
+            .cloned();
+        let Some(response) = response else {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Status unknown for {page_url} — no document response seen for the current page (loaded before connecting, from cache without a request, or a non-HTTP URL). Reload to capture it."
+            ))]));
         };
 
-        Ok(CallToolResult::success(vec![
-            Content::image(final_data, "image/webp"),
-            Content::text(format!("{size_kb}KB (q{used_quality}){scale_note}")),
-        ]))
+        let output = serde_json::json!({
+            "status": response.status,
+            "status_text": response.status_text,
+            "ok": (200..300).contains(&response.status),
+            "url": response.url,
+            "mime_type": response.mime_type,
+            "headers": response.headers,
+        });
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&output).unwrap_or_else(|_| output.to_string()),
+        )]))
+    }
+
+    #[tool(description = "Get the current page's document.title. No navigation or waiting — just reads the title.")]
+    async fn get_title(&self) -> Result<CallToolResult, McpError> {
+        let result = self.eval_value("document.title")
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get title failed: {e}"), None))?;
+
+        let title = result
+            .as_str()
+            .unwrap_or("");
+
+        Ok(CallToolResult::success(vec![Content::text(if title.is_empty() {
+            "(no title)".to_owned()
+        } else {
+            format!("Title: {title}")
+        })]))
+    }
+
+    #[tool(description = "Set the current page's document.title. Lasts until the page changes it or navigates.")]
+    async fn set_title(
+        &self,
+        Parameters(SetTitleParams { title }): Parameters<SetTitleParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            "(() => {{ document.title = {t}; return document.title; }})()",
+            t = serde_json::to_string(&title).unwrap()
+        );
+
+        let result = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Set title failed: {e}"), None))?;
+
+        let now = result
+            .as_str()
+            .unwrap_or("");
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Title set: {now}"
+        ))]))
+    }
+
+    #[tool(description = "Extract the page's main content as clean, article-style text (Readability-like): finds the main content region, strips navigation, sidebars, ads, share widgets, and comments, and returns headings (#), paragraphs, lists (-), and quotes (>) in reading order, plus the selector of the region used. Far fewer tokens than read_page on article and documentation pages.")]
+    async fn extract_content(
+        &self,
+        Parameters(ExtractContentParams { max_chars }): Parameters<ExtractContentParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let max_chars = max_chars.unwrap_or(20000);
+        let js = format!(
+            r#"(() => {{
+                {unique_selector}
+                const textLen = el => (el.textContent || '').replace(/\s+/g, ' ').trim().length;
+                // Score containers by the paragraph text they hold (parent full, grandparent half)
+                const scores = new Map();
+                for (const p of document.querySelectorAll('p, pre, blockquote, li')) {{
+                    const len = textLen(p);
+                    if (len < 40) continue;
+                    const parent = p.parentElement, grand = parent && parent.parentElement;
+                    if (parent) scores.set(parent, (scores.get(parent) || 0) + len);
+                    if (grand) scores.set(grand, (scores.get(grand) || 0) + len / 2);
+                }}
+                let root = null, best = 0;
+                for (const [el, score] of scores) {{
+                    if (score > best && el !== document.body && el !== document.documentElement) {{ best = score; root = el; }}
+                }}
+                // Prefer an enclosing <article>/<main> when the best block sits inside one
+                const landmark = root && root.closest('article, main, [role="main"]');
+                if (landmark && textLen(landmark) < textLen(root) * 3) root = landmark;
+                if (!root) root = document.querySelector('article, main, [role="main"]') || document.body;
+
+                const clone = root.cloneNode(true);
+                const noise = /(^|[\s_-])(ad|ads|advert|advertisement|promo|sponsor(ed)?|share|sharing|social|related|newsletter|subscribe|comments?|cookie|banner|sidebar|breadcrumbs?|pagination)([\s_-]|$)/i;
+                clone.querySelectorAll('script, style, noscript, template, svg, canvas, iframe, form, button, nav, aside, footer, [role="navigation"], [role="complementary"], [aria-hidden="true"], [hidden]')
+                    .forEach(el => el.remove());
+                clone.querySelectorAll('[class], [id]').forEach(el => {{
+                    const label = (typeof el.className === 'string' ? el.className : '') + ' ' + el.id;
+                    if (noise.test(label)) el.remove();
+                }});
+
+                const blocks = 'h1, h2, h3, h4, h5, h6, p, li, pre, blockquote, figcaption, dd, dt';
+                const lines = [];
+                for (const el of clone.querySelectorAll(blocks)) {{
+                    if (el.parentElement && el.parentElement.closest(blocks)) continue;
+                    const text = el.tagName === 'PRE' ? el.textContent.trim() : (el.textContent || '').replace(/\s+/g, ' ').trim();
+                    if (!text) continue;
+                    const tag = el.tagName;
+                    if (/^H[1-6]$/.test(tag)) lines.push('#'.repeat(+tag[1]) + ' ' + text);
+                    else if (tag === 'LI') lines.push('- ' + text);
+                    else if (tag === 'BLOCKQUOTE') lines.push('> ' + text);
+                    else lines.push(text);
+                }}
+                const text = lines.length ? lines.join('\n\n') : (clone.textContent || '').replace(/\s+/g, ' ').trim();
+                const words = text.split(/\s+/).filter(Boolean).length;
+                return {{
+                    selector: root === document.body ? 'body' : uniqueSelector(root),
+                    title: document.title,
+                    words,
+                    total: text.length,
+                    text: text.slice(0, {max_chars}),
+                }};
+            }})()"#,
+            unique_selector = JS_UNIQUE_SELECTOR,
+        );
+
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Extract content failed: {e}"), None))?;
+
+        let selector = value.get("selector").and_then(|v| v.as_str()).unwrap_or("body");
+        let title = value.get("title").and_then(|v| v.as_str()).unwrap_or("");
+        let words = value.get("words").and_then(|v| v.as_u64()).unwrap_or(0);
+        let total = value.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
+        let text = value.get("text").and_then(|v| v.as_str()).unwrap_or("");
+        if text.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No readable content found (main region: {selector})"
+            ))]));
+        }
+
+        let mut output = format!("Title: {title}\nMain content: {selector} ({words} words)\n\n{text}");
+        if total as usize > max_chars {
+            output.push_str(&format!("...\n\n[Truncated — {total} total characters]"));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
     #[tool(description = "Read the text content of the current page. Returns the visible text.")]
     async fn read_page(&self) -> Result<CallToolResult, McpError> {
-        let result = self.execute_reconnect(commands::evaluate("document.body.innerText"))
+        let result = self.eval_value("document.body.innerText")
             .await
             .map_err(|e| McpError::internal_error(format!("Read page failed: {e}"), None))?;
 
         let text = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .and_then(|v| v.as_str())
+            .as_str()
             .unwrap_or("(empty page)");
 
         // Truncate if extremely long to avoid overwhelming context
@@ -767,6 +2534,55 @@ impl CausewayServer {
         Ok(CallToolResult::success(vec![Content::text(truncated)]))
     }
 
+    #[tool(description = "Snapshot the page's visible text (document.body.innerText) for a later diff_text_since_snapshot. Take one before an action, then diff after it instead of re-reading the whole page. Stored per tab, so it survives navigation.")]
+    async fn snapshot_text(&self) -> Result<CallToolResult, McpError> {
+        let (key, lines) = self.page_text_lines().await?;
+        let count = lines.len();
+        self.text_snapshots.lock().await.insert(key, lines);
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Text snapshot taken ({count} lines)"
+        ))]))
+    }
+
+    #[tool(description = "Show only the lines of visible page text added or removed since the last snapshot_text on this tab — e.g. the success message or validation errors after clicking Submit. The current text becomes the new snapshot unless update_snapshot=false. Reordered lines aren't reported.")]
+    async fn diff_text_since_snapshot(
+        &self,
+        Parameters(DiffTextParams { update_snapshot, max_lines }): Parameters<DiffTextParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let (key, lines) = self.page_text_lines().await?;
+        let mut snapshots = self.text_snapshots.lock().await;
+        let Some(previous) = snapshots.get(&key) else {
+            return Err(McpError::invalid_params(
+                "No text snapshot for this tab — call snapshot_text first".to_owned(),
+                None,
+            ));
+        };
+
+        let (removed, added) = diff_lines(previous, &lines);
+        let cap = max_lines.unwrap_or(200);
+        let mut output = if removed.is_empty() && added.is_empty() {
+            "No text changes since snapshot".to_owned()
+        } else {
+            format!("{} line(s) added, {} removed", added.len(), removed.len())
+        };
+        for line in added.iter().take(cap) {
+            output.push_str(&format!("\n+ {line}"));
+        }
+        for line in removed.iter().take(cap.saturating_sub(added.len())) {
+            output.push_str(&format!("\n- {line}"));
+        }
+        if added.len() + removed.len() > cap {
+            output.push_str(&format!("\n[{} more line(s) not shown]", added.len() + removed.len() - cap));
+        }
+
+        if update_snapshot.unwrap_or(true) {
+            snapshots.insert(key, lines);
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
     #[tool(description = "Read text content from a specific element by CSS selector. More focused than read_page — avoids overwhelming output on complex pages.")]
     async fn read_text(
         &self,
@@ -781,16 +2597,12 @@ impl CausewayServer {
             sel = serde_json::to_string(&selector).unwrap()
         );
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let value = self.eval_value(&js)
             .await
             .map_err(|e| McpError::internal_error(format!("Read text failed: {e}"), None))?;
 
-        let value = result
-            .get("result")
-            .and_then(|r| r.get("value"));
-
         match value {
-            Some(v) if !v.is_null() => {
+            v if !v.is_null() => {
                 let text = v.as_str().unwrap_or("(non-text content)");
                 let truncated = if text.len() > 10000 {
                     format!("{}...\n\n[Truncated — {} total characters]", &text[..10000], text.len())
@@ -901,14 +2713,12 @@ impl CausewayServer {
                 needle = serde_json::to_string(&needle).unwrap(),
             );
 
-            let result = self.execute_reconnect(commands::evaluate(&js))
+            let result = self.eval_value(&js)
                 .await
                 .map_err(|e| McpError::internal_error(format!("Text check failed: {e}"), None))?;
 
             let found = result
-                .get("result")
-                .and_then(|r| r.get("value"))
-                .and_then(|v| v.as_bool())
+                .as_bool()
                 .unwrap_or(false);
 
             if found {
@@ -976,50 +2786,28 @@ impl CausewayServer {
     #[tool(description = "Execute JavaScript in the page context and return the result.")]
     async fn evaluate_js(
         &self,
-        Parameters(EvaluateJsParams { expression }): Parameters<EvaluateJsParams>,
+        Parameters(EvaluateJsParams { expression, user_gesture }): Parameters<EvaluateJsParams>,
     ) -> Result<CallToolResult, McpError> {
-        let result = self.execute_reconnect(commands::evaluate(&expression))
-            .await
-            .map_err(|e| McpError::internal_error(format!("JS evaluation failed: {e}"), None))?;
-
-        // Check for exceptions. On SPA navigation teardown, retry once after a short delay.
-        if let Some(exception) = result.get("exceptionDetails") {
-            let msg = exception
-                .get("exception")
-                .and_then(|e| e.get("description"))
-                .and_then(|d| d.as_str())
-                .unwrap_or("Unknown JS error");
+        let command = commands::evaluate_with_gesture(&expression, user_gesture.unwrap_or(false));
+        let mut result = self.execute_reconnect(command.clone()).await;
 
+        // On SPA navigation teardown, retry once after a short delay.
+        if let Err(cdp::CdpError::JsException(msg)) = &result {
             if msg.contains("global scope") || msg.contains("Cannot read properties of undefined") {
                 tokio::time::sleep(std::time::Duration::from_millis(300)).await;
-                let retry = self.execute_reconnect(commands::evaluate(&expression))
-                    .await
-                    .map_err(|e| McpError::internal_error(format!("JS evaluation failed: {e}"), None))?;
-                if retry.get("exceptionDetails").is_none() {
-                    let value = retry
-                        .get("result")
-                        .and_then(|r| r.get("value"))
-                        .cloned()
-                        .unwrap_or(serde_json::Value::Null);
-                    let output = if value.is_string() {
-                        value.as_str().unwrap().to_owned()
-                    } else {
-                        serde_json::to_string_pretty(&value).unwrap_or_else(|_| format!("{value:?}"))
-                    };
-                    return Ok(CallToolResult::success(vec![Content::text(output)]));
-                }
+                result = self.execute_reconnect(command).await;
             }
-
-            return Ok(CallToolResult::success(vec![Content::text(format!(
-                "JS Error: {msg}"
-            ))]));
         }
 
-        let value = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .cloned()
-            .unwrap_or(serde_json::Value::Null);
+        let value = match result {
+            Ok(result) => cdp::take_value(result),
+            Err(cdp::CdpError::JsException(msg)) => {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "JS Error: {msg}"
+                ))]));
+            }
+            Err(e) => return Err(McpError::internal_error(format!("JS evaluation failed: {e}"), None)),
+        };
 
         let output = if value.is_string() {
             value.as_str().unwrap().to_owned()
@@ -1030,36 +2818,84 @@ impl CausewayServer {
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
+    #[tool(description = "Evaluate a JavaScript expression repeatedly and return the time series of values — count samples, interval_ms apart, each with its offset from the first. For watching a countdown, progress bar, or counter change without many evaluate_js calls. A sample that throws is recorded as an error and sampling continues.")]
+    async fn poll_expression(
+        &self,
+        Parameters(PollExpressionParams { expression, count, interval_ms }): Parameters<PollExpressionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let count = count.unwrap_or(10).clamp(1, 500);
+        let interval = std::time::Duration::from_millis(interval_ms.unwrap_or(500).min(10_000));
+        let start = tokio::time::Instant::now();
+
+        let mut samples = Vec::with_capacity(count as usize);
+        let mut changes = 0u32;
+        let mut previous: Option<serde_json::Value> = None;
+        for i in 0..count {
+            if i > 0 {
+                let due = interval.checked_mul(i).and_then(|offset| start.checked_add(offset));
+                tokio::time::sleep_until(due.unwrap_or_else(tokio::time::Instant::now)).await;
+            }
+            let t_ms = start.elapsed().as_millis() as u64;
+            let sample = match self.eval_value(&expression).await {
+                Ok(value) => {
+                    if previous.as_ref().is_some_and(|p| *p != value) {
+                        changes += 1;
+                    }
+                    previous = Some(value.clone());
+                    serde_json::json!({ "t_ms": t_ms, "value": value })
+                }
+                Err(cdp::CdpError::JsException(msg)) => serde_json::json!({ "t_ms": t_ms, "error": msg }),
+                Err(e) => return Err(McpError::internal_error(format!("Poll failed at sample {}: {e}", i + 1), None)),
+            };
+            samples.push(sample);
+        }
+
+        let output = serde_json::json!({
+            "samples": samples,
+            "count": count,
+            "changes": changes,
+            "duration_ms": start.elapsed().as_millis() as u64,
+        });
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&output).unwrap_or_else(|_| output.to_string()),
+        )]))
+    }
+
     #[tool(description = "Click an element on the page by CSS selector.")]
     async fn click(
         &self,
-        Parameters(ClickParams { selector }): Parameters<ClickParams>,
+        Parameters(ClickParams { selector, human_like, wait_ms }): Parameters<ClickParams>,
     ) -> Result<CallToolResult, McpError> {
         let js = js_find_visible_element(&selector);
+        let wait = wait_ms.unwrap_or(0);
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(wait);
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
-            .await
-            .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
+        // Poll until a visible match appears or wait_ms runs out (one attempt when 0)
+        let coords = loop {
+            let result = self.eval_value(&js)
+                .await
+                .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
 
-        let coords = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .ok_or_else(|| {
-                McpError::invalid_params(format!("No visible, in-viewport element found for: {selector}"), None)
-            })?;
+            if !result.is_null() {
+                break result;
+            }
 
-        if coords.is_null() {
-            return Err(McpError::invalid_params(
-                format!("No visible, in-viewport element found for: {selector}"),
-                None,
-            ));
-        }
+            if tokio::time::Instant::now() >= deadline {
+                let waited = if wait > 0 { format!(" after {wait}ms") } else { String::new() };
+                return Err(McpError::invalid_params(
+                    format!("No visible, in-viewport element found for: {selector}{waited}"),
+                    None,
+                ));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        };
 
         let x = coords.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
         let y = coords.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
 
         self.snapshot_pre_nav().await;
-        self.execute_seq_reconnect(commands::click(x, y))
+        let human = human_like.unwrap_or(self.browser_config.human_like);
+        self.execute_seq_paced(commands::click(x, y), human)
             .await
             .map_err(|e| McpError::internal_error(format!("Click failed: {e}"), None))?;
 
@@ -1068,59 +2904,73 @@ impl CausewayServer {
         ))]))
     }
 
-    #[tool(description = "Click an element by its visible text content. More reliable than CSS selectors on dynamic UIs. Finds the first visible, in-viewport element whose text, value, or aria-label contains the search string. Works on buttons, links, inputs (including submit buttons by their value), and any element with text.")]
-    async fn click_text(
+    #[tool(description = "Click at an offset within an element instead of its center. Offsets are measured from the element's top-left corner, in pixels or as 0-1 fractions (relative=true). For canvas apps, sliders, maps, and other hit-tested surfaces.")]
+    async fn click_at(
         &self,
-        Parameters(ClickTextParams { text, tag }): Parameters<ClickTextParams>,
+        Parameters(ClickAtParams { selector, offset_x, offset_y, relative }): Parameters<ClickAtParams>,
     ) -> Result<CallToolResult, McpError> {
-        let tag_filter = tag.as_deref().unwrap_or("*");
-
         let js = format!(
             r#"(async () => {{
-                const searchText = {text}.toLowerCase();
-                const els = document.querySelectorAll({tag});
-                const vw = window.innerWidth;
-                const vh = window.innerHeight;
-                const candidates = [];
+                const els = document.querySelectorAll({sel});
                 for (const el of els) {{
-                    const elText = (el.textContent || '').trim().toLowerCase();
-                    const elValue = (el.value || el.getAttribute('aria-label') || '').trim().toLowerCase();
-                    if (!elText.includes(searchText) && !elValue.includes(searchText)) continue;
                     const r = el.getBoundingClientRect();
                     if (r.width === 0 || r.height === 0) continue;
-                    const matchLen = elText.includes(searchText) ? elText.length : elValue.length;
-                    candidates.push({{ el, len: matchLen }});
-                }}
-                candidates.sort((a, b) => a.len - b.len);
-                for (const {{ el }} of candidates) {{
                     el.scrollIntoView({{ block: 'center', behavior: 'instant' }});
                     await new Promise(resolve => requestAnimationFrame(() => requestAnimationFrame(resolve)));
                     const rect = el.getBoundingClientRect();
-                    const cx = rect.x + rect.width / 2;
-                    const cy = rect.y + rect.height / 2;
-                    if (cx >= 0 && cy >= 0 && cx <= vw && cy <= vh) {{
-                        return {{ x: cx, y: cy, matched: el.textContent.trim().substring(0, 80) }};
-                    }}
+                    const x = rect.x + ({relative} ? rect.width * {ox} : {ox});
+                    const y = rect.y + ({relative} ? rect.height * {oy} : {oy});
+                    const inViewport = x >= 0 && y >= 0 && x <= window.innerWidth && y <= window.innerHeight;
+                    return {{ x, y, inViewport, width: rect.width, height: rect.height }};
                 }}
                 return null;
             }})()"#,
-            text = serde_json::to_string(&text).unwrap(),
-            tag = serde_json::to_string(tag_filter).unwrap()
+            sel = serde_json::to_string(&selector).unwrap(),
+            relative = relative.unwrap_or(false),
+            ox = serde_json::json!(offset_x),
+            oy = serde_json::json!(offset_y),
         );
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let point = self.eval_value(&js)
             .await
             .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
 
-        let coords = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .ok_or_else(|| {
-                McpError::invalid_params(
-                    format!("No visible element found containing text: \"{text}\""),
-                    None,
-                )
-            })?;
+        if point.is_null() {
+            return Err(McpError::invalid_params(format!("No visible element found for: {selector}"), None));
+        }
+
+        let x = point.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let y = point.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        if !point.get("inViewport").and_then(|v| v.as_bool()).unwrap_or(false) {
+            let w = point.get("width").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let h = point.get("height").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            return Err(McpError::invalid_params(
+                format!("Offset resolves to ({x:.0}, {y:.0}), outside the viewport (element is {w:.0}x{h:.0})"),
+                None,
+            ));
+        }
+
+        self.snapshot_pre_nav().await;
+        self.execute_seq_paced(commands::click(x, y), self.browser_config.human_like)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Click failed: {e}"), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Clicked '{selector}' at offset ({offset_x}, {offset_y}) → ({x:.0}, {y:.0})"
+        ))]))
+    }
+
+    #[tool(description = "Click an element by its visible text content. More reliable than CSS selectors on dynamic UIs. Finds the first visible, in-viewport element whose text, value, or aria-label contains the search string. Works on buttons, links, inputs (including submit buttons by their value), and any element with text.")]
+    async fn click_text(
+        &self,
+        Parameters(ClickTextParams { text, tag }): Parameters<ClickTextParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let tag_filter = tag.as_deref().unwrap_or("*");
+        let js = js_find_text_element(&text, tag_filter);
+
+        let coords = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
 
         if coords.is_null() {
             return Err(McpError::invalid_params(
@@ -1185,20 +3035,10 @@ impl CausewayServer {
             idx = idx,
         );
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let coords = self.eval_value(&js)
             .await
             .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
 
-        let coords = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .ok_or_else(|| {
-                McpError::invalid_params(
-                    format!("No interactive element found containing text: \"{text}\""),
-                    None,
-                )
-            })?;
-
         if coords.is_null() {
             return Err(McpError::invalid_params(
                 format!("No interactive element found containing text: \"{text}\""),
@@ -1233,19 +3073,17 @@ impl CausewayServer {
     #[tool(description = "Type text into an element on the page. Focuses the element first, then types character by character.")]
     async fn type_text(
         &self,
-        Parameters(TypeTextParams { selector, text, clear }): Parameters<TypeTextParams>,
+        Parameters(TypeTextParams { selector, text, clear, human_like }): Parameters<TypeTextParams>,
     ) -> Result<CallToolResult, McpError> {
         let should_clear = clear.unwrap_or(false);
         let js = js_focus_visible_element(&selector, should_clear);
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let result = self.eval_value(&js)
             .await
             .map_err(|e| McpError::internal_error(format!("Failed to focus element: {e}"), None))?;
 
         let focused = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .and_then(|v| v.as_bool())
+            .as_bool()
             .unwrap_or(false);
 
         if !focused {
@@ -1256,7 +3094,8 @@ impl CausewayServer {
         }
 
         // Type each character (replaces selected text if clear was used)
-        self.execute_seq_reconnect(commands::type_text(&text))
+        let human = human_like.unwrap_or(self.browser_config.human_like);
+        self.execute_seq_paced(commands::type_text(&text), human)
             .await
             .map_err(|e| McpError::internal_error(format!("Type failed: {e}"), None))?;
 
@@ -1267,12 +3106,69 @@ impl CausewayServer {
         ))]))
     }
 
-    #[tool(description = "Read all form fields on the page or within a container. Returns each field's tag, type, name, id, label, value, placeholder, and whether it's required/disabled. Great for understanding a form before filling it.")]
+    #[tool(description = "Paste text into an element: focuses it and dispatches a paste ClipboardEvent carrying the text in clipboardData. If no page handler takes the paste (calls preventDefault), the text is inserted with Input.insertText instead, as the browser would. For code editors, OTP inputs, and fields that ignore typed keys but handle paste. Doesn't touch the system clipboard.")]
+    async fn paste_into(
+        &self,
+        Parameters(PasteIntoParams { selector, text }): Parameters<PasteIntoParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = js_focus_visible_element(&selector, false);
+        let result = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to focus element: {e}"), None))?;
+
+        let focused = result
+            .as_bool()
+            .unwrap_or(false);
+
+        if !focused {
+            return Err(McpError::invalid_params(
+                format!("Element not found or not focusable: {selector}"),
+                None,
+            ));
+        }
+
+        let js = format!(
+            r#"(() => {{
+                const el = document.activeElement || document.body;
+                const dt = new DataTransfer();
+                dt.setData('text/plain', {text});
+                const ev = new ClipboardEvent('paste', {{ clipboardData: dt, bubbles: true, cancelable: true, composed: true }});
+                // A handler that takes the paste cancels the default insertion
+                return !el.dispatchEvent(ev);
+            }})()"#,
+            text = serde_json::to_string(&text).unwrap()
+        );
+        let result = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Paste failed: {e}"), None))?;
+
+        let handled = result
+            .as_bool()
+            .unwrap_or(false);
+
+        let chars = text.chars().count();
+        let output = if handled {
+            format!("Pasted {chars} characters into '{selector}' — the page handled the paste event")
+        } else {
+            // Synthetic paste events have no default action — do the insertion ourselves
+            self.execute_reconnect(commands::insert_text(&text))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Insert text failed: {e}"), None))?;
+            format!("Pasted {chars} characters into '{selector}' — no handler took the paste event, inserted via Input.insertText")
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Read all form fields on the page or within a container. Returns each field's tag, type, name, id, label, value, placeholder, and whether it's required/disabled. Great for understanding a form before filling it. With values_only, returns a plain { selector: value } JSON object instead — the inverse of fill_form.")]
     async fn read_form(
         &self,
-        Parameters(ReadFormParams { selector }): Parameters<ReadFormParams>,
+        Parameters(ReadFormParams { selector, values_only }): Parameters<ReadFormParams>,
     ) -> Result<CallToolResult, McpError> {
         let sel = selector.as_deref().unwrap_or("body");
+        if values_only.unwrap_or(false) {
+            return self.read_form_values(sel).await;
+        }
         let js = format!(
             r#"(() => {{
                 const container = document.querySelector({sel});
@@ -1313,27 +3209,269 @@ impl CausewayServer {
             sel = serde_json::to_string(sel).unwrap()
         );
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let value = self.eval_value(&js)
             .await
             .map_err(|e| McpError::internal_error(format!("Read form failed: {e}"), None))?;
 
-        let value = result
-            .get("result")
-            .and_then(|r| r.get("value"));
+        if value.is_null() {
+            return Ok(CallToolResult::success(vec![Content::text("No form data returned")]));
+        }
+        if let Some(err) = value.get("error").and_then(|e| e.as_str()) {
+            return Err(McpError::invalid_params(err.to_owned(), None));
+        }
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&value).unwrap_or_else(|_| format!("{value}"))
+        )]))
+    }
+
+    #[tool(description = "Assert an element's visible text equals, contains, or matches a regex. Returns structured JSON { pass, actual } for test-style workflows; with fail_on_mismatch a mismatch becomes a tool error.")]
+    async fn assert_text(
+        &self,
+        Parameters(AssertTextParams { selector, value, mode, fail_on_mismatch }): Parameters<AssertTextParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mode = mode.unwrap_or_else(|| "equals".to_owned());
+        if !matches!(mode.as_str(), "equals" | "contains" | "matches_regex") {
+            return Err(McpError::invalid_params(
+                format!("Unknown mode: {mode}. Use 'equals', 'contains', or 'matches_regex'."),
+                None,
+            ));
+        }
+
+        // Regex matching runs in the page, so patterns use JavaScript RegExp syntax
+        let js = format!(
+            r#"(() => {{
+                const el = document.querySelector({sel});
+                if (!el) return null;
+                const actual = (el.innerText || el.textContent || '').trim();
+                const expected = {expected};
+                let pass;
+                switch ({mode}) {{
+                    case 'equals': pass = actual === expected.trim(); break;
+                    case 'contains': pass = actual.includes(expected); break;
+                    default:
+                        try {{ pass = new RegExp(expected).test(actual); }}
+                        catch (e) {{ return {{ error: String(e.message || e) }}; }}
+                }}
+                return {{ pass, actual }};
+            }})()"#,
+            sel = serde_json::to_string(&selector).unwrap(),
+            expected = serde_json::to_string(&value).unwrap(),
+            mode = serde_json::to_string(&mode).unwrap(),
+        );
+
+        let result = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Assert text failed: {e}"), None))?;
+
+        if result.is_null() {
+            return Err(McpError::invalid_params(format!("Element not found: {selector}"), None));
+        }
+        if let Some(err) = result.get("error").and_then(|v| v.as_str()) {
+            return Err(McpError::invalid_params(format!("Invalid regex: {err}"), None));
+        }
+
+        let pass = result.get("pass").and_then(|v| v.as_bool()).unwrap_or(false);
+        let actual = result.get("actual").and_then(|v| v.as_str()).unwrap_or("");
+        if !pass && fail_on_mismatch.unwrap_or(false) {
+            return Err(McpError::internal_error(
+                format!("Assertion failed: text of '{selector}' {mode} {value:?} — actual: {actual:?}"),
+                None,
+            ));
+        }
+
+        let output = serde_json::json!({ "pass": pass, "actual": actual });
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&output).unwrap_or_else(|_| output.to_string())
+        )]))
+    }
+
+    #[tool(description = "Assert how many elements match a selector (eq, gte, or lte an expected count). Returns structured JSON { pass, actual } — e.g. \"exactly 5 results rendered\" without counting query_elements output.")]
+    async fn assert_count(
+        &self,
+        Parameters(AssertCountParams { selector, expected, op }): Parameters<AssertCountParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let op = op.unwrap_or_else(|| "eq".to_owned());
+        let js = format!(
+            "document.querySelectorAll({sel}).length",
+            sel = serde_json::to_string(&selector).unwrap()
+        );
 
-        if let Some(v) = value {
-            if let Some(err) = v.get("error").and_then(|e| e.as_str()) {
-                return Err(McpError::invalid_params(err.to_owned(), None));
+        let actual = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Assert count failed: {e}"), None))?
+            .as_u64()
+            .unwrap_or(0);
+
+        let pass = match op.as_str() {
+            "eq" => actual == expected,
+            "gte" => actual >= expected,
+            "lte" => actual <= expected,
+            _ => {
+                return Err(McpError::invalid_params(
+                    format!("Unknown op: {op}. Use 'eq', 'gte', or 'lte'."),
+                    None,
+                ));
             }
-            Ok(CallToolResult::success(vec![Content::text(
-                serde_json::to_string_pretty(v).unwrap_or_else(|_| format!("{v}"))
-            )]))
-        } else {
-            Ok(CallToolResult::success(vec![Content::text("No form data returned")]))
+        };
+
+        let output = serde_json::json!({ "pass": pass, "actual": actual });
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&output).unwrap_or_else(|_| output.to_string())
+        )]))
+    }
+
+    #[tool(description = "List keyboard-focusable elements in Tab order: positive tabindex first (ascending), then tabindex=0 and natively focusable elements in DOM order. Skips disabled, hidden, and inert elements. Each entry has its position, tag, label, tabindex, and a selector — for checking logical keyboard navigation and planning press_key(\"Tab\") sequences.")]
+    async fn get_tab_order(
+        &self,
+        Parameters(GetTabOrderParams { selector, limit }): Parameters<GetTabOrderParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let limit = limit.unwrap_or(100);
+        let js = format!(
+            r#"(() => {{
+                {unique_selector}
+                const sel = {sel};
+                const root = sel ? document.querySelector(sel) : document;
+                if (!root) return null;
+                const candidates = root.querySelectorAll('a[href], area[href], button, input, select, textarea, iframe, summary, audio[controls], video[controls], [contenteditable=""], [contenteditable="true"], [tabindex]');
+                const focusable = [];
+                candidates.forEach((el, domIndex) => {{
+                    if (el.tabIndex < 0 || el.disabled || (el.type === 'hidden') || el.closest('[inert]')) return;
+                    const style = getComputedStyle(el);
+                    if (style.visibility === 'hidden' || style.display === 'none' || el.getClientRects().length === 0) return;
+                    const label = (el.getAttribute('aria-label')
+                        || (el.labels && el.labels[0] && el.labels[0].innerText)
+                        || el.innerText
+                        || el.getAttribute('alt') || el.getAttribute('title') || el.getAttribute('placeholder')
+                        || (el.type !== 'password' ? el.value : '')
+                        || '').replace(/\s+/g, ' ').trim().slice(0, 80);
+                    focusable.push({{
+                        tag: el.tagName.toLowerCase() + (el.type && el.tagName === 'INPUT' ? '[' + el.type + ']' : ''),
+                        label,
+                        tabindex: el.tabIndex,
+                        explicit: el.hasAttribute('tabindex'),
+                        selector: uniqueSelector(el),
+                        domIndex,
+                    }});
+                }});
+                focusable.sort((a, b) => {{
+                    const ka = a.tabindex > 0 ? a.tabindex : Infinity;
+                    const kb = b.tabindex > 0 ? b.tabindex : Infinity;
+                    return ka === kb ? a.domIndex - b.domIndex : ka - kb;
+                }});
+                return {{ total: focusable.length, items: focusable.slice(0, {limit}) }};
+            }})()"#,
+            unique_selector = JS_UNIQUE_SELECTOR,
+            sel = serde_json::to_string(&selector).unwrap(),
+        );
+
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get tab order failed: {e}"), None))?;
+
+        if value.is_null() {
+            let selector = selector.as_deref().unwrap_or("");
+            return Err(McpError::invalid_params(format!("Element not found: {selector}"), None));
+        }
+
+        let total = value.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
+        let items = value.get("items").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        if items.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No keyboard-focusable elements".to_owned(),
+            )]));
+        }
+
+        let mut positive = 0;
+        let mut lines = Vec::with_capacity(items.len());
+        for (i, item) in items.iter().enumerate() {
+            let tag = item.get("tag").and_then(|v| v.as_str()).unwrap_or("?");
+            let label = item.get("label").and_then(|v| v.as_str()).unwrap_or("");
+            let selector = item.get("selector").and_then(|v| v.as_str()).unwrap_or("");
+            let tabindex = item.get("tabindex").and_then(|v| v.as_i64()).unwrap_or(0);
+            let explicit = item.get("explicit").and_then(|v| v.as_bool()).unwrap_or(false);
+            if tabindex > 0 {
+                positive += 1;
+            }
+            let tabindex_note = if explicit { format!(" tabindex={tabindex}") } else { String::new() };
+            let label = if label.is_empty() { "(no label)".to_owned() } else { format!("\"{label}\"") };
+            lines.push(format!("{:>3}. {tag} {label}{tabindex_note} — {selector}", i + 1));
+        }
+
+        let mut output = format!("{total} focusable element(s) in Tab order:\n{}", lines.join("\n"));
+        if total as usize > items.len() {
+            output.push_str(&format!("\n... {} more (raise limit to see them)", total as usize - items.len()));
+        }
+        if positive > 0 {
+            output.push_str(&format!("\n\n{positive} element(s) use a positive tabindex, which overrides DOM order — often an accessibility smell"));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Run native HTML form validation (checkValidity / reportValidity) on a form or field and list each invalid field with the browser's validationMessage and the failing validity flags (valueMissing, typeMismatch, patternMismatch, ...). Explains why a form won't submit without screenshotting tooltip bubbles.")]
+    async fn get_validation(
+        &self,
+        Parameters(GetValidationParams { selector, report }): Parameters<GetValidationParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                {unique_selector}
+                const root = document.querySelector({sel});
+                if (!root) return null;
+                const fields = root instanceof HTMLFormElement ? Array.from(root.elements)
+                    : typeof root.checkValidity === 'function' ? [root]
+                    : Array.from(root.querySelectorAll('input, select, textarea, button, fieldset, output, object'));
+                const valid = root[{method}] ? root[{method}]() : fields.every(f => !f.checkValidity || f.checkValidity());
+                const FLAGS = ['valueMissing', 'typeMismatch', 'patternMismatch', 'tooLong', 'tooShort',
+                    'rangeUnderflow', 'rangeOverflow', 'stepMismatch', 'badInput', 'customError'];
+                const invalid = fields
+                    .filter(f => f.willValidate && f.validity && !f.validity.valid)
+                    .map(f => ({{
+                        selector: uniqueSelector(f),
+                        name: f.name || f.id || null,
+                        message: f.validationMessage,
+                        flags: FLAGS.filter(k => f.validity[k]),
+                    }}));
+                return {{ valid: valid && invalid.length === 0, checked: fields.filter(f => f.willValidate).length, invalid }};
+            }})()"#,
+            unique_selector = JS_UNIQUE_SELECTOR,
+            sel = serde_json::to_string(&selector).unwrap(),
+            method = serde_json::to_string(if report.unwrap_or(false) { "reportValidity" } else { "checkValidity" }).unwrap(),
+        );
+
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Validation check failed: {e}"), None))?;
+
+        if value.is_null() {
+            return Err(McpError::invalid_params(format!("Element not found: {selector}"), None));
+        }
+
+        let checked = value.get("checked").and_then(|v| v.as_u64()).unwrap_or(0);
+        let invalid = value.get("invalid").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        if value.get("valid").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Valid — {checked} field(s) checked"
+            ))]));
+        }
+
+        let mut output = format!("Invalid — {} of {checked} field(s) failing:\n", invalid.len());
+        for field in &invalid {
+            let label = field.get("selector").and_then(|v| v.as_str())
+                .or_else(|| field.get("name").and_then(|v| v.as_str()))
+                .unwrap_or("(unnamed field)");
+            let message = field.get("message").and_then(|v| v.as_str()).unwrap_or("");
+            let flags: Vec<&str> = field.get("flags")
+                .and_then(|v| v.as_array())
+                .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+            output.push_str(&format!("  {label}: {message} [{}]\n", flags.join(", ")));
         }
+
+        Ok(CallToolResult::success(vec![Content::text(output.trim_end().to_owned())]))
     }
 
-    #[tool(description = "Fill multiple form fields at once. Takes a JSON object mapping CSS selectors to values. Each field is focused, cleared, and typed into.")]
+    #[tool(description = "Fill multiple form fields at once. Takes a JSON object mapping CSS selectors to values. Text fields are focused, cleared, and typed into; checkboxes, radios, and selects are set directly and fire input/change events.")]
     async fn fill_form(
         &self,
         Parameters(FillFormParams { selector, fields }): Parameters<FillFormParams>,
@@ -1344,45 +3482,73 @@ impl CausewayServer {
                 const container = document.querySelector({sel});
                 if (!container) return {{ error: "Container not found" }};
                 const fields = {fields};
-                const results = [];
+                const results = {{}};
+                const fire = el => {{
+                    el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                    el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                }};
                 for (const [fieldSel, value] of Object.entries(fields)) {{
-                    const el = container.querySelector(fieldSel) || document.querySelector(fieldSel);
+                    const inContainer = Array.from(container.querySelectorAll(fieldSel));
+                    const els = inContainer.length ? inContainer : Array.from(document.querySelectorAll(fieldSel));
+                    const el = els[0];
                     if (!el) {{
-                        results.push({{ field: fieldSel, status: "not_found" }});
+                        results[fieldSel] = "not_found";
                         continue;
                     }}
                     el.scrollIntoView({{ block: 'center', behavior: 'instant' }});
-                    await new Promise(r => requestAnimationFrame(() => requestAnimationFrame(r)));
-                    el.focus();
-                    el.select();
-                    results.push({{ field: fieldSel, status: "focused" }});
+                    if (el.type === 'checkbox' || el.type === 'radio') {{
+                        // A [name="…"] selector covers the whole group: an array or string says which
+                        // members end up checked; a bool sets just the first match.
+                        const want = e => Array.isArray(value) ? value.map(String).includes(e.value)
+                            : typeof value === 'boolean' ? (e === el ? value : e.checked)
+                            : value !== null && e.value === String(value);
+                        for (const e of els.filter(e => e.type === el.type)) {{
+                            if (e.checked !== want(e)) {{ e.checked = want(e); fire(e); }}
+                        }}
+                        results[fieldSel] = "set";
+                    }} else if (el.tagName === 'SELECT') {{
+                        const wanted = Array.isArray(value) ? value.map(String) : value === null ? [] : [String(value)];
+                        for (const o of el.options) o.selected = wanted.includes(o.value);
+                        fire(el);
+                        results[fieldSel] = "set";
+                    }} else if (el.type === 'file') {{
+                        results[fieldSel] = "file input (use upload_file)";
+                    }} else if (typeof value === 'string' || typeof value === 'number') {{
+                        results[fieldSel] = "type";
+                    }} else {{
+                        results[fieldSel] = "expects a string value";
+                    }}
                 }}
-                return {{ ok: true, count: results.length, results: results }};
+                return {{ ok: true, results: results }};
             }})()"#,
             sel = serde_json::to_string(&selector).unwrap(),
             fields = fields_json
         );
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let value = self.eval_value(&js)
             .await
             .map_err(|e| McpError::internal_error(format!("Fill form failed: {e}"), None))?;
 
-        let value = result
-            .get("result")
-            .and_then(|r| r.get("value"));
-
-        if let Some(v) = value {
-            if v.get("error").is_some() {
-                return Err(McpError::invalid_params(
-                    format!("Container not found: {selector}"),
-                    None,
-                ));
-            }
+        if value.get("error").is_some() {
+            return Err(McpError::invalid_params(
+                format!("Container not found: {selector}"),
+                None,
+            ));
         }
 
-        // Now type into each field sequentially
+        // Checkboxes, radios, and selects were set above; type into the text fields sequentially
         let mut filled = Vec::new();
         for (field_sel, field_value) in &fields {
+            let status = value["results"][field_sel.as_str()].as_str().unwrap_or("not_found");
+            if status != "type" {
+                filled.push(match status {
+                    "set" => format!("{field_sel}: {field_value}"),
+                    "not_found" => format!("{field_sel}: NOT FOUND"),
+                    other => format!("{field_sel}: SKIPPED, {other}"),
+                });
+                continue;
+            }
+            let field_value = field_text(field_value).unwrap_or_default();
             // Focus the field
             let focus_js = format!(
                 r#"(() => {{
@@ -1397,18 +3563,16 @@ impl CausewayServer {
                 field = serde_json::to_string(field_sel).unwrap()
             );
 
-            let focus_result = self.execute_reconnect(commands::evaluate(&focus_js))
+            let focus_result = self.eval_value(&focus_js)
                 .await
                 .map_err(|e| McpError::internal_error(format!("Focus failed: {e}"), None))?;
 
             let focused = focus_result
-                .get("result")
-                .and_then(|r| r.get("value"))
-                .and_then(|v| v.as_bool())
+                .as_bool()
                 .unwrap_or(false);
 
             if focused {
-                self.execute_seq_reconnect(commands::type_text(field_value))
+                self.execute_seq_reconnect(commands::type_text(&field_value))
                     .await
                     .map_err(|e| McpError::internal_error(format!("Type failed: {e}"), None))?;
                 filled.push(format!("{field_sel}: \"{field_value}\""));
@@ -1424,6 +3588,24 @@ impl CausewayServer {
         ))]))
     }
 
+    #[tool(description = "Check whether the page is still loading, without waiting: document.readyState plus the number of frames with a navigation in progress (from Page.frameStartedLoading/frameStoppedLoading). Cheap enough to poll instead of sleeping after navigate, go_back, or a click that navigates.")]
+    async fn is_loading(&self) -> Result<CallToolResult, McpError> {
+        let ready_state = self.eval_value("document.readyState")
+            .await
+            .map_err(|e| McpError::internal_error(format!("Loading check failed: {e}"), None))?;
+        let ready_state = ready_state.as_str().unwrap_or("unknown").to_owned();
+        let loading_frames = self.loading_frames.lock().await.len();
+
+        let output = serde_json::json!({
+            "loading": ready_state != "complete" || loading_frames > 0,
+            "ready_state": ready_state,
+            "frames_loading": loading_frames,
+        });
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&output).unwrap_or_else(|_| output.to_string()),
+        )]))
+    }
+
     #[tool(description = "Wait for an element matching a CSS selector to appear in the DOM.")]
     async fn wait_for(
         &self,
@@ -1442,14 +3624,12 @@ impl CausewayServer {
                 sel = serde_json::to_string(&selector).unwrap()
             );
 
-            let result = self.execute_reconnect(commands::evaluate(&js))
+            let result = self.eval_value(&js)
                 .await
                 .map_err(|e| McpError::internal_error(format!("Wait check failed: {e}"), None))?;
 
             let found = result
-                .get("result")
-                .and_then(|r| r.get("value"))
-                .and_then(|v| v.as_bool())
+                .as_bool()
                 .unwrap_or(false);
 
             if found {
@@ -1467,1073 +3647,3174 @@ impl CausewayServer {
         ))
     }
 
-    #[tool(description = "Scroll the page by a given number of pixels.")]
-    async fn scroll(
+    #[tool(description = "Wait until a JavaScript expression evaluates truthy. Polls at an interval; exceptions during evaluation (e.g. the variable doesn't exist yet) are ignored and polling continues. Returns the final value.")]
+    async fn wait_for_condition(
         &self,
-        Parameters(ScrollParams { x, y }): Parameters<ScrollParams>,
+        Parameters(WaitForConditionParams { expression, timeout_ms, interval_ms }): Parameters<WaitForConditionParams>,
     ) -> Result<CallToolResult, McpError> {
-        let scroll_x = x.unwrap_or(0.0);
-        let scroll_y = y.unwrap_or(0.0);
+        let timeout = timeout_ms.unwrap_or(5000);
+        let interval = interval_ms.unwrap_or(200).max(10);
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(timeout);
 
-        self.execute_reconnect(commands::scroll(scroll_x, scroll_y))
-            .await
-            .map_err(|e| McpError::internal_error(format!("Scroll failed: {e}"), None))?;
+        let js = format!(
+            r#"(async () => {{
+                const value = await ({expression});
+                return {{ truthy: !!value, value: value === undefined ? null : value }};
+            }})()"#
+        );
 
-        Ok(CallToolResult::success(vec![Content::text(format!(
-            "Scrolled by ({scroll_x:.0}, {scroll_y:.0})"
-        ))]))
+        let mut last_error: Option<String> = None;
+        loop {
+            let result = match self.eval_value(&js).await {
+                Ok(result) => Some(result),
+                // Not ready yet (e.g. ReferenceError) — keep polling
+                Err(cdp::CdpError::JsException(msg)) => {
+                    last_error = Some(msg);
+                    None
+                }
+                Err(e) => return Err(McpError::internal_error(format!("Condition check failed: {e}"), None)),
+            };
+
+            if let Some(value) = result {
+                last_error = None;
+                if value.get("truthy").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    let final_value = value.get("value").cloned().unwrap_or(serde_json::Value::Null);
+                    let output = if final_value.is_string() {
+                        final_value.as_str().unwrap().to_owned()
+                    } else {
+                        serde_json::to_string_pretty(&final_value).unwrap_or_else(|_| format!("{final_value:?}"))
+                    };
+                    return Ok(CallToolResult::success(vec![Content::text(format!(
+                        "Condition met: {output}"
+                    ))]));
+                }
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(interval)).await;
+        }
+
+        let detail = last_error.map(|e| format!(" (last error: {e})")).unwrap_or_default();
+        Err(McpError::internal_error(
+            format!("Condition not met within {timeout}ms{detail}"),
+            None,
+        ))
     }
 
-    #[tool(description = "Hover over an element by CSS selector. Useful for revealing dropdown menus, tooltips, or hover states.")]
-    async fn hover(
+    #[tool(description = "Wait until an element has no running CSS animations or transitions (via getAnimations), up to a timeout. More precise than a fixed settle delay before a screenshot or click. Reports whether it settled — a timeout is not an error.")]
+    async fn wait_for_animation(
         &self,
-        Parameters(HoverParams { selector }): Parameters<HoverParams>,
+        Parameters(WaitForAnimationParams { selector, timeout_ms }): Parameters<WaitForAnimationParams>,
     ) -> Result<CallToolResult, McpError> {
-        let js = js_find_visible_element(&selector);
+        let timeout = timeout_ms.unwrap_or(5000);
+        let start = tokio::time::Instant::now();
+        let deadline = start + std::time::Duration::from_millis(timeout);
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
-            .await
-            .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
+        let js = format!(
+            r#"(() => {{
+                const el = document.querySelector({sel});
+                if (!el) return null;
+                if (typeof el.getAnimations !== 'function') return {{ running: [] }};
+                const running = el.getAnimations()
+                    .filter(a => a.playState === 'running')
+                    .map(a => a.animationName || a.transitionProperty || a.id || 'animation');
+                return {{ running }};
+            }})()"#,
+            sel = serde_json::to_string(&selector).unwrap()
+        );
 
-        let coords = result
-            .get("result")
-            .and_then(|r| r.get("value"));
+        loop {
+            let value = self.eval_value(&js)
+                .await
+                .map_err(|e| McpError::internal_error(format!("Animation check failed: {e}"), None))?;
 
-        match coords {
-            Some(v) if !v.is_null() => {
-                let x = v.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
-                let y = v.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            if value.is_null() {
+                return Err(McpError::invalid_params(format!("Element not found: {selector}"), None));
+            }
 
-                self.execute_reconnect(commands::hover(x, y))
-                    .await
-                    .map_err(|e| McpError::internal_error(format!("Hover failed: {e}"), None))?;
+            let running: Vec<String> = value
+                .get("running")
+                .and_then(|v| v.as_array())
+                .map(|a| a.iter().filter_map(|v| v.as_str().map(|s| s.to_owned())).collect())
+                .unwrap_or_default();
 
-                Ok(CallToolResult::success(vec![Content::text(format!(
-                    "Hovered over '{selector}' at ({x:.0}, {y:.0})"
-                ))]))
+            if running.is_empty() {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Settled: '{selector}' has no running animations ({}ms)",
+                    start.elapsed().as_millis()
+                ))]));
             }
-            _ => Err(McpError::invalid_params(
-                format!("No visible, in-viewport element found for: {selector}"),
-                None,
-            )),
+
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Not settled after {timeout}ms: '{selector}' still running {}",
+                    running.join(", ")
+                ))]));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
         }
     }
 
-    #[tool(description = "Press a keyboard key (Enter, Tab, Escape, ArrowDown, Backspace, Space, etc.). Useful for form submission, navigation, and closing dialogs.")]
-    async fn press_key(
+    #[tool(description = "Wait until every <img> (optionally within a selector) has finished loading — complete with a nonzero naturalWidth, or failed — up to a timeout. Returns loaded vs failed counts and the failed URLs. Offscreen loading=\"lazy\" images are reported separately, not waited for. Use before screenshots of content pages, alongside wait_for_fonts.")]
+    async fn wait_for_images(
         &self,
-        Parameters(PressKeyParams { key }): Parameters<PressKeyParams>,
+        Parameters(WaitForImagesParams { selector, timeout_ms }): Parameters<WaitForImagesParams>,
     ) -> Result<CallToolResult, McpError> {
-        self.execute_seq_reconnect(commands::press_key(&key))
-            .await
-            .map_err(|e| McpError::internal_error(format!("Key press failed: {e}"), None))?;
-
-        Ok(CallToolResult::success(vec![Content::text(format!(
-            "Pressed key: {key}"
-        ))]))
-    }
+        let timeout = timeout_ms.unwrap_or(10000);
+        let start = tokio::time::Instant::now();
+        let deadline = start + std::time::Duration::from_millis(timeout);
 
-    #[tool(description = "Read an attribute value from the first matching element. Useful for getting href, src, data-* attributes, or form values.")]
-    async fn get_attribute(
-        &self,
-        Parameters(GetAttributeParams { selector, attribute }): Parameters<GetAttributeParams>,
-    ) -> Result<CallToolResult, McpError> {
         let js = format!(
             r#"(() => {{
-                const el = document.querySelector({sel});
-                if (!el) return null;
-                return el.getAttribute({attr});
+                const sel = {sel};
+                const root = sel ? document.querySelector(sel) : document;
+                if (!root) return null;
+                const imgs = root instanceof HTMLImageElement ? [root] : Array.from(root.querySelectorAll('img'));
+                let loaded = 0, pending = 0, deferred = 0;
+                const failed = [];
+                for (const img of imgs) {{
+                    if (img.complete) {{
+                        if (img.naturalWidth > 0) loaded++;
+                        else if (img.currentSrc || img.src) failed.push(img.currentSrc || img.src);
+                        else loaded++; // no source — nothing to wait for
+                    }} else if (img.loading === 'lazy') {{
+                        const r = img.getBoundingClientRect();
+                        if (r.bottom < 0 || r.top > innerHeight || r.right < 0 || r.left > innerWidth) deferred++;
+                        else pending++;
+                    }} else pending++;
+                }}
+                return {{ total: imgs.length, loaded, pending, deferred, failed }};
             }})()"#,
-            sel = serde_json::to_string(&selector).unwrap(),
-            attr = serde_json::to_string(&attribute).unwrap()
+            sel = serde_json::to_string(&selector).unwrap()
         );
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
-            .await
-            .map_err(|e| McpError::internal_error(format!("Failed to get attribute: {e}"), None))?;
+        loop {
+            let value = self.eval_value(&js)
+                .await
+                .map_err(|e| McpError::internal_error(format!("Image check failed: {e}"), None))?;
 
-        let value = result
-            .get("result")
-            .and_then(|r| r.get("value"));
+            if value.is_null() {
+                let selector = selector.as_deref().unwrap_or("");
+                return Err(McpError::invalid_params(format!("Element not found: {selector}"), None));
+            }
 
-        match value {
-            Some(v) if !v.is_null() => {
-                let attr_value = v.as_str().unwrap_or("(non-string value)");
-                Ok(CallToolResult::success(vec![Content::text(format!(
-                    "{attribute}=\"{attr_value}\""
-                ))]))
+            let count = |key: &str| value.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+            let (total, loaded, pending, deferred) = (count("total"), count("loaded"), count("pending"), count("deferred"));
+            let failed: Vec<&str> = value
+                .get("failed")
+                .and_then(|v| v.as_array())
+                .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+
+            let timed_out = tokio::time::Instant::now() >= deadline;
+            if pending == 0 || timed_out {
+                let mut output = if pending == 0 {
+                    format!("Images loaded ({}ms): {loaded} of {total} loaded, {} failed", start.elapsed().as_millis(), failed.len())
+                } else {
+                    format!("Still loading after {timeout}ms: {loaded} of {total} loaded, {pending} pending, {} failed", failed.len())
+                };
+                if deferred > 0 {
+                    output.push_str(&format!(", {deferred} lazy offscreen (not waited for)"));
+                }
+                for url in failed.iter().take(20) {
+                    output.push_str(&format!("\n  failed: {url}"));
+                }
+                return Ok(CallToolResult::success(vec![Content::text(output)]));
             }
-            _ => Err(McpError::invalid_params(
-                format!("Element '{selector}' not found or attribute '{attribute}' not present"),
-                None,
-            )),
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
         }
     }
 
-    #[tool(description = "Get the current page URL and title without navigating.")]
-    async fn get_url(&self) -> Result<CallToolResult, McpError> {
-        let result = self.execute_reconnect(
-            commands::evaluate("JSON.stringify({ url: window.location.href, title: document.title })"),
+    #[tool(description = "Record DOM mutations (added/removed nodes, attribute and text changes) for a period, then return a compact summary. Start it, then act in parallel (or let the page update on its own) — answers \"what changed?\" after an action. Keeps recording across a navigation, but only the new document's changes survive it.")]
+    async fn watch_mutations(
+        &self,
+        Parameters(WatchMutationsParams { duration_ms, selector, max_records }): Parameters<WatchMutationsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let duration_ms = duration_ms.unwrap_or(3000).min(60_000);
+        let max_records = max_records.unwrap_or(200).min(2000);
+
+        let result = self.eval_value(&js_watch_mutations(selector.as_deref(), max_records, false))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Watch mutations failed: {e}"), None))?;
+        let rooted = result
+            .get("rooted")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !rooted {
+            let _ = self.execute_reconnect(commands::evaluate(
+                "window.__causeway_mutations && window.__causeway_mutations.observer.disconnect(); delete window.__causeway_mutations",
+            )).await;
+            return Err(McpError::invalid_params(
+                format!("Element not found: {}", selector.as_deref().unwrap_or("")),
+                None,
+            ));
+        }
+
+        // Re-arm on navigation so a page load mid-watch doesn't silently end recording
+        let init_identifier = self.execute_reconnect(commands::add_init_script(&js_watch_mutations(selector.as_deref(), max_records, true)))
+            .await
+            .ok()
+            .and_then(|r| r.get("identifier").and_then(|v| v.as_str()).map(|s| s.to_owned()));
+
+        tokio::time::sleep(std::time::Duration::from_millis(duration_ms)).await;
+
+        if let Some(identifier) = init_identifier.as_deref() {
+            let _ = self.execute_reconnect(commands::remove_init_script(identifier)).await;
+        }
+
+        let value = self.eval_value(
+            r#"(() => {
+                const m = window.__causeway_mutations;
+                if (!m) return null;
+                m.observer.disconnect();
+                delete window.__causeway_mutations;
+                return { records: m.records, dropped: m.dropped, fromInit: m.fromInit };
+            })()"#,
         )
         .await
-        .map_err(|e| McpError::internal_error(format!("Get URL failed: {e}"), None))?;
+        .map_err(|e| McpError::internal_error(format!("Read mutations failed: {e}"), None))?;
 
-        let value = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("{}");
+        if value.is_null() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Recording lost — the page navigated and the observer wasn't re-armed".to_owned(),
+            )]));
+        }
+        let records = value.get("records").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        let dropped = value.get("dropped").and_then(|v| v.as_u64()).unwrap_or(0);
+        let navigated = value.get("fromInit").and_then(|v| v.as_bool()).unwrap_or(false);
 
-        let parsed: serde_json::Value = serde_json::from_str(value).unwrap_or_default();
-        let url = parsed.get("url").and_then(|v| v.as_str()).unwrap_or("(unknown)");
-        let title = parsed.get("title").and_then(|v| v.as_str()).unwrap_or("(unknown)");
+        let str_of = |r: &serde_json::Value, key: &str| -> String {
+            r.get(key).and_then(|v| v.as_str()).unwrap_or("").to_owned()
+        };
+        let quoted = |v: Option<&serde_json::Value>| -> String {
+            match v.and_then(|v| v.as_str()) {
+                Some(s) => serde_json::to_string(s).unwrap(),
+                None => "null".to_owned(),
+            }
+        };
 
-        Ok(CallToolResult::success(vec![Content::text(format!(
-            "URL: {url}\nTitle: {title}"
-        ))]))
+        let mut counts = [0usize; 4];
+        let lines: Vec<String> = records
+            .iter()
+            .map(|r| match r.get("kind").and_then(|v| v.as_str()).unwrap_or("") {
+                "added" => {
+                    counts[0] += 1;
+                    format!("+ {} (in {})", str_of(r, "node"), str_of(r, "target"))
+                }
+                "removed" => {
+                    counts[1] += 1;
+                    format!("- {} (from {})", str_of(r, "node"), str_of(r, "target"))
+                }
+                "attribute" => {
+                    counts[2] += 1;
+                    format!(
+                        "~ {} [{}]: {} → {}",
+                        str_of(r, "target"), str_of(r, "attr"), quoted(r.get("old")), quoted(r.get("new"))
+                    )
+                }
+                _ => {
+                    counts[3] += 1;
+                    format!("✎ {}: {} → {}", str_of(r, "target"), quoted(r.get("old")), quoted(r.get("new")))
+                }
+            })
+            .collect();
+
+        let mut output = format!(
+            "{} mutation(s) in {duration_ms}ms: {} added, {} removed, {} attribute, {} text",
+            records.len() as u64 + dropped, counts[0], counts[1], counts[2], counts[3]
+        );
+        if dropped > 0 {
+            output.push_str(&format!(" ({dropped} more not recorded — cap {max_records})"));
+        }
+        if navigated {
+            output.push_str("\nPage navigated during the watch — showing changes in the new document only");
+        }
+        if !lines.is_empty() {
+            output.push('\n');
+            output.push_str(&lines.join("\n"));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[tool(description = "Wait for a page navigation to complete (e.g. after clicking a link). Detects both full page loads (via CDP events) and SPA navigations (via History API interception). Returns immediately on detection rather than waiting for timeout.")]
-    async fn wait_for_navigation(
+    #[tool(description = "Scroll the page by a given number of pixels. Pass instant=true on pages with smooth scrolling so the scroll has finished before a following screenshot.")]
+    async fn scroll(
         &self,
-        Parameters(WaitForNavigationParams { timeout_ms }): Parameters<WaitForNavigationParams>,
+        Parameters(ScrollParams { x, y, instant, restore }): Parameters<ScrollParams>,
     ) -> Result<CallToolResult, McpError> {
-        let timeout = timeout_ms.unwrap_or(10000);
+        let scroll_x = x.unwrap_or(0.0);
+        let scroll_y = y.unwrap_or(0.0);
 
-        // Read pre-click URL + title snapshot (set by click/click_text/submit_form)
-        let (orig_url, orig_title) = self.pre_nav_snapshot.lock().await.clone();
+        let override_state = if instant.unwrap_or(false) {
+            Some(self.eval_value(&js_instant_scroll(true))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Scroll-behavior override failed: {e}"), None))?)
+        } else {
+            None
+        };
 
-        // Inject SPA interceptors: patch pushState/replaceState, listen for popstate/hashchange
-        let setup_js = r#"(() => {
-            window.__causeway_nav = null;
-            const origPush = history.pushState;
-            const origReplace = history.replaceState;
-            function done(type) {
-                window.__causeway_nav = { type, url: location.href };
-                history.pushState = origPush;
-                history.replaceState = origReplace;
-                window.removeEventListener('popstate', onNav);
-                window.removeEventListener('hashchange', onNav);
+        self.execute_reconnect(commands::scroll(scroll_x, scroll_y))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Scroll failed: {e}"), None))?;
+
+        let mut output = format!("Scrolled by ({scroll_x:.0}, {scroll_y:.0})");
+        if let Some(state) = override_state {
+            let page = state.get("page").and_then(|v| v.as_str()).unwrap_or("auto");
+            let was_active = state.get("was_active").and_then(|v| v.as_bool()).unwrap_or(false);
+            // Leave an override set by set_instant_scroll alone
+            let kept = was_active || !restore.unwrap_or(true);
+            if !kept {
+                let _ = self.eval_value(&js_instant_scroll(false)).await;
             }
-            history.pushState = function(...a) { origPush.apply(this, a); done('pushState'); };
-            history.replaceState = function(...a) { origReplace.apply(this, a); done('replaceState'); };
-            const onNav = (e) => done(e.type);
-            window.addEventListener('popstate', onNav);
-            window.addEventListener('hashchange', onNav);
-        })()"#;
-        self.execute_reconnect(commands::evaluate(setup_js)).await.ok();
+            output.push_str(&format!(
+                " instantly (page scroll-behavior: {page}; override {})",
+                if kept { "still active" } else { "removed" }
+            ));
+        }
 
-        // Subscribe to CDP events for full navigations
-        let mut receiver = {
-            let conn = self.live.get().await.ok_or(McpError::internal_error("Not connected", None))?;
-            cdp::subscribe_events(&*conn)
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Force instant scrolling on the current page by injecting `html { scroll-behavior: auto }` (overriding smooth scrolling), or remove the override. Prevents screenshots being taken mid-scroll after scroll or scrollIntoView-based tools. Lasts until navigation.")]
+    async fn set_instant_scroll(
+        &self,
+        Parameters(SetInstantScrollParams { enabled }): Parameters<SetInstantScrollParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let enabled = enabled.unwrap_or(true);
+        let state = self.eval_value(&js_instant_scroll(enabled))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Scroll-behavior override failed: {e}"), None))?;
+
+        let page = state.get("page").and_then(|v| v.as_str()).unwrap_or("auto");
+        let was_active = state.get("was_active").and_then(|v| v.as_bool()).unwrap_or(false);
+        let output = match (enabled, was_active) {
+            (true, _) => format!("Instant scroll override applied — page scroll-behavior was '{page}', now forced to 'auto'"),
+            (false, true) => format!("Instant scroll override removed — page scroll-behavior is back to '{page}'"),
+            (false, false) => format!("No instant scroll override was active — page scroll-behavior is '{page}'"),
         };
 
-        let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(timeout);
-        let mut frame_navigated = false;
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
 
-        loop {
-            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
-            if remaining.is_zero() { break; }
+    #[tool(description = "Check whether the current page is a CAPTCHA or bot wall (Cloudflare challenge, reCAPTCHA, hCaptcha, \"verify you are human\" text, etc.). Returns the markers detected, if any. Use this when a page isn't behaving as expected, so you can stop and ask a human instead of looping.")]
+    async fn detect_bot_wall(&self) -> Result<CallToolResult, McpError> {
+        let markers: Vec<serde_json::Value> = BOT_WALL_MARKERS
+            .iter()
+            .map(|(label, kind, pattern)| serde_json::json!({ "label": label, "kind": kind, "pattern": pattern }))
+            .collect();
+        let js = format!(
+            r#"(() => {{
+                const markers = {markers};
+                const text = (document.body ? document.body.innerText : '').toLowerCase();
+                const title = document.title.toLowerCase();
+                const hits = [];
+                for (const m of markers) {{
+                    let hit = false;
+                    try {{
+                        if (m.kind === 'selector') hit = document.querySelector(m.pattern) !== null;
+                        else if (m.kind === 'text') hit = text.includes(m.pattern);
+                        else if (m.kind === 'title') hit = title.includes(m.pattern);
+                    }} catch (e) {{}}
+                    if (hit) hits.push(m.label + ' (' + m.kind + ': ' + m.pattern + ')');
+                }}
+                return hits;
+            }})()"#,
+            markers = serde_json::Value::Array(markers)
+        );
 
-            // Wait for CDP events in 200ms windows, then poll JS + URL snapshot
-            let wait = std::cmp::min(remaining, std::time::Duration::from_millis(200));
-            match tokio::time::timeout(wait, receiver.recv()).await {
-                Ok(Ok(event)) => match event.method.as_str() {
-                    "Page.navigatedWithinDocument" => {
-                        let url = event.params.get("url")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("(unknown)");
-                        return Ok(CallToolResult::success(vec![Content::text(format!(
-                            "SPA navigation: {url}"
-                        ))]));
-                    }
-                    "Page.frameNavigated" => {
-                        let is_main = event.params
-                            .get("frame")
-                            .and_then(|f| f.get("parentId"))
-                            .is_none();
-                        if is_main { frame_navigated = true; }
-                    }
-                    "Page.loadEventFired" if frame_navigated => {
-                        let title = self.execute_reconnect(commands::evaluate("document.title"))
-                            .await.ok()
-                            .and_then(|r| r.get("result")?.get("value")?.as_str().map(|s| s.to_owned()))
-                            .unwrap_or_else(|| "(unknown)".to_owned());
-                        return Ok(CallToolResult::success(vec![Content::text(format!(
-                            "Page loaded: {title}"
-                        ))]));
-                    }
-                    _ => {}
-                },
-                Ok(Err(tokio::sync::broadcast::error::RecvError::Lagged(_))) => continue,
-                Ok(Err(tokio::sync::broadcast::error::RecvError::Closed)) => {
-                    // Connection dropped — likely a full navigation destroyed context.
-                    // Wait briefly for the new page to settle, then check URL.
-                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-                    let title = self.execute_reconnect(commands::evaluate("document.title"))
-                        .await.ok()
-                        .and_then(|r| r.get("result")?.get("value")?.as_str().map(|s| s.to_owned()))
-                        .unwrap_or_else(|| "(unknown)".to_owned());
-                    return Ok(CallToolResult::success(vec![Content::text(format!(
-                        "Page loaded: {title}"
-                    ))]));
-                }
-                Err(_) => {
-                    // 200ms window expired — poll JS SPA interceptors + URL change
-                    let poll_js = r#"JSON.stringify({
-                        nav: window.__causeway_nav,
-                        url: location.href,
-                        title: document.title
-                    })"#;
-                    if let Ok(result) = self.execute_reconnect(commands::evaluate(poll_js)).await {
-                        let raw = result.get("result")
-                            .and_then(|r| r.get("value"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("{}");
-                        if let Ok(poll) = serde_json::from_str::<serde_json::Value>(raw) {
-                            // Check SPA interceptor
-                            if let Some(nav) = poll.get("nav").filter(|v| !v.is_null()) {
-                                let nav_type = nav.get("type").and_then(|v| v.as_str()).unwrap_or("spa");
-                                let url = nav.get("url").and_then(|v| v.as_str()).unwrap_or("(unknown)");
-                                return Ok(CallToolResult::success(vec![Content::text(format!(
-                                    "SPA navigation ({nav_type}): {url}"
-                                ))]));
-                            }
-                            // Check URL/title change (catches frameworks that bypass History API)
-                            let cur_url = poll.get("url").and_then(|v| v.as_str()).unwrap_or("");
-                            let cur_title = poll.get("title").and_then(|v| v.as_str()).unwrap_or("");
-                            if cur_url != orig_url || cur_title != orig_title {
-                                return Ok(CallToolResult::success(vec![Content::text(format!(
-                                    "Page changed: {cur_title}"
-                                ))]));
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let result = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Bot wall check failed: {e}"), None))?;
 
-        // Clean up interceptors
-        self.execute_reconnect(commands::evaluate(
-            "delete window.__causeway_nav"
-        )).await.ok();
+        let hits: Vec<String> = result
+            .as_array()
+            .map(|a| a.iter().filter_map(|v| v.as_str().map(|s| s.to_owned())).collect())
+            .unwrap_or_default();
 
-        // Fallback: if we saw frameNavigated but missed loadEventFired
-        if frame_navigated {
-            let title = self.execute_reconnect(commands::evaluate("document.title"))
-                .await.ok()
-                .and_then(|r| r.get("result")?.get("value")?.as_str().map(|s| s.to_owned()))
-                .unwrap_or_else(|| "(unknown)".to_owned());
-            return Ok(CallToolResult::success(vec![Content::text(format!(
-                "Page loaded: {title}"
-            ))]));
+        if hits.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No bot wall detected".to_owned(),
+            )]));
         }
 
-        // Soft timeout — don't error, just inform
-        let title = self.execute_reconnect(commands::evaluate("document.title"))
-            .await.ok()
-            .and_then(|r| r.get("result")?.get("value")?.as_str().map(|s| s.to_owned()))
-            .unwrap_or_else(|| "(unknown)".to_owned());
         Ok(CallToolResult::success(vec![Content::text(format!(
-            "No navigation detected within {timeout}ms (current page: {title})"
+            "Bot wall detected — {} marker(s):\n{}",
+            hits.len(),
+            hits.join("\n")
         ))]))
     }
 
-    #[tool(description = "Navigate back in browser history.")]
-    async fn back(&self) -> Result<CallToolResult, McpError> {
-        self.nav_history_step(-1).await
-    }
-
-    #[tool(description = "Navigate forward in browser history.")]
-    async fn forward(&self) -> Result<CallToolResult, McpError> {
-        self.nav_history_step(1).await
-    }
+    #[tool(description = "Get the current scroll position, document size, and viewport size, plus whether the page is at the top or bottom. Call before scroll to know how far you can go.")]
+    async fn get_scroll_info(&self) -> Result<CallToolResult, McpError> {
+        let js = r#"(() => {
+            const doc = document.scrollingElement || document.documentElement;
+            const maxY = Math.max(0, doc.scrollHeight - window.innerHeight);
+            return {
+                scrollX: Math.round(window.scrollX),
+                scrollY: Math.round(window.scrollY),
+                scrollWidth: doc.scrollWidth,
+                scrollHeight: doc.scrollHeight,
+                innerWidth: window.innerWidth,
+                innerHeight: window.innerHeight,
+                atTop: window.scrollY <= 1,
+                atBottom: window.scrollY >= maxY - 1,
+            };
+        })()"#;
 
-    /// Navigate back (delta = -1) or forward (delta = +1) using CDP history API.
-    /// Page.navigateToHistoryEntry returns after navigation commits — no sleep needed.
-    async fn nav_history_step(&self, delta: i64) -> Result<CallToolResult, McpError> {
-        let history = self.execute_reconnect(commands::get_navigation_history())
+        let value = self.eval_value(js)
             .await
-            .map_err(|e| McpError::internal_error(format!("History fetch failed: {e}"), None))?;
-
-        let current_index = history.get("currentIndex").and_then(|v| v.as_i64()).unwrap_or(0);
-        let entries = history
-            .get("entries")
-            .and_then(|v| v.as_array())
-            .ok_or_else(|| McpError::internal_error("No navigation history".to_owned(), None))?;
+            .map_err(|e| McpError::internal_error(format!("Get scroll info failed: {e}"), None))?;
 
-        let target_index = current_index + delta;
-        if target_index < 0 || target_index >= entries.len() as i64 {
-            let dir = if delta < 0 { "beginning" } else { "end" };
-            return Ok(CallToolResult::success(vec![Content::text(format!(
-                "Already at the {dir} of history"
-            ))]));
-        }
+        let output = serde_json::to_string_pretty(&value)
+            .unwrap_or_else(|_| format!("{value:?}"));
 
-        let entry_id = entries[target_index as usize]
-            .get("id")
-            .and_then(|v| v.as_i64())
-            .ok_or_else(|| McpError::internal_error("Invalid history entry".to_owned(), None))?;
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
 
-        // navigateToHistoryEntry returns after navigation commits — then wait for load.
-        self.execute_reconnect(commands::navigate_to_history_entry(entry_id))
-            .await
-            .map_err(|e| McpError::internal_error(format!("Navigation failed: {e}"), None))?;
+    #[tool(description = "Scroll with a synthesized input gesture (Input.synthesizeScrollGesture) — realistic, optionally with fling momentum. Use instead of scroll when testing scroll-snap, lazy-load thresholds, or scroll-driven animations. Waits for the gesture to finish.")]
+    async fn smooth_scroll(
+        &self,
+        Parameters(SmoothScrollParams { direction, distance, speed, momentum, selector, x, y }): Parameters<SmoothScrollParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let direction = direction.unwrap_or_else(|| "down".to_owned());
+        let distance = distance.unwrap_or(500.0).abs();
+        // Gesture distances follow the finger: dragging up scrolls the page down
+        let (dx, dy) = match direction.as_str() {
+            "down" => (0.0, -distance),
+            "up" => (0.0, distance),
+            "right" => (-distance, 0.0),
+            "left" => (distance, 0.0),
+            other => return Err(McpError::invalid_params(
+                format!("Unknown direction \"{other}\" — use down, up, left, or right"),
+                None,
+            )),
+        };
 
-        let _ = self.execute_reconnect(commands::evaluate(
-            "new Promise(resolve => {
-                if (document.readyState === 'complete') { resolve(); return; }
-                window.addEventListener('load', () => resolve(), { once: true });
-                setTimeout(resolve, 8000);
-            })",
-        ))
-        .await;
+        let (ox, oy) = match (&selector, x, y) {
+            (Some(sel), _, _) => self.element_center(sel).await?,
+            (None, Some(x), Some(y)) => (x, y),
+            _ => {
+                let dims = self.eval_value("[window.innerWidth / 2, window.innerHeight / 2]")
+                    .await
+                    .ok()
+                    .and_then(|v| v.as_array().cloned())
+                    .unwrap_or_default();
+                (
+                    dims.first().and_then(|v| v.as_f64()).unwrap_or(400.0),
+                    dims.get(1).and_then(|v| v.as_f64()).unwrap_or(300.0),
+                )
+            }
+        };
+
+        let speed = speed.unwrap_or(800).max(1);
+        self.execute_reconnect(commands::synthesize_scroll(ox, oy, dx, dy, speed, !momentum.unwrap_or(false)))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Smooth scroll failed: {e}"), None))?;
 
-        let dir = if delta < 0 { "back" } else { "forward" };
         Ok(CallToolResult::success(vec![Content::text(format!(
-            "Navigated {dir}"
+            "Smooth-scrolled {direction} {distance:.0}px at {speed}px/s from ({ox:.0}, {oy:.0})"
         ))]))
     }
 
-    #[tool(description = "Select an option in a <select> dropdown by its value attribute.")]
-    async fn select_option(
+    #[tool(description = "Scroll inside a specific element by dispatching a mouse wheel event at its center. Unlike scroll (window.scrollBy), this reaches inner overflow containers, maps, and canvas-based scroll zones.")]
+    async fn scroll_element(
         &self,
-        Parameters(SelectOptionParams { selector, value }): Parameters<SelectOptionParams>,
+        Parameters(ScrollElementParams { selector, delta_x, delta_y }): Parameters<ScrollElementParams>,
     ) -> Result<CallToolResult, McpError> {
-        let js = format!(
-            r#"(() => {{
-                const el = document.querySelector({sel});
-                if (!el) return "not_found";
-                el.value = {val};
-                el.dispatchEvent(new Event('change', {{ bubbles: true }}));
-                return "ok";
-            }})()"#,
-            sel = serde_json::to_string(&selector).unwrap(),
-            val = serde_json::to_string(&value).unwrap()
-        );
+        let dx = delta_x.unwrap_or(0.0);
+        let dy = delta_y.unwrap_or(0.0);
+        let js = js_find_visible_element(&selector);
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let coords = self.eval_value(&js)
             .await
-            .map_err(|e| McpError::internal_error(format!("Select failed: {e}"), None))?;
-
-        let status = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
+            .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
 
-        if status == "not_found" {
+        if coords.is_null() {
             return Err(McpError::invalid_params(
-                format!("Select element not found: {selector}"),
+                format!("No visible, in-viewport element found for: {selector}"),
                 None,
             ));
         }
 
+        let x = coords.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let y = coords.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
+
+        self.execute_seq_reconnect(vec![commands::hover(x, y), commands::mouse_wheel(x, y, dx, dy)])
+            .await
+            .map_err(|e| McpError::internal_error(format!("Wheel scroll failed: {e}"), None))?;
+
         Ok(CallToolResult::success(vec![Content::text(format!(
-            "Selected value '{value}' in '{selector}'"
+            "Wheel-scrolled '{selector}' by ({dx:.0}, {dy:.0}) at ({x:.0}, {y:.0})"
         ))]))
     }
 
-    #[tool(description = "Toggle a checkbox or radio button by its label text, name, or nearby text. Works via JavaScript — no mouse simulation — so it handles hidden inputs, custom styled controls, and Material Design components that ignore click events. Use this instead of click/click_text when targeting checkboxes or radio buttons.")]
-    async fn toggle(
+    #[tool(description = "Tap an element with a touch event (touchStart + touchEnd) instead of a mouse click. For mobile-emulated pages whose handlers only respond to touch. Pairs with emulate_device.")]
+    async fn tap(
         &self,
-        Parameters(ToggleParams { label, checked }): Parameters<ToggleParams>,
+        Parameters(TapParams { selector }): Parameters<TapParams>,
     ) -> Result<CallToolResult, McpError> {
-        let force_state = match checked {
-            Some(true) => "true",
-            Some(false) => "false",
-            None => "null",
-        };
-
-        let js = format!(
-            r#"(() => {{
-                const search = {label}.toLowerCase();
-                const force = {force};
+        let (x, y) = self.element_center(&selector).await?;
 
-                // Strategy 1: find input by label[for] text match
-                for (const lbl of document.querySelectorAll('label')) {{
-                    if (!lbl.textContent.trim().toLowerCase().includes(search)) continue;
-                    const forId = lbl.getAttribute('for');
-                    const input = forId ? document.getElementById(forId)
-                        : lbl.querySelector('input[type="checkbox"], input[type="radio"]');
-                    if (input) {{
-                        if (force !== null) input.checked = force;
-                        else input.checked = !input.checked;
-                        input.dispatchEvent(new Event('input', {{ bubbles: true }}));
-                        input.dispatchEvent(new Event('change', {{ bubbles: true }}));
-                        return {{ found: true, label: lbl.textContent.trim().substring(0, 80), checked: input.checked, method: 'label' }};
-                    }}
-                }}
+        self.snapshot_pre_nav().await;
+        self.execute_seq_reconnect(commands::tap(x, y))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Tap failed: {e}"), None))?;
 
-                // Strategy 2: find input whose name/id/value contains search text
-                for (const input of document.querySelectorAll('input[type="checkbox"], input[type="radio"]')) {{
-                    const haystack = [input.name, input.id, input.value,
-                        input.getAttribute('aria-label') || ''].join(' ').toLowerCase();
-                    if (!haystack.includes(search)) continue;
-                    if (force !== null) input.checked = force;
-                    else input.checked = !input.checked;
-                    input.dispatchEvent(new Event('input', {{ bubbles: true }}));
-                    input.dispatchEvent(new Event('change', {{ bubbles: true }}));
-                    return {{ found: true, label: input.name || input.id || input.value, checked: input.checked, method: 'input-attr' }};
-                }}
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Tapped '{selector}' at ({x:.0}, {y:.0})"
+        ))]))
+    }
 
-                // Strategy 3: find any element with role=checkbox or role=radio
-                for (const el of document.querySelectorAll('[role="checkbox"], [role="radio"], [role="switch"]')) {{
-                    const elText = (el.textContent || el.getAttribute('aria-label') || '').trim().toLowerCase();
-                    if (!elText.includes(search)) continue;
-                    const isChecked = el.getAttribute('aria-checked') === 'true';
-                    const newState = force !== null ? force : !isChecked;
-                    el.setAttribute('aria-checked', String(newState));
-                    el.click();
-                    return {{ found: true, label: elText.substring(0, 80), checked: newState, method: 'aria-role' }};
-                }}
+    #[tool(description = "Swipe with one finger from a start point to an end point, with interpolated touchMove events. Points come from coordinates or from element centers (from_selector / to_selector). For carousels, pull-to-refresh, and swipe-to-dismiss on mobile-emulated pages.")]
+    async fn swipe(
+        &self,
+        Parameters(SwipeParams { start_x, start_y, end_x, end_y, from_selector, to_selector, steps, duration_ms }): Parameters<SwipeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let (sx, sy) = match (&from_selector, start_x, start_y) {
+            (Some(sel), _, _) => self.element_center(sel).await?,
+            (None, Some(x), Some(y)) => (x, y),
+            _ => return Err(McpError::invalid_params("Provide from_selector or start_x + start_y".to_owned(), None)),
+        };
+        let (ex, ey) = match (&to_selector, end_x, end_y) {
+            (Some(sel), _, _) => self.element_center(sel).await?,
+            (None, Some(x), Some(y)) => (x, y),
+            _ => return Err(McpError::invalid_params("Provide to_selector or end_x + end_y".to_owned(), None)),
+        };
 
-                // Strategy 4: walk text nodes and find nearest input
-                const walker = document.createTreeWalker(document.body, NodeFilter.SHOW_TEXT);
-                while (walker.nextNode()) {{
-                    if (!walker.currentNode.textContent.trim().toLowerCase().includes(search)) continue;
-                    const parent = walker.currentNode.parentElement;
-                    if (!parent) continue;
-                    const container = parent.closest('label, div, li, span, td, fieldset');
-                    if (!container) continue;
-                    const input = container.querySelector('input[type="checkbox"], input[type="radio"]');
-                    if (input) {{
-                        if (force !== null) input.checked = force;
-                        else input.checked = !input.checked;
-                        input.dispatchEvent(new Event('input', {{ bubbles: true }}));
-                        input.dispatchEvent(new Event('change', {{ bubbles: true }}));
-                        return {{ found: true, label: walker.currentNode.textContent.trim().substring(0, 80), checked: input.checked, method: 'nearby-text' }};
-                    }}
-                }}
+        let steps = steps.unwrap_or(10).max(1);
+        let pause = std::time::Duration::from_millis(duration_ms.unwrap_or(300) / steps as u64);
 
-                return {{ found: false }};
-            }})()"#,
-            label = serde_json::to_string(&label).unwrap(),
-            force = force_state,
-        );
+        let send = |cmd: (&'static str, serde_json::Value)| async move {
+            self.execute_reconnect(cmd)
+                .await
+                .map_err(|e| McpError::internal_error(format!("Swipe failed: {e}"), None))
+        };
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
-            .await
-            .map_err(|e| McpError::internal_error(format!("Toggle failed: {e}"), None))?;
+        send(commands::touch_event("touchStart", &[(sx, sy)])).await?;
+        for i in 1..=steps {
+            let t = i as f64 / steps as f64;
+            tokio::time::sleep(pause).await;
+            send(commands::touch_event("touchMove", &[(sx + (ex - sx) * t, sy + (ey - sy) * t)])).await?;
+        }
+        send(commands::touch_event("touchEnd", &[])).await?;
 
-        let val = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .ok_or_else(|| McpError::internal_error("No result from toggle".to_owned(), None))?;
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Swiped ({sx:.0}, {sy:.0}) → ({ex:.0}, {ey:.0}) in {steps} steps"
+        ))]))
+    }
 
-        let found = val.get("found").and_then(|v| v.as_bool()).unwrap_or(false);
-        if !found {
-            return Err(McpError::invalid_params(
-                format!("No checkbox or radio button found matching: \"{label}\""),
-                None,
-            ));
+    #[tool(description = "Pinch-zoom around an element or point (two-finger gesture). scale > 1 zooms in, < 1 zooms out. Uses Input.synthesizePinchGesture, falling back to raw two-point touch events. For maps and image viewers on mobile-emulated pages. Returns the visual viewport scale afterwards.")]
+    async fn pinch(
+        &self,
+        Parameters(PinchParams { selector, x, y, scale }): Parameters<PinchParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(McpError::invalid_params(format!("scale must be a positive number, got {scale}"), None));
         }
+        let (cx, cy) = match (&selector, x, y) {
+            (Some(sel), _, _) => self.element_center(sel).await?,
+            (None, Some(x), Some(y)) => (x, y),
+            _ => return Err(McpError::invalid_params("Provide selector or x + y".to_owned(), None)),
+        };
 
-        let matched_label = val.get("label").and_then(|v| v.as_str()).unwrap_or("(unknown)");
-        let is_checked = val.get("checked").and_then(|v| v.as_bool()).unwrap_or(false);
-        let method = val.get("method").and_then(|v| v.as_str()).unwrap_or("unknown");
-        let state = if is_checked { "checked" } else { "unchecked" };
+        let method = match self.execute_reconnect(commands::synthesize_pinch(cx, cy, scale)).await {
+            Ok(_) => "synthesized",
+            Err(e) => {
+                // Fallback: two fingers moving apart/together along the horizontal axis
+                tracing::debug!("synthesizePinchGesture unavailable ({e}), falling back to touch events");
+                let start_gap = 50.0;
+                let end_gap = start_gap * scale;
+                let steps = 10;
+                let mut commands_list = vec![commands::touch_event("touchStart", &[(cx - start_gap, cy), (cx + start_gap, cy)])];
+                for i in 1..=steps {
+                    let gap = start_gap + (end_gap - start_gap) * (i as f64 / steps as f64);
+                    commands_list.push(commands::touch_event("touchMove", &[(cx - gap, cy), (cx + gap, cy)]));
+                }
+                commands_list.push(commands::touch_event("touchEnd", &[]));
+                self.execute_seq_reconnect(commands_list)
+                    .await
+                    .map_err(|e| McpError::internal_error(format!("Pinch failed: {e}"), None))?;
+                "touch events"
+            }
+        };
+
+        let applied = self.eval_value("window.visualViewport ? window.visualViewport.scale : 1")
+            .await
+            .ok()
+            .and_then(|v| v.as_f64())
+            .unwrap_or(1.0);
 
         Ok(CallToolResult::success(vec![Content::text(format!(
-            "Toggled \"{matched_label}\" → {state} (via {method})"
+            "Pinched ×{scale} at ({cx:.0}, {cy:.0}) via {method} — visual viewport scale now {applied:.2}"
         ))]))
     }
 
-    #[tool(description = "Submit a form element by CSS selector.")]
-    async fn submit_form(
+    #[tool(description = "Hover over an element by CSS selector. Useful for revealing dropdown menus, tooltips, or hover states.")]
+    async fn hover(
         &self,
-        Parameters(ClickParams { selector }): Parameters<ClickParams>,
+        Parameters(HoverParams { selector }): Parameters<HoverParams>,
     ) -> Result<CallToolResult, McpError> {
-        let js = format!(
-            r#"(() => {{
-                const el = document.querySelector({sel});
-                if (!el) return "not_found";
-                if (el.tagName === 'FORM') {{
-                    el.submit();
-                }} else {{
-                    const form = el.closest('form');
-                    if (form) form.submit();
-                    else return "no_form";
-                }}
-                return "ok";
-            }})()"#,
-            sel = serde_json::to_string(&selector).unwrap()
-        );
+        let js = js_find_visible_element(&selector);
 
-        self.snapshot_pre_nav().await;
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let coords = self.eval_value(&js)
             .await
-            .map_err(|e| McpError::internal_error(format!("Submit failed: {e}"), None))?;
+            .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
 
-        let status = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
+        match coords {
+            v if !v.is_null() => {
+                let x = v.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                let y = v.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
 
-        match status {
-            "not_found" => Err(McpError::invalid_params(
-                format!("Element not found: {selector}"),
-                None,
-            )),
-            "no_form" => Err(McpError::invalid_params(
-                format!("No parent form found for: {selector}"),
+                self.execute_reconnect(commands::hover(x, y))
+                    .await
+                    .map_err(|e| McpError::internal_error(format!("Hover failed: {e}"), None))?;
+
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Hovered over '{selector}' at ({x:.0}, {y:.0})"
+                ))]))
+            }
+            _ => Err(McpError::invalid_params(
+                format!("No visible, in-viewport element found for: {selector}"),
                 None,
             )),
-            _ => Ok(CallToolResult::success(vec![Content::text(
-                "Form submitted".to_owned(),
-            )])),
         }
     }
 
-    #[tool(description = "Discover WebMCP tools declared by the current page via navigator.modelContext. Returns structured tool definitions if the page exposes any.")]
-    async fn discover_webmcp_tools(&self) -> Result<CallToolResult, McpError> {
-        let js = r#"(() => {
-            if (!navigator.modelContext) return { supported: false };
-            const tools = navigator.modelContext.tools || [];
-            return {
-                supported: true,
-                tools: tools.map(t => ({
-                    name: t.name,
-                    description: t.description,
-                    inputSchema: t.inputSchema,
-                }))
-            };
-        })()"#;
+    #[tool(description = "Move the mouse to raw viewport coordinates. Fires mousemove/mouseover handlers at that point. Use to dismiss tooltips or trigger hover effects where no selector applies — hover is the selector-based equivalent.")]
+    async fn move_mouse(
+        &self,
+        Parameters(MoveMouseParams { x, y }): Parameters<MoveMouseParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if !x.is_finite() || !y.is_finite() {
+            return Err(McpError::invalid_params("Coordinates must be finite numbers".to_owned(), None));
+        }
 
-        let result = self.execute_reconnect(commands::evaluate(js))
+        self.execute_reconnect(commands::hover(x, y))
             .await
-            .map_err(|e| McpError::internal_error(format!("WebMCP check failed: {e}"), None))?;
+            .map_err(|e| McpError::internal_error(format!("Mouse move failed: {e}"), None))?;
 
-        let value = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .cloned()
-            .unwrap_or(serde_json::Value::Null);
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Moved mouse to ({x:.0}, {y:.0})"
+        ))]))
+    }
 
-        let supported = value
-            .get("supported")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
+    #[tool(description = "Press a keyboard key (Enter, Tab, Escape, ArrowDown, Backspace, Space, etc.). Useful for form submission, navigation, and closing dialogs.")]
+    async fn press_key(
+        &self,
+        Parameters(PressKeyParams { key }): Parameters<PressKeyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.execute_seq_reconnect(commands::press_key(&key))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Key press failed: {e}"), None))?;
 
-        if !supported {
-            return Ok(CallToolResult::success(vec![Content::text(
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Pressed key: {key}"
+        ))]))
+    }
+
+    #[tool(description = "Press and hold a key for a duration, then release it — keyDown, wait, keyUp — optionally with auto-repeat keyDowns in between. For hold-to-activate controls, games, and key-repeat behavior; press_key releases immediately.")]
+    async fn hold_key(
+        &self,
+        Parameters(HoldKeyParams { key, duration_ms, repeat }): Parameters<HoldKeyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        const REPEAT_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+        const REPEAT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+        let duration = std::time::Duration::from_millis(duration_ms.unwrap_or(1000).min(30_000));
+
+        self.execute_reconnect(commands::named_key_event("keyDown", &key, false))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Key down failed: {e}"), None))?;
+        let start = tokio::time::Instant::now();
+        let release_at = start + duration;
+
+        // Always release, even if a repeat fails — a stuck key breaks every later input
+        let mut repeats = 0u32;
+        let mut repeat_error = None;
+        if repeat.unwrap_or(false) {
+            let mut next = start + REPEAT_DELAY;
+            while next < release_at {
+                tokio::time::sleep_until(next).await;
+                if let Err(e) = self.execute_reconnect(commands::named_key_event("keyDown", &key, true)).await {
+                    repeat_error = Some(e);
+                    break;
+                }
+                repeats += 1;
+                next += REPEAT_INTERVAL;
+            }
+        }
+        tokio::time::sleep_until(release_at).await;
+
+        self.execute_reconnect(commands::named_key_event("keyUp", &key, false))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Key up failed: {e}"), None))?;
+        if let Some(e) = repeat_error {
+            return Err(McpError::internal_error(format!("Key repeat failed (key released): {e}"), None));
+        }
+
+        let held_ms = start.elapsed().as_millis();
+        Ok(CallToolResult::success(vec![Content::text(if repeats > 0 {
+            format!("Held {key} for {held_ms}ms ({repeats} auto-repeat events)")
+        } else {
+            format!("Held {key} for {held_ms}ms")
+        })]))
+    }
+
+    #[tool(description = "Read an attribute value from the first matching element. Useful for getting href, src, data-* attributes, or form values.")]
+    async fn get_attribute(
+        &self,
+        Parameters(GetAttributeParams { selector, attribute }): Parameters<GetAttributeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                const el = document.querySelector({sel});
+                if (!el) return null;
+                return el.getAttribute({attr});
+            }})()"#,
+            sel = serde_json::to_string(&selector).unwrap(),
+            attr = serde_json::to_string(&attribute).unwrap()
+        );
+
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to get attribute: {e}"), None))?;
+
+        match value {
+            v if !v.is_null() => {
+                let attr_value = v.as_str().unwrap_or("(non-string value)");
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "{attribute}=\"{attr_value}\""
+                ))]))
+            }
+            _ => Err(McpError::invalid_params(
+                format!("Element '{selector}' not found or attribute '{attribute}' not present"),
+                None,
+            )),
+        }
+    }
+
+    #[tool(description = "Get the current page URL and title without navigating.")]
+    async fn get_url(&self) -> Result<CallToolResult, McpError> {
+        let result = self.eval_value("JSON.stringify({ url: window.location.href, title: document.title })")
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get URL failed: {e}"), None))?;
+
+        let value = result
+            .as_str()
+            .unwrap_or("{}");
+
+        let parsed: serde_json::Value = serde_json::from_str(value).unwrap_or_default();
+        let url = parsed.get("url").and_then(|v| v.as_str()).unwrap_or("(unknown)");
+        let title = parsed.get("title").and_then(|v| v.as_str()).unwrap_or("(unknown)");
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "URL: {url}\nTitle: {title}"
+        ))]))
+    }
+
+    #[tool(description = "Wait for a page navigation to complete (e.g. after clicking a link). Detects both full page loads (via CDP events) and SPA navigations (via History API interception). Returns immediately on detection rather than waiting for timeout.")]
+    async fn wait_for_navigation(
+        &self,
+        Parameters(WaitForNavigationParams { timeout_ms }): Parameters<WaitForNavigationParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let timeout = timeout_ms.unwrap_or(10000);
+
+        // Read pre-click URL + title snapshot (set by click/click_text/submit_form)
+        let (orig_url, orig_title) = self.pre_nav_snapshot.lock().await.clone();
+
+        // Inject SPA interceptors: patch pushState/replaceState, listen for popstate/hashchange
+        let setup_js = r#"(() => {
+            window.__causeway_nav = null;
+            const origPush = history.pushState;
+            const origReplace = history.replaceState;
+            function done(type) {
+                window.__causeway_nav = { type, url: location.href };
+                history.pushState = origPush;
+                history.replaceState = origReplace;
+                window.removeEventListener('popstate', onNav);
+                window.removeEventListener('hashchange', onNav);
+            }
+            history.pushState = function(...a) { origPush.apply(this, a); done('pushState'); };
+            history.replaceState = function(...a) { origReplace.apply(this, a); done('replaceState'); };
+            const onNav = (e) => done(e.type);
+            window.addEventListener('popstate', onNav);
+            window.addEventListener('hashchange', onNav);
+        })()"#;
+        self.execute_reconnect(commands::evaluate(setup_js)).await.ok();
+
+        // Subscribe to CDP events for full navigations
+        let mut receiver = {
+            let conn = self.live.get().await.ok_or(McpError::internal_error("Not connected", None))?;
+            cdp::subscribe_events(&*conn)
+        };
+
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(timeout);
+        let mut frame_navigated = false;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() { break; }
+
+            // Wait for CDP events in 200ms windows, then poll JS + URL snapshot
+            let wait = std::cmp::min(remaining, std::time::Duration::from_millis(200));
+            match tokio::time::timeout(wait, receiver.recv()).await {
+                Ok(Ok(event)) => match event.method.as_str() {
+                    "Page.navigatedWithinDocument" => {
+                        let url = event.params.get("url")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("(unknown)");
+                        return Ok(CallToolResult::success(vec![Content::text(format!(
+                            "SPA navigation: {url}"
+                        ))]));
+                    }
+                    "Page.frameNavigated" => {
+                        let is_main = event.params
+                            .get("frame")
+                            .and_then(|f| f.get("parentId"))
+                            .is_none();
+                        if is_main { frame_navigated = true; }
+                    }
+                    "Page.loadEventFired" if frame_navigated => {
+                        let title = self.eval_value("document.title")
+                            .await.ok()
+                            .and_then(|v| v.as_str().map(|s| s.to_owned()))
+                            .unwrap_or_else(|| "(unknown)".to_owned());
+                        return Ok(CallToolResult::success(vec![Content::text(format!(
+                            "Page loaded: {title}"
+                        ))]));
+                    }
+                    _ => {}
+                },
+                Ok(Err(tokio::sync::broadcast::error::RecvError::Lagged(_))) => continue,
+                Ok(Err(tokio::sync::broadcast::error::RecvError::Closed)) => {
+                    // Connection dropped — likely a full navigation destroyed context.
+                    // Wait briefly for the new page to settle, then check URL.
+                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                    let title = self.eval_value("document.title")
+                        .await.ok()
+                        .and_then(|v| v.as_str().map(|s| s.to_owned()))
+                        .unwrap_or_else(|| "(unknown)".to_owned());
+                    return Ok(CallToolResult::success(vec![Content::text(format!(
+                        "Page loaded: {title}"
+                    ))]));
+                }
+                Err(_) => {
+                    // 200ms window expired — poll JS SPA interceptors + URL change
+                    let poll_js = r#"JSON.stringify({
+                        nav: window.__causeway_nav,
+                        url: location.href,
+                        title: document.title
+                    })"#;
+                    if let Ok(result) = self.eval_value(poll_js).await {
+                        let raw = result
+                            .as_str()
+                            .unwrap_or("{}");
+                        if let Ok(poll) = serde_json::from_str::<serde_json::Value>(raw) {
+                            // Check SPA interceptor
+                            if let Some(nav) = poll.get("nav").filter(|v| !v.is_null()) {
+                                let nav_type = nav.get("type").and_then(|v| v.as_str()).unwrap_or("spa");
+                                let url = nav.get("url").and_then(|v| v.as_str()).unwrap_or("(unknown)");
+                                return Ok(CallToolResult::success(vec![Content::text(format!(
+                                    "SPA navigation ({nav_type}): {url}"
+                                ))]));
+                            }
+                            // Check URL/title change (catches frameworks that bypass History API)
+                            let cur_url = poll.get("url").and_then(|v| v.as_str()).unwrap_or("");
+                            let cur_title = poll.get("title").and_then(|v| v.as_str()).unwrap_or("");
+                            if cur_url != orig_url || cur_title != orig_title {
+                                return Ok(CallToolResult::success(vec![Content::text(format!(
+                                    "Page changed: {cur_title}"
+                                ))]));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Clean up interceptors
+        self.execute_reconnect(commands::evaluate(
+            "delete window.__causeway_nav"
+        )).await.ok();
+
+        // Fallback: if we saw frameNavigated but missed loadEventFired
+        if frame_navigated {
+            let title = self.eval_value("document.title")
+                .await.ok()
+                .and_then(|v| v.as_str().map(|s| s.to_owned()))
+                .unwrap_or_else(|| "(unknown)".to_owned());
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Page loaded: {title}"
+            ))]));
+        }
+
+        // Soft timeout — don't error, just inform
+        let title = self.eval_value("document.title")
+            .await.ok()
+            .and_then(|v| v.as_str().map(|s| s.to_owned()))
+            .unwrap_or_else(|| "(unknown)".to_owned());
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "No navigation detected within {timeout}ms (current page: {title})"
+        ))]))
+    }
+
+    #[tool(description = "Navigate back in browser history.")]
+    async fn back(&self) -> Result<CallToolResult, McpError> {
+        self.nav_history_step(-1).await
+    }
+
+    #[tool(description = "Navigate forward in browser history.")]
+    async fn forward(&self) -> Result<CallToolResult, McpError> {
+        self.nav_history_step(1).await
+    }
+
+    /// Navigate back (delta = -1) or forward (delta = +1) using CDP history API.
+    /// Page.navigateToHistoryEntry returns after navigation commits — no sleep needed.
+    async fn nav_history_step(&self, delta: i64) -> Result<CallToolResult, McpError> {
+        let history = self.execute_reconnect(commands::get_navigation_history())
+            .await
+            .map_err(|e| McpError::internal_error(format!("History fetch failed: {e}"), None))?;
+
+        let current_index = history.get("currentIndex").and_then(|v| v.as_i64()).unwrap_or(0);
+        let entries = history
+            .get("entries")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| McpError::internal_error("No navigation history".to_owned(), None))?;
+
+        let target_index = current_index + delta;
+        if target_index < 0 || target_index >= entries.len() as i64 {
+            let dir = if delta < 0 { "beginning" } else { "end" };
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Already at the {dir} of history"
+            ))]));
+        }
+
+        let entry_id = entries[target_index as usize]
+            .get("id")
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| McpError::internal_error("Invalid history entry".to_owned(), None))?;
+
+        // navigateToHistoryEntry returns after navigation commits — then wait for load.
+        self.execute_reconnect(commands::navigate_to_history_entry(entry_id))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Navigation failed: {e}"), None))?;
+
+        let _ = self.execute_reconnect(commands::evaluate(
+            "new Promise(resolve => {
+                if (document.readyState === 'complete') { resolve(); return; }
+                window.addEventListener('load', () => resolve(), { once: true });
+                setTimeout(resolve, 8000);
+            })",
+        ))
+        .await;
+
+        let dir = if delta < 0 { "back" } else { "forward" };
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Navigated {dir}"
+        ))]))
+    }
+
+    #[tool(description = "Read the live value of an input, textarea, or select — the .value property the user sees, not the HTML value attribute (which get_attribute returns and which goes stale once a field is edited). Selects return the selected option value(s); checkboxes and radios also report checked. Non-form elements return null.")]
+    async fn get_value(
+        &self,
+        Parameters(GetValueParams { selector }): Parameters<GetValueParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                const el = document.querySelector({sel});
+                if (!el) return {{ status: "not_found" }};
+                const tag = el.tagName.toLowerCase();
+                if (el instanceof HTMLSelectElement) {{
+                    const selected = Array.from(el.selectedOptions).map(o => ({{ value: o.value, text: o.text.trim() }}));
+                    return {{ status: "ok", tag, multiple: el.multiple, selected }};
+                }}
+                if (el instanceof HTMLInputElement) {{
+                    const checkable = el.type === 'checkbox' || el.type === 'radio';
+                    return {{ status: "ok", tag, type: el.type, value: el.value, checked: checkable ? el.checked : null }};
+                }}
+                if (el instanceof HTMLTextAreaElement || el instanceof HTMLOutputElement) {{
+                    return {{ status: "ok", tag, value: el.value }};
+                }}
+                return {{ status: "not_form", tag }};
+            }})()"#,
+            sel = serde_json::to_string(&selector).unwrap()
+        );
+
+        let value_obj = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get value failed: {e}"), None))?;
+        let status = value_obj
+            .get("status")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown");
+        let tag = value_obj.get("tag").and_then(|v| v.as_str()).unwrap_or("?");
+
+        let output = match status {
+            "not_found" => {
+                return Err(McpError::invalid_params(format!("Element not found: {selector}"), None));
+            }
+            "not_form" => format!(
+                "null — '{selector}' is a <{tag}>, not an input, textarea, or select. Use read_text or get_attribute instead."
+            ),
+            _ if tag == "select" => {
+                let selected = value_obj
+                    .get("selected")
+                    .and_then(|v| v.as_array())
+                    .cloned()
+                    .unwrap_or_default();
+                let multiple = value_obj.get("multiple").and_then(|v| v.as_bool()).unwrap_or(false);
+                if selected.is_empty() {
+                    "null (no option selected)".to_owned()
+                } else {
+                    let values: Vec<String> = selected
+                        .iter()
+                        .map(|o| {
+                            let value = o.get("value").and_then(|v| v.as_str()).unwrap_or("");
+                            let text = o.get("text").and_then(|v| v.as_str()).unwrap_or("");
+                            format!("{} (\"{text}\")", serde_json::to_string(value).unwrap())
+                        })
+                        .collect();
+                    if multiple {
+                        format!("[{}]", values.join(", "))
+                    } else {
+                        values.join("")
+                    }
+                }
+            }
+            _ => {
+                let value = value_obj.get("value").and_then(|v| v.as_str()).unwrap_or("");
+                let mut out = serde_json::to_string(value).unwrap();
+                if let Some(checked) = value_obj.get("checked").and_then(|v| v.as_bool()) {
+                    out.push_str(if checked { " (checked)" } else { " (unchecked)" });
+                }
+                out
+            }
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Set a field's value directly, without typing — much faster than type_text for long values. Uses the native value setter and fires input + change so React/Vue/Angular see the update. Returns the value read back from the element. Use type_text when the page reacts to individual keystrokes.")]
+    async fn set_value(
+        &self,
+        Parameters(SetValueParams { selector, value }): Parameters<SetValueParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                const el = document.querySelector({sel});
+                if (!el) return {{ status: "not_found" }};
+                // Native setter bypasses framework-patched value properties (React's value tracker)
+                const proto = el instanceof HTMLTextAreaElement ? HTMLTextAreaElement.prototype
+                    : el instanceof HTMLSelectElement ? HTMLSelectElement.prototype
+                    : HTMLInputElement.prototype;
+                const setter = Object.getOwnPropertyDescriptor(proto, 'value');
+                if (setter && setter.set && el instanceof proto.constructor) setter.set.call(el, {val});
+                else el.value = {val};
+                el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                return {{ status: "ok", value: String(el.value) }};
+            }})()"#,
+            sel = serde_json::to_string(&selector).unwrap(),
+            val = serde_json::to_string(&value).unwrap()
+        );
+
+        let value_obj = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Set value failed: {e}"), None))?;
+        let status = value_obj
+            .get("status")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown");
+
+        if status == "not_found" {
+            return Err(McpError::invalid_params(
+                format!("Element not found: {selector}"),
+                None,
+            ));
+        }
+
+        let read_back = value_obj
+            .get("value")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Set '{selector}' — value is now: {read_back}"
+        ))]))
+    }
+
+    #[tool(description = "Set a range input / slider to a value. Native <input type=range> gets the value set directly plus input and change events; custom role=slider widgets, or sliders whose framework reverts the direct set, get the thumb dragged to the computed position instead. Returns the resulting value and percentage.")]
+    async fn set_slider(
+        &self,
+        Parameters(SetSliderParams { selector, value, drag }): Parameters<SetSliderParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if !value.is_finite() {
+            return Err(McpError::invalid_params("Slider value must be a finite number".to_owned(), None));
+        }
+        let sel = serde_json::to_string(&selector).unwrap();
+        let js = format!(
+            r#"(async () => {{
+                const el = document.querySelector({sel});
+                if (!el) return {{ status: "not_found" }};
+                const isRange = el instanceof HTMLInputElement && el.type === 'range';
+                if (!isRange && el.getAttribute('role') !== 'slider') {{
+                    return {{ status: "not_slider", tag: el.tagName.toLowerCase() }};
+                }}
+                el.scrollIntoView({{ block: 'center', behavior: 'instant' }});
+                const num = (v, d) => {{ const n = parseFloat(v); return isNaN(n) ? d : n; }};
+                const min = num(isRange ? el.min : el.getAttribute('aria-valuemin'), 0);
+                const max = num(isRange ? el.max : el.getAttribute('aria-valuemax'), 100);
+                const current = () => num(isRange ? el.value : el.getAttribute('aria-valuenow'), min);
+                const before = current();
+                let settled = false;
+                if (isRange && !{force_drag}) {{
+                    // Native setter bypasses framework-patched value properties (React's value tracker)
+                    Object.getOwnPropertyDescriptor(HTMLInputElement.prototype, 'value').set.call(el, String({target}));
+                    const expected = el.value;
+                    el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                    el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                    await new Promise(r => requestAnimationFrame(() => requestAnimationFrame(r)));
+                    settled = el.value === expected;
+                }}
+                const r = el.getBoundingClientRect();
+                return {{
+                    status: "ok", min, max, before, value: current(), settled,
+                    vertical: el.getAttribute('aria-orientation') === 'vertical',
+                    rect: {{ x: r.left, y: r.top, w: r.width, h: r.height }},
+                }};
+            }})()"#,
+            force_drag = drag.unwrap_or(false),
+            target = serde_json::json!(value),
+        );
+
+        let state = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Set slider failed: {e}"), None))?;
+
+        match state.get("status").and_then(|v| v.as_str()) {
+            Some("not_found") => {
+                return Err(McpError::invalid_params(format!("Element not found: {selector}"), None));
+            }
+            Some("not_slider") => {
+                let tag = state.get("tag").and_then(|v| v.as_str()).unwrap_or("?");
+                return Err(McpError::invalid_params(
+                    format!("'{selector}' is a <{tag}>, not a range input or role=slider"),
+                    None,
+                ));
+            }
+            _ => {}
+        }
+
+        let n = |key: &str| state.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let (min, max) = (n("min"), n("max"));
+        let mut result = n("value");
+        let mut method = "set directly";
+
+        if !state.get("settled").and_then(|v| v.as_bool()).unwrap_or(false) {
+            // Drag the thumb from where the current value sits to where the target would
+            let rect = |key: &str| state.get("rect").and_then(|r| r.get(key)).and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let (rx, ry, rw, rh) = (rect("x"), rect("y"), rect("w"), rect("h"));
+            let vertical = state.get("vertical").and_then(|v| v.as_bool()).unwrap_or(false);
+            let span = if max > min { max - min } else { 1.0 };
+            let point = |v: f64| {
+                let f = ((v - min) / span).clamp(0.0, 1.0);
+                if vertical { (rx + rw / 2.0, ry + rh - f * rh) } else { (rx + f * rw, ry + rh / 2.0) }
+            };
+            let (fx, fy) = point(result);
+            let (tx, ty) = point(value);
+            self.execute_seq_reconnect(commands::drag(fx, fy, tx, ty, 10))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Slider drag failed: {e}"), None))?;
+
+            let read_js = format!(
+                r#"(() => {{
+                    const el = document.querySelector({sel});
+                    if (!el) return null;
+                    const v = parseFloat(el instanceof HTMLInputElement ? el.value : el.getAttribute('aria-valuenow'));
+                    return isNaN(v) ? null : v;
+                }})()"#
+            );
+            result = self.eval_value(&read_js)
+                .await
+                .map_err(|e| McpError::internal_error(format!("Read slider failed: {e}"), None))?
+                .as_f64()
+                .unwrap_or(result);
+            method = "dragged";
+        }
+
+        let pct = if max > min { (result - min) / (max - min) * 100.0 } else { 0.0 };
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Slider '{selector}' {method} — value is now {result} ({pct:.0}% of {min}–{max})"
+        ))]))
+    }
+
+    #[tool(description = "Set a checkbox or radio button to a known checked state — idempotent, unlike click. Clicks only if the current state differs (so handlers fire), falling back to setting .checked and dispatching change. For a radio group, pass a selector matching the group (e.g. input[name=size]) plus value. Returns the final state.")]
+    async fn set_checked(
+        &self,
+        Parameters(SetCheckedParams { selector, checked, value }): Parameters<SetCheckedParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                const matches = Array.from(document.querySelectorAll({sel}));
+                if (!matches.length) return {{ status: "not_found" }};
+                const wanted = {val};
+                const el = wanted === null ? matches[0] : matches.find(e => e.value === wanted);
+                if (!el) return {{ status: "no_value", values: matches.map(e => e.value) }};
+                if (!(el instanceof HTMLInputElement) || (el.type !== 'checkbox' && el.type !== 'radio')) {{
+                    return {{ status: "not_checkable", tag: el.tagName.toLowerCase(), type: el.type || null }};
+                }}
+                const target = {checked};
+                const before = el.checked;
+                if (el.checked !== target) el.click();
+                // click can't uncheck a radio, and handlers may cancel it — force the state
+                if (el.checked !== target) {{
+                    el.checked = target;
+                    el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                    el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                }}
+                return {{ status: "ok", type: el.type, value: el.value, before, checked: el.checked }};
+            }})()"#,
+            sel = serde_json::to_string(&selector).unwrap(),
+            val = serde_json::to_string(&value).unwrap(),
+            checked = checked,
+        );
+
+        let value_obj = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Set checked failed: {e}"), None))?;
+        let status = value_obj
+            .get("status")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown");
+
+        match status {
+            "not_found" => {
+                return Err(McpError::invalid_params(format!("Element not found: {selector}"), None));
+            }
+            "no_value" => {
+                let values = value_obj
+                    .get("values")
+                    .map(|v| v.to_string())
+                    .unwrap_or_default();
+                return Err(McpError::invalid_params(
+                    format!("No element matching '{selector}' has value {value:?}. Values: {values}"),
+                    None,
+                ));
+            }
+            "not_checkable" => {
+                let tag = value_obj.get("tag").and_then(|v| v.as_str()).unwrap_or("?");
+                return Err(McpError::invalid_params(
+                    format!("'{selector}' is a <{tag}>, not a checkbox or radio"),
+                    None,
+                ));
+            }
+            _ => {}
+        }
+
+        let kind = value_obj.get("type").and_then(|v| v.as_str()).unwrap_or("checkbox");
+        let before = value_obj.get("before").and_then(|v| v.as_bool()).unwrap_or(false);
+        let after = value_obj.get("checked").and_then(|v| v.as_bool()).unwrap_or(false);
+        let label = match value_obj.get("value").and_then(|v| v.as_str()) {
+            Some(v) if kind == "radio" => format!("{selector} [value={v}]"),
+            _ => selector.clone(),
+        };
+        let state = if after { "checked" } else { "unchecked" };
+
+        let output = if after != checked {
+            format!("{kind} '{label}' is still {state} — the page rejected the change")
+        } else if before == after {
+            format!("{kind} '{label}' already {state} — no change")
+        } else {
+            format!("{kind} '{label}' is now {state}")
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Select an option in a <select> dropdown by its value attribute.")]
+    async fn select_option(
+        &self,
+        Parameters(SelectOptionParams { selector, value }): Parameters<SelectOptionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                const el = document.querySelector({sel});
+                if (!el) return "not_found";
+                el.value = {val};
+                el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                return "ok";
+            }})()"#,
+            sel = serde_json::to_string(&selector).unwrap(),
+            val = serde_json::to_string(&value).unwrap()
+        );
+
+        let result = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Select failed: {e}"), None))?;
+
+        let status = result
+            .as_str()
+            .unwrap_or("unknown");
+
+        if status == "not_found" {
+            return Err(McpError::invalid_params(
+                format!("Select element not found: {selector}"),
+                None,
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Selected value '{value}' in '{selector}'"
+        ))]))
+    }
+
+    #[tool(description = "Toggle a checkbox or radio button by its label text, name, or nearby text. Works via JavaScript — no mouse simulation — so it handles hidden inputs, custom styled controls, and Material Design components that ignore click events. Use this instead of click/click_text when targeting checkboxes or radio buttons.")]
+    async fn toggle(
+        &self,
+        Parameters(ToggleParams { label, checked }): Parameters<ToggleParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let force_state = match checked {
+            Some(true) => "true",
+            Some(false) => "false",
+            None => "null",
+        };
+
+        let js = format!(
+            r#"(() => {{
+                const search = {label}.toLowerCase();
+                const force = {force};
+
+                // Strategy 1: find input by label[for] text match
+                for (const lbl of document.querySelectorAll('label')) {{
+                    if (!lbl.textContent.trim().toLowerCase().includes(search)) continue;
+                    const forId = lbl.getAttribute('for');
+                    const input = forId ? document.getElementById(forId)
+                        : lbl.querySelector('input[type="checkbox"], input[type="radio"]');
+                    if (input) {{
+                        if (force !== null) input.checked = force;
+                        else input.checked = !input.checked;
+                        input.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                        input.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                        return {{ found: true, label: lbl.textContent.trim().substring(0, 80), checked: input.checked, method: 'label' }};
+                    }}
+                }}
+
+                // Strategy 2: find input whose name/id/value contains search text
+                for (const input of document.querySelectorAll('input[type="checkbox"], input[type="radio"]')) {{
+                    const haystack = [input.name, input.id, input.value,
+                        input.getAttribute('aria-label') || ''].join(' ').toLowerCase();
+                    if (!haystack.includes(search)) continue;
+                    if (force !== null) input.checked = force;
+                    else input.checked = !input.checked;
+                    input.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                    input.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                    return {{ found: true, label: input.name || input.id || input.value, checked: input.checked, method: 'input-attr' }};
+                }}
+
+                // Strategy 3: find any element with role=checkbox or role=radio
+                for (const el of document.querySelectorAll('[role="checkbox"], [role="radio"], [role="switch"]')) {{
+                    const elText = (el.textContent || el.getAttribute('aria-label') || '').trim().toLowerCase();
+                    if (!elText.includes(search)) continue;
+                    const isChecked = el.getAttribute('aria-checked') === 'true';
+                    const newState = force !== null ? force : !isChecked;
+                    el.setAttribute('aria-checked', String(newState));
+                    el.click();
+                    return {{ found: true, label: elText.substring(0, 80), checked: newState, method: 'aria-role' }};
+                }}
+
+                // Strategy 4: walk text nodes and find nearest input
+                const walker = document.createTreeWalker(document.body, NodeFilter.SHOW_TEXT);
+                while (walker.nextNode()) {{
+                    if (!walker.currentNode.textContent.trim().toLowerCase().includes(search)) continue;
+                    const parent = walker.currentNode.parentElement;
+                    if (!parent) continue;
+                    const container = parent.closest('label, div, li, span, td, fieldset');
+                    if (!container) continue;
+                    const input = container.querySelector('input[type="checkbox"], input[type="radio"]');
+                    if (input) {{
+                        if (force !== null) input.checked = force;
+                        else input.checked = !input.checked;
+                        input.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                        input.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                        return {{ found: true, label: walker.currentNode.textContent.trim().substring(0, 80), checked: input.checked, method: 'nearby-text' }};
+                    }}
+                }}
+
+                return {{ found: false }};
+            }})()"#,
+            label = serde_json::to_string(&label).unwrap(),
+            force = force_state,
+        );
+
+        let val = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Toggle failed: {e}"), None))?;
+
+        if val.is_null() {
+            return Err(McpError::internal_error("No result from toggle".to_owned(), None));
+        }
+
+        let found = val.get("found").and_then(|v| v.as_bool()).unwrap_or(false);
+        if !found {
+            return Err(McpError::invalid_params(
+                format!("No checkbox or radio button found matching: \"{label}\""),
+                None,
+            ));
+        }
+
+        let matched_label = val.get("label").and_then(|v| v.as_str()).unwrap_or("(unknown)");
+        let is_checked = val.get("checked").and_then(|v| v.as_bool()).unwrap_or(false);
+        let method = val.get("method").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let state = if is_checked { "checked" } else { "unchecked" };
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Toggled \"{matched_label}\" → {state} (via {method})"
+        ))]))
+    }
+
+    #[tool(description = "Submit a form element by CSS selector.")]
+    async fn submit_form(
+        &self,
+        Parameters(SubmitFormParams { selector }): Parameters<SubmitFormParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                const el = document.querySelector({sel});
+                if (!el) return "not_found";
+                if (el.tagName === 'FORM') {{
+                    el.submit();
+                }} else {{
+                    const form = el.closest('form');
+                    if (form) form.submit();
+                    else return "no_form";
+                }}
+                return "ok";
+            }})()"#,
+            sel = serde_json::to_string(&selector).unwrap()
+        );
+
+        self.snapshot_pre_nav().await;
+        let result = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Submit failed: {e}"), None))?;
+
+        let status = result
+            .as_str()
+            .unwrap_or("unknown");
+
+        match status {
+            "not_found" => Err(McpError::invalid_params(
+                format!("Element not found: {selector}"),
+                None,
+            )),
+            "no_form" => Err(McpError::invalid_params(
+                format!("No parent form found for: {selector}"),
+                None,
+            )),
+            _ => Ok(CallToolResult::success(vec![Content::text(
+                "Form submitted".to_owned(),
+            )])),
+        }
+    }
+
+    #[tool(description = "Discover WebMCP tools declared by the current page via navigator.modelContext. Returns structured tool definitions if the page exposes any.")]
+    async fn discover_webmcp_tools(&self) -> Result<CallToolResult, McpError> {
+        let js = r#"(() => {
+            if (!navigator.modelContext) return { supported: false };
+            const tools = navigator.modelContext.tools || [];
+            return {
+                supported: true,
+                tools: tools.map(t => ({
+                    name: t.name,
+                    description: t.description,
+                    inputSchema: t.inputSchema,
+                }))
+            };
+        })()"#;
+
+        let value = self.eval_value(js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("WebMCP check failed: {e}"), None))?;
+
+        let supported = value
+            .get("supported")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if !supported {
+            return Ok(CallToolResult::success(vec![Content::text(
                 "This page does not support WebMCP (navigator.modelContext not available)".to_owned(),
             )]));
         }
 
-        let tools = value.get("tools").cloned().unwrap_or(serde_json::json!([]));
-        let tool_count = tools.as_array().map(|a| a.len()).unwrap_or(0);
+        let tools = value.get("tools").cloned().unwrap_or(serde_json::json!([]));
+        let tool_count = tools.as_array().map(|a| a.len()).unwrap_or(0);
+
+        if tool_count == 0 {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "WebMCP is available but no tools are registered on this page".to_owned(),
+            )]));
+        }
+
+        let formatted = serde_json::to_string_pretty(&tools)
+            .unwrap_or_else(|_| format!("{tools:?}"));
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Found {tool_count} WebMCP tool(s):\n\n{formatted}"
+        ))]))
+    }
+
+    #[tool(description = "List or evaluate JavaScript in browser extension service workers. Use action='list' to see running extensions, action='eval' to run JS in an extension's context with userGesture=true (enables chrome.sidePanel.open(), chrome.action.openPopup(), etc).")]
+    async fn extension_eval(
+        &self,
+        Parameters(ExtensionEvalParams { action, query, expression }): Parameters<ExtensionEvalParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let url = format!("http://localhost:{}/json", self.port);
+        let client = reqwest::Client::new();
+
+        let response = match client.get(&url).send().await {
+            Ok(r) => r,
+            Err(_) => {
+                self.try_reconnect().await.map_err(|msg| McpError::internal_error(msg, None))?;
+                client.get(&url).send().await
+                    .map_err(|e| McpError::internal_error(format!("Failed to list targets: {e}"), None))?
+            }
+        };
+
+        let targets: Vec<serde_json::Value> = response
+            .json()
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to parse targets: {e}"), None))?;
+
+        // Filter to extension targets: service_worker, background_page
+        let ext_types = ["service_worker", "background_page"];
+        let mut extensions: Vec<&serde_json::Value> = targets.iter()
+            .filter(|t| {
+                let t_type = t.get("type").and_then(|v| v.as_str()).unwrap_or("");
+                ext_types.contains(&t_type)
+            })
+            .collect();
+
+        // Apply query filter if provided
+        if let Some(ref q) = query {
+            let q_lower = q.to_lowercase();
+            extensions.retain(|t| {
+                let title = t.get("title").and_then(|v| v.as_str()).unwrap_or("").to_lowercase();
+                let url = t.get("url").and_then(|v| v.as_str()).unwrap_or("").to_lowercase();
+                title.contains(&q_lower) || url.contains(&q_lower)
+            });
+        }
+
+        match action.as_str() {
+            "list" => {
+                if extensions.is_empty() {
+                    return Ok(CallToolResult::success(vec![Content::text(
+                        "No extension service workers found".to_owned(),
+                    )]));
+                }
+                let mut output = String::new();
+                for ext in &extensions {
+                    let title = ext.get("title").and_then(|v| v.as_str()).unwrap_or("(untitled)");
+                    let url = ext.get("url").and_then(|v| v.as_str()).unwrap_or("?");
+                    let t_type = ext.get("type").and_then(|v| v.as_str()).unwrap_or("?");
+                    let id = ext.get("id").and_then(|v| v.as_str()).unwrap_or("?");
+                    output.push_str(&format!("[{t_type}] {title}\n  id: {id}\n  url: {url}\n\n"));
+                }
+                Ok(CallToolResult::success(vec![Content::text(output)]))
+            }
+            "eval" => {
+                let js = expression.ok_or_else(|| McpError::invalid_params(
+                    "expression is required for eval action", None,
+                ))?;
+
+                if extensions.is_empty() {
+                    return Err(McpError::invalid_params(
+                        format!("No extension found matching query: {}", query.unwrap_or_default()),
+                        None,
+                    ));
+                }
+                if extensions.len() > 1 && query.is_none() {
+                    let names: Vec<&str> = extensions.iter()
+                        .map(|t| t.get("title").and_then(|v| v.as_str()).unwrap_or("?"))
+                        .collect();
+                    return Err(McpError::invalid_params(
+                        format!("Multiple extensions found, use query to disambiguate: {:?}", names),
+                        None,
+                    ));
+                }
+
+                let target = extensions[0];
+                let ws_url = target.get("webSocketDebuggerUrl")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| McpError::internal_error(
+                        "Extension target has no webSocketDebuggerUrl", None,
+                    ))?;
+
+                // Open a separate CDP connection to the extension's service worker
+                let ext_conn = cdp::connect(ws_url)
+                    .await
+                    .map_err(|e| McpError::internal_error(
+                        format!("Failed to connect to extension: {e}"), None,
+                    ))?;
+
+                // Enable Runtime domain on the extension connection
+                cdp::send(&ext_conn, "Runtime.enable", serde_json::json!({}))
+                    .await
+                    .map_err(|e| McpError::internal_error(
+                        format!("Failed to enable Runtime on extension: {e}"), None,
+                    ))?;
+
+                // Evaluate with userGesture=true so chrome APIs requiring user action work
+                let result = cdp::send(&ext_conn, "Runtime.evaluate", serde_json::json!({
+                    "expression": js,
+                    "returnByValue": true,
+                    "awaitPromise": true,
+                    "userGesture": true,
+                }))
+                .await
+                .map_err(|e| McpError::internal_error(
+                    format!("Extension eval failed: {e}"), None,
+                ))?;
+
+                // Extract result or error
+                let title = target.get("title").and_then(|v| v.as_str()).unwrap_or("extension");
+                if let Some(exception) = result.get("exceptionDetails") {
+                    let text = exception
+                        .get("exception")
+                        .and_then(|e| e.get("description").or(e.get("value")))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("Unknown error");
+                    Ok(CallToolResult::success(vec![Content::text(format!(
+                        "JS Error in {title}: {text}"
+                    ))]))
+                } else {
+                    let value = cdp::take_value(result);
+                    let formatted = if value.is_string() {
+                        value.as_str().unwrap().to_owned()
+                    } else {
+                        serde_json::to_string_pretty(&value).unwrap_or_else(|_| format!("{value}"))
+                    };
+                    Ok(CallToolResult::success(vec![Content::text(format!(
+                        "[{title}] {formatted}"
+                    ))]))
+                }
+
+                // ext_conn drops here — separate connection, doesn't affect the main page connection
+            }
+            _ => Err(McpError::invalid_params(
+                format!("Unknown action: {action}. Use 'list' or 'eval'."),
+                None,
+            )),
+        }
+    }
+
+    #[tool(description = "Inspect the DOM tree starting from a CSS selector. Returns a compact structural view with tag names, key attributes (id, class, href, type, name, value, role, aria-label), and truncated text content. Essential for understanding page structure without screenshots.")]
+    async fn inspect(
+        &self,
+        Parameters(InspectParams { selector, max_depth }): Parameters<InspectParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let sel = selector.as_deref().unwrap_or("body");
+        let depth = max_depth.unwrap_or(4);
+
+        let js = format!(
+            r#"(() => {{
+                const ATTRS = ['id','class','href','src','type','name','value','role','aria-label','placeholder','action','method','for','alt','title'];
+                const MAX_TEXT = 80;
+                const MAX_CHILDREN = 50;
+
+                function walk(el, depth, maxDepth) {{
+                    if (depth > maxDepth) return '  '.repeat(depth) + '...';
+                    const tag = el.tagName.toLowerCase();
+                    let attrs = '';
+                    for (const a of ATTRS) {{
+                        const v = el.getAttribute(a);
+                        if (v) attrs += ' ' + a + '="' + v.substring(0, 60) + '"';
+                    }}
+                    const indent = '  '.repeat(depth);
+                    let result = indent + '<' + tag + attrs + '>';
+
+                    const children = el.children;
+                    const textContent = el.childNodes.length === 1 && el.childNodes[0].nodeType === 3
+                        ? el.childNodes[0].textContent.trim() : null;
+
+                    if (textContent && textContent.length > 0) {{
+                        const t = textContent.length > MAX_TEXT
+                            ? textContent.substring(0, MAX_TEXT) + '...' : textContent;
+                        result += t + '</' + tag + '>';
+                        return result;
+                    }}
+
+                    if (children.length === 0) {{
+                        const t = el.textContent.trim();
+                        if (t.length > 0) {{
+                            const truncated = t.length > MAX_TEXT ? t.substring(0, MAX_TEXT) + '...' : t;
+                            result += truncated + '</' + tag + '>';
+                        }} else {{
+                            result = indent + '<' + tag + attrs + ' />';
+                        }}
+                        return result;
+                    }}
+
+                    result += '\n';
+                    const len = Math.min(children.length, MAX_CHILDREN);
+                    for (let i = 0; i < len; i++) {{
+                        result += walk(children[i], depth + 1, maxDepth) + '\n';
+                    }}
+                    if (children.length > MAX_CHILDREN) {{
+                        result += indent + '  ... +' + (children.length - MAX_CHILDREN) + ' more\n';
+                    }}
+                    result += indent + '</' + tag + '>';
+                    return result;
+                }}
+
+                const root = document.querySelector({sel});
+                if (!root) return null;
+                return walk(root, 0, {depth});
+            }})()"#,
+            sel = serde_json::to_string(sel).unwrap(),
+            depth = depth
+        );
+
+        let result = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Inspect failed: {e}"), None))?;
+
+        let value = result.as_str();
+
+        match value {
+            Some(tree) => {
+                let truncated = if tree.len() > 15000 {
+                    format!("{}...\n\n[Truncated — {} total chars]", &tree[..15000], tree.len())
+                } else {
+                    tree.to_owned()
+                };
+                Ok(CallToolResult::success(vec![Content::text(truncated)]))
+            }
+            None => Err(McpError::invalid_params(
+                format!("Element not found: {sel}"),
+                None,
+            )),
+        }
+    }
+
+    #[tool(description = "Identify the DOM element at specific x/y coordinates. Returns the element and its parent chain with tag, classes, id, role, and text. Use after a screenshot to discover what's at a specific spot — especially useful for custom/obfuscated UIs where CSS selectors are unknown.")]
+    async fn point_inspect(
+        &self,
+        Parameters(PointInspectParams { x, y }): Parameters<PointInspectParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                const ATTRS = ['id','class','role','type','name','href','aria-label','data-testid','tabindex','onclick'];
+                const el = document.elementFromPoint({x}, {y});
+                if (!el) return null;
+
+                function describe(node) {{
+                    const tag = node.tagName.toLowerCase();
+                    let attrs = {{}};
+                    for (const a of ATTRS) {{
+                        const v = node.getAttribute(a);
+                        if (v && v.length > 0) attrs[a] = v.substring(0, 120);
+                    }}
+                    const text = (node.textContent || '').trim();
+                    const rect = node.getBoundingClientRect();
+                    return {{
+                        tag,
+                        attrs,
+                        text: text.substring(0, 100),
+                        bounds: {{ x: Math.round(rect.x), y: Math.round(rect.y), w: Math.round(rect.width), h: Math.round(rect.height) }}
+                    }};
+                }}
+
+                const chain = [];
+                let cur = el;
+                for (let i = 0; i < 6 && cur && cur !== document.documentElement; i++) {{
+                    chain.push(describe(cur));
+                    cur = cur.parentElement;
+                }}
+                return chain;
+            }})()"#,
+            x = serde_json::json!(x),
+            y = serde_json::json!(y),
+        );
+
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Point inspect failed: {e}"), None))?;
+
+        match value {
+            serde_json::Value::Null => {
+                Ok(CallToolResult::success(vec![Content::text(
+                    format!("No element found at ({x}, {y})")
+                )]))
+            }
+            chain => {
+                let formatted = serde_json::to_string_pretty(&chain).unwrap_or_else(|_| chain.to_string());
+                Ok(CallToolResult::success(vec![Content::text(formatted)]))
+            }
+        }
+    }
+
+    #[tool(description = "Get the element at x/y viewport coordinates with a generated unique CSS selector for it. Bridges screenshots and selector-based tools: find a spot visually, then click/type/read_text using the returned selector. For the full parent chain use point_inspect.")]
+    async fn element_at_point(
+        &self,
+        Parameters(PointInspectParams { x, y }): Parameters<PointInspectParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                {unique_selector}
+                const el = document.elementFromPoint({x}, {y});
+                if (!el) return null;
+                return {{
+                    tag: el.tagName.toLowerCase(),
+                    text: (el.textContent || '').trim().substring(0, 100),
+                    selector: uniqueSelector(el),
+                }};
+            }})()"#,
+            unique_selector = JS_UNIQUE_SELECTOR,
+            x = serde_json::json!(x),
+            y = serde_json::json!(y),
+        );
+
+        let el = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Element at point failed: {e}"), None))?;
+
+        if el.is_null() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No element at ({x}, {y}) — the point is outside the page or viewport"
+            ))]));
+        }
+
+        let tag = el.get("tag").and_then(|v| v.as_str()).unwrap_or("?");
+        let text = el.get("text").and_then(|v| v.as_str()).unwrap_or("");
+        let selector = el.get("selector").and_then(|v| v.as_str()).unwrap_or("(no unique selector)");
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "<{tag}> at ({x}, {y})\nSelector: {selector}\nText: {text}"
+        ))]))
+    }
+
+    #[tool(description = "Get the declared CSS styles for an element — shows the actual authored values (rem, var(), %, etc.) not just resolved pixels. Resolves CSS variable references to their declared values. Use this instead of getComputedStyle when you need the real design tokens.")]
+    async fn inspect_styles(
+        &self,
+        Parameters(InspectStylesParams { selector, properties }): Parameters<InspectStylesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let default_props = vec![
+            "font-size", "font-weight", "font-family", "line-height", "letter-spacing",
+            "color", "background-color", "background",
+            "padding", "padding-top", "padding-right", "padding-bottom", "padding-left",
+            "margin", "margin-top", "margin-right", "margin-bottom", "margin-left",
+            "width", "max-width", "min-width", "height", "max-height", "min-height",
+            "display", "position", "gap", "border-radius",
+            "border", "border-top", "border-bottom", "border-left", "border-right",
+            "box-shadow", "text-align", "text-transform", "opacity",
+        ];
+
+        let props_json = match &properties {
+            Some(p) => serde_json::to_string(p).unwrap(),
+            None => serde_json::to_string(&default_props).unwrap(),
+        };
+
+        let js = format!(
+            r#"(() => {{
+                const el = document.querySelector({sel});
+                if (!el) return {{ error: "Element not found" }};
+
+                const props = {props};
+                const computed = getComputedStyle(el);
+                const results = [];
+
+                // Collect all matched CSS rules for this element
+                const ruleMap = new Map();
+                for (const sheet of document.styleSheets) {{
+                    try {{
+                        for (const rule of sheet.cssRules || []) {{
+                            if (rule.selectorText && el.matches(rule.selectorText)) {{
+                                for (const prop of props) {{
+                                    const val = rule.style.getPropertyValue(prop);
+                                    if (val) ruleMap.set(prop, {{ value: val.trim(), selector: rule.selectorText }});
+                                }}
+                            }}
+                        }}
+                    }} catch (e) {{ /* cross-origin sheets */ }}
+                }}
+
+                // Also check inline styles
+                for (const prop of props) {{
+                    const inline = el.style.getPropertyValue(prop);
+                    if (inline) ruleMap.set(prop, {{ value: inline.trim(), selector: "inline" }});
+                }}
+
+                // Resolve var() references
+                function resolveVar(value) {{
+                    const varMatch = value.match(/var\(--([^,)]+)/);
+                    if (!varMatch) return null;
+                    const varName = '--' + varMatch[1].trim();
+                    // Walk up the tree to find where the variable is defined
+                    let node = el;
+                    while (node) {{
+                        const val = getComputedStyle(node).getPropertyValue(varName).trim();
+                        if (val) return {{ varName, resolved: val }};
+                        node = node.parentElement;
+                    }}
+                    return null;
+                }}
+
+                for (const prop of props) {{
+                    const rule = ruleMap.get(prop);
+                    const computedVal = computed.getPropertyValue(prop).trim();
+                    if (!rule && !computedVal) continue;
+
+                    const entry = {{ property: prop, computed: computedVal }};
+                    if (rule) {{
+                        entry.declared = rule.value;
+                        entry.from = rule.selector;
+                        const resolved = resolveVar(rule.value);
+                        if (resolved) {{
+                            entry.variable = resolved.varName;
+                            entry.resolvedVar = resolved.resolved;
+                        }}
+                    }}
+                    results.push(entry);
+                }}
+
+                // Get element info
+                const tag = el.tagName.toLowerCase();
+                const cls = el.className ? '.' + el.className.split(/\s+/).join('.') : '';
+                const id = el.id ? '#' + el.id : '';
+
+                return {{ element: tag + id + cls, results }};
+            }})()"#,
+            sel = serde_json::to_string(&selector).unwrap(),
+            props = props_json,
+        );
+
+        let val = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Style inspection failed: {e}"), None))?;
+
+        if val.is_null() {
+            return Err(McpError::internal_error("No result from style inspection".to_owned(), None));
+        }
+
+        if let Some(err) = val.get("error").and_then(|v| v.as_str()) {
+            return Err(McpError::invalid_params(err.to_owned(), None));
+        }
+
+        let element = val.get("element").and_then(|v| v.as_str()).unwrap_or("?");
+        let results = val.get("results").and_then(|v| v.as_array());
+
+        let mut output = format!("Styles for: {element}\n\n");
+
+        if let Some(entries) = results {
+            for entry in entries {
+                let prop = entry.get("property").and_then(|v| v.as_str()).unwrap_or("?");
+                let computed = entry.get("computed").and_then(|v| v.as_str()).unwrap_or("");
+                let declared = entry.get("declared").and_then(|v| v.as_str());
+                let from = entry.get("from").and_then(|v| v.as_str());
+                let var_name = entry.get("variable").and_then(|v| v.as_str());
+                let resolved = entry.get("resolvedVar").and_then(|v| v.as_str());
+
+                if let Some(decl) = declared {
+                    output.push_str(&format!("{prop}: {decl}"));
+                    if let (Some(vn), Some(rv)) = (var_name, resolved) {
+                        output.push_str(&format!("  →  {vn}: {rv}"));
+                    }
+                    output.push_str(&format!("  (computed: {computed})"));
+                    if let Some(f) = from {
+                        output.push_str(&format!("  [{f}]"));
+                    }
+                } else {
+                    output.push_str(&format!("{prop}: {computed}"));
+                }
+                output.push('\n');
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Find all elements matching a CSS selector and return their tag, text content, key attributes, and count. Useful for finding interactive elements, links, buttons, form fields, etc.")]
+    async fn query_elements(
+        &self,
+        Parameters(QueryElementsParams { selector }): Parameters<QueryElementsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                const els = document.querySelectorAll({sel});
+                const results = [];
+                const MAX = 50;
+                for (let i = 0; i < Math.min(els.length, MAX); i++) {{
+                    const el = els[i];
+                    const rect = el.getBoundingClientRect();
+                    const entry = {{
+                        index: i,
+                        tag: el.tagName.toLowerCase(),
+                        text: el.textContent.trim().substring(0, 100),
+                        id: el.id || undefined,
+                        class: el.className || undefined,
+                        href: el.getAttribute('href') || undefined,
+                        type: el.getAttribute('type') || undefined,
+                        name: el.getAttribute('name') || undefined,
+                        value: el.value || undefined,
+                        visible: rect.width > 0 && rect.height > 0,
+                    }};
+                    // Remove undefined keys
+                    Object.keys(entry).forEach(k => entry[k] === undefined && delete entry[k]);
+                    results.push(entry);
+                }}
+                return {{ total: els.length, shown: Math.min(els.length, MAX), elements: results }};
+            }})()"#,
+            sel = serde_json::to_string(&selector).unwrap()
+        );
+
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Query failed: {e}"), None))?;
+
+        let output = serde_json::to_string_pretty(&value)
+            .unwrap_or_else(|_| format!("{value:?}"));
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Evaluate an XPath expression with document.evaluate. Node results return the same fields as query_elements (tag, text, id, class, href, type, name, value, visible) plus a CSS selector usable with other tools; text and attribute nodes return their content. String, number, and boolean expressions (e.g. count(//li), string(//h1)) return the value. For teams migrating Selenium/XPath automation.")]
+    async fn query_xpath(
+        &self,
+        Parameters(QueryXpathParams { xpath }): Parameters<QueryXpathParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                {unique_selector}
+                let result;
+                try {{
+                    result = document.evaluate({xpath}, document, null, XPathResult.ANY_TYPE, null);
+                }} catch (e) {{
+                    return {{ error: e.message }};
+                }}
+                switch (result.resultType) {{
+                    case XPathResult.NUMBER_TYPE: return {{ type: 'number', value: result.numberValue }};
+                    case XPathResult.STRING_TYPE: return {{ type: 'string', value: result.stringValue }};
+                    case XPathResult.BOOLEAN_TYPE: return {{ type: 'boolean', value: result.booleanValue }};
+                }}
+                const MAX = 50;
+                const nodes = [];
+                for (let n = result.iterateNext(); n; n = result.iterateNext()) nodes.push(n);
+                const results = nodes.slice(0, MAX).map((node, i) => {{
+                    if (node.nodeType === Node.ATTRIBUTE_NODE) {{
+                        return {{ index: i, node: 'attribute', name: node.name, value: node.value, owner: node.ownerElement ? uniqueSelector(node.ownerElement) : undefined }};
+                    }}
+                    if (node.nodeType !== Node.ELEMENT_NODE) {{
+                        return {{ index: i, node: node.nodeName.replace('#', ''), text: (node.textContent || '').trim().substring(0, 100) }};
+                    }}
+                    const el = node;
+                    const rect = el.getBoundingClientRect();
+                    const entry = {{
+                        index: i,
+                        tag: el.tagName.toLowerCase(),
+                        selector: uniqueSelector(el),
+                        text: el.textContent.trim().substring(0, 100),
+                        id: el.id || undefined,
+                        class: (typeof el.className === 'string' && el.className) || undefined,
+                        href: el.getAttribute('href') || undefined,
+                        type: el.getAttribute('type') || undefined,
+                        name: el.getAttribute('name') || undefined,
+                        value: el.value || undefined,
+                        visible: rect.width > 0 && rect.height > 0,
+                    }};
+                    // Remove undefined keys
+                    Object.keys(entry).forEach(k => entry[k] === undefined && delete entry[k]);
+                    return entry;
+                }});
+                return {{ type: 'nodes', total: nodes.length, shown: results.length, nodes: results }};
+            }})()"#,
+            unique_selector = JS_UNIQUE_SELECTOR,
+            xpath = serde_json::to_string(&xpath).unwrap()
+        );
+
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("XPath query failed: {e}"), None))?;
 
-        if tool_count == 0 {
-            return Ok(CallToolResult::success(vec![Content::text(
-                "WebMCP is available but no tools are registered on this page".to_owned(),
-            )]));
+        if let Some(error) = value.get("error").and_then(|v| v.as_str()) {
+            return Err(McpError::invalid_params(format!("Invalid XPath: {error}"), None));
         }
 
-        let formatted = serde_json::to_string_pretty(&tools)
-            .unwrap_or_else(|_| format!("{tools:?}"));
+        let output = serde_json::to_string_pretty(&value)
+            .unwrap_or_else(|_| format!("{value:?}"));
 
-        Ok(CallToolResult::success(vec![Content::text(format!(
-            "Found {tool_count} WebMCP tool(s):\n\n{formatted}"
-        ))]))
+        Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[tool(description = "List or evaluate JavaScript in browser extension service workers. Use action='list' to see running extensions, action='eval' to run JS in an extension's context with userGesture=true (enables chrome.sidePanel.open(), chrome.action.openPopup(), etc).")]
-    async fn extension_eval(
+    #[tool(description = "Query inside a web component's shadow DOM: finds host_selector, then returns elements matching selector within host.shadowRoot (and nested shadow roots, unless recursive=false) with the same fields as query_elements plus shadow depth. Reports clearly when the host's shadow root is closed and therefore not accessible.")]
+    async fn query_shadow(
         &self,
-        Parameters(ExtensionEvalParams { action, query, expression }): Parameters<ExtensionEvalParams>,
+        Parameters(QueryShadowParams { host_selector, selector, recursive }): Parameters<QueryShadowParams>,
     ) -> Result<CallToolResult, McpError> {
-        let url = format!("http://localhost:{}/json", self.port);
-        let client = reqwest::Client::new();
+        let js = format!(
+            r#"(() => {{
+                const host = document.querySelector({host});
+                if (!host) return {{ status: "no_host" }};
+                if (!host.shadowRoot) return {{ status: "no_shadow", tag: host.tagName.toLowerCase() }};
+                const sel = {sel};
+                const RECURSIVE = {recursive};
+                const MAX = 50;
+                const found = [];
+                let total = 0;
+                const walk = (root, depth) => {{
+                    for (const el of root.querySelectorAll(sel)) {{
+                        total++;
+                        if (found.length < MAX) found.push([el, depth]);
+                    }}
+                    if (!RECURSIVE) return;
+                    for (const el of root.querySelectorAll('*')) {{
+                        if (el.shadowRoot) walk(el.shadowRoot, depth + 1);
+                    }}
+                }};
+                walk(host.shadowRoot, 1);
+                const elements = found.map(([el, depth], i) => {{
+                    const rect = el.getBoundingClientRect();
+                    const entry = {{
+                        index: i,
+                        depth,
+                        tag: el.tagName.toLowerCase(),
+                        text: el.textContent.trim().substring(0, 100),
+                        id: el.id || undefined,
+                        class: el.className || undefined,
+                        href: el.getAttribute('href') || undefined,
+                        type: el.getAttribute('type') || undefined,
+                        name: el.getAttribute('name') || undefined,
+                        value: el.value || undefined,
+                        visible: rect.width > 0 && rect.height > 0,
+                    }};
+                    Object.keys(entry).forEach(k => entry[k] === undefined && delete entry[k]);
+                    return entry;
+                }});
+                return {{ status: "ok", total, shown: elements.length, elements }};
+            }})()"#,
+            host = serde_json::to_string(&host_selector).unwrap(),
+            sel = serde_json::to_string(&selector).unwrap(),
+            recursive = recursive.unwrap_or(true),
+        );
 
-        let response = match client.get(&url).send().await {
-            Ok(r) => r,
-            Err(_) => {
-                self.try_reconnect().await.map_err(|msg| McpError::internal_error(msg, None))?;
-                client.get(&url).send().await
-                    .map_err(|e| McpError::internal_error(format!("Failed to list targets: {e}"), None))?
+        let mut value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Query shadow failed: {e}"), None))?;
+
+        match value.get("status").and_then(|v| v.as_str()) {
+            Some("no_host") => {
+                return Err(McpError::invalid_params(format!("Host element not found: {host_selector}"), None));
             }
-        };
+            Some("no_shadow") => {
+                let tag = value.get("tag").and_then(|v| v.as_str()).unwrap_or("?").to_owned();
+                // Page JS sees null for both "no shadow root" and "closed" — ask CDP which it is
+                let closed = self.shadow_root_type(&host_selector).await.as_deref() == Some("closed");
+                let message = if closed {
+                    format!("<{tag}> '{host_selector}' has a closed shadow root — its contents are not accessible from page JavaScript")
+                } else {
+                    format!("<{tag}> '{host_selector}' has no shadow root")
+                };
+                return Err(McpError::invalid_params(message, None));
+            }
+            _ => {}
+        }
 
-        let targets: Vec<serde_json::Value> = response
-            .json()
-            .await
-            .map_err(|e| McpError::internal_error(format!("Failed to parse targets: {e}"), None))?;
+        if let Some(obj) = value.as_object_mut() {
+            obj.remove("status");
+        }
+        let output = serde_json::to_string_pretty(&value)
+            .unwrap_or_else(|_| format!("{value:?}"));
 
-        // Filter to extension targets: service_worker, background_page
-        let ext_types = ["service_worker", "background_page"];
-        let mut extensions: Vec<&serde_json::Value> = targets.iter()
-            .filter(|t| {
-                let t_type = t.get("type").and_then(|v| v.as_str()).unwrap_or("");
-                ext_types.contains(&t_type)
-            })
-            .collect();
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
 
-        // Apply query filter if provided
-        if let Some(ref q) = query {
-            let q_lower = q.to_lowercase();
-            extensions.retain(|t| {
-                let title = t.get("title").and_then(|v| v.as_str()).unwrap_or("").to_lowercase();
-                let url = t.get("url").and_then(|v| v.as_str()).unwrap_or("").to_lowercase();
-                title.contains(&q_lower) || url.contains(&q_lower)
-            });
+    #[tool(description = "Find fixed and stuck-sticky elements covering part of the viewport — sticky headers, cookie banners, chat widgets, modal backdrops — that can intercept clicks meant for content underneath. Returns each overlay's selector, rect, viewport coverage, z-index, and a text snippet, largest first. Use when a click lands on the wrong element, then dismiss it or scroll the target clear.")]
+    async fn find_overlays(
+        &self,
+        Parameters(FindOverlaysParams { min_area_pct }): Parameters<FindOverlaysParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let min_area_pct = min_area_pct.unwrap_or(2.0).max(0.0);
+        let js = format!(
+            r#"(() => {{
+                {unique_selector}
+                const vw = innerWidth, vh = innerHeight;
+                const found = [];
+                for (const el of document.querySelectorAll('body *')) {{
+                    const style = getComputedStyle(el);
+                    if (style.position !== 'fixed' && style.position !== 'sticky') continue;
+                    if (style.display === 'none' || style.visibility === 'hidden' || parseFloat(style.opacity) === 0) continue;
+                    const r = el.getBoundingClientRect();
+                    const w = Math.max(0, Math.min(r.right, vw) - Math.max(r.left, 0));
+                    const h = Math.max(0, Math.min(r.bottom, vh) - Math.max(r.top, 0));
+                    const pct = (w * h) / (vw * vh) * 100;
+                    if (pct < {min_area_pct}) continue;
+                    // A sticky element only overlays content once it's stuck against an edge
+                    if (style.position === 'sticky') {{
+                        const top = parseFloat(style.top), bottom = parseFloat(style.bottom);
+                        const stuck = (!isNaN(top) && Math.abs(r.top - top) < 1) || (!isNaN(bottom) && Math.abs(vh - r.bottom - bottom) < 1);
+                        if (!stuck) continue;
+                    }}
+                    found.push({{
+                        el,
+                        position: style.position,
+                        z_index: style.zIndex === 'auto' ? null : parseInt(style.zIndex, 10),
+                        coverage_pct: Math.round(pct * 10) / 10,
+                        rect: {{ x: Math.round(r.left), y: Math.round(r.top), w: Math.round(r.width), h: Math.round(r.height) }},
+                        intercepts_clicks: style.pointerEvents !== 'none',
+                        text: (el.innerText || '').replace(/\s+/g, ' ').trim().slice(0, 80),
+                    }});
+                }}
+                // Nested fixed elements inside an already-listed overlay add nothing
+                const outermost = found.filter(o => !found.some(p => p !== o && p.el.contains(o.el)));
+                outermost.sort((a, b) => b.coverage_pct - a.coverage_pct);
+                return outermost.map(o => {{ const {{ el, ...rest }} = o; return {{ selector: uniqueSelector(el), ...rest }}; }});
+            }})()"#,
+            unique_selector = JS_UNIQUE_SELECTOR,
+            min_area_pct = serde_json::json!(min_area_pct),
+        );
+
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Find overlays failed: {e}"), None))?;
+        let overlays = value.as_array().cloned().unwrap_or_default();
+        if overlays.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No fixed or sticky overlays covering at least {min_area_pct}% of the viewport"
+            ))]));
         }
 
-        match action.as_str() {
-            "list" => {
-                if extensions.is_empty() {
-                    return Ok(CallToolResult::success(vec![Content::text(
-                        "No extension service workers found".to_owned(),
-                    )]));
-                }
-                let mut output = String::new();
-                for ext in &extensions {
-                    let title = ext.get("title").and_then(|v| v.as_str()).unwrap_or("(untitled)");
-                    let url = ext.get("url").and_then(|v| v.as_str()).unwrap_or("?");
-                    let t_type = ext.get("type").and_then(|v| v.as_str()).unwrap_or("?");
-                    let id = ext.get("id").and_then(|v| v.as_str()).unwrap_or("?");
-                    output.push_str(&format!("[{t_type}] {title}\n  id: {id}\n  url: {url}\n\n"));
-                }
-                Ok(CallToolResult::success(vec![Content::text(output)]))
+        let mut output = format!("{} overlay(s):\n", overlays.len());
+        for overlay in &overlays {
+            let selector = overlay.get("selector").and_then(|v| v.as_str()).unwrap_or("");
+            let position = overlay.get("position").and_then(|v| v.as_str()).unwrap_or("");
+            let coverage = overlay.get("coverage_pct").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let rect = overlay.get("rect");
+            let dim = |k: &str| rect.and_then(|r| r.get(k)).and_then(|v| v.as_i64()).unwrap_or(0);
+            let z = overlay.get("z_index").and_then(|v| v.as_i64()).map(|z| z.to_string()).unwrap_or_else(|| "auto".to_owned());
+            let passthrough = if overlay.get("intercepts_clicks").and_then(|v| v.as_bool()) == Some(false) {
+                ", pointer-events: none"
+            } else {
+                ""
+            };
+            let text = overlay.get("text").and_then(|v| v.as_str()).unwrap_or("");
+            output.push_str(&format!(
+                "  {selector} — {position}, {coverage:.1}% of viewport at ({}, {}) {}×{}, z-index {z}{passthrough}\n",
+                dim("x"), dim("y"), dim("w"), dim("h")
+            ));
+            if !text.is_empty() {
+                output.push_str(&format!("    \"{text}\"\n"));
             }
-            "eval" => {
-                let js = expression.ok_or_else(|| McpError::invalid_params(
-                    "expression is required for eval action", None,
-                ))?;
+        }
 
-                if extensions.is_empty() {
-                    return Err(McpError::invalid_params(
-                        format!("No extension found matching query: {}", query.unwrap_or_default()),
-                        None,
-                    ));
-                }
-                if extensions.len() > 1 && query.is_none() {
-                    let names: Vec<&str> = extensions.iter()
-                        .map(|t| t.get("title").and_then(|v| v.as_str()).unwrap_or("?"))
-                        .collect();
-                    return Err(McpError::invalid_params(
-                        format!("Multiple extensions found, use query to disambiguate: {:?}", names),
-                        None,
-                    ));
-                }
+        Ok(CallToolResult::success(vec![Content::text(output.trim_end().to_owned())]))
+    }
 
-                let target = extensions[0];
-                let ws_url = target.get("webSocketDebuggerUrl")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| McpError::internal_error(
-                        "Extension target has no webSocketDebuggerUrl", None,
-                    ))?;
+    #[tool(description = "Dismiss a cookie/consent banner by clicking its accept (default) or reject button. Tries known consent platforms first (OneTrust, Cookiebot, Quantcast, Didomi, TrustArc, Osano, Complianz), then buttons labelled \"Accept all\", \"I agree\", \"Reject all\", \"Got it\", etc. inside a cookie/consent-looking container. Reports what it clicked and whether the banner went away, or that none was found.")]
+    async fn dismiss_consent(
+        &self,
+        Parameters(DismissConsentParams { choice }): Parameters<DismissConsentParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let choice = choice.unwrap_or_else(|| "accept".to_owned());
+        let (frameworks, texts): (Vec<serde_json::Value>, &[&str]) = match choice.as_str() {
+            "accept" => (
+                CONSENT_FRAMEWORKS.iter().map(|(name, accept, _)| serde_json::json!([name, accept])).collect(),
+                CONSENT_ACCEPT_TEXTS,
+            ),
+            "reject" => (
+                CONSENT_FRAMEWORKS.iter().map(|(name, _, reject)| serde_json::json!([name, reject])).collect(),
+                CONSENT_REJECT_TEXTS,
+            ),
+            other => return Err(McpError::invalid_params(
+                format!("Unknown choice \"{other}\" — use accept or reject"),
+                None,
+            )),
+        };
 
-                // Open a separate CDP connection to the extension's service worker
-                let ext_conn = cdp::connect(ws_url)
-                    .await
-                    .map_err(|e| McpError::internal_error(
-                        format!("Failed to connect to extension: {e}"), None,
-                    ))?;
+        let js = format!(
+            r#"(async () => {{
+                const visible = el => {{
+                    const r = el.getBoundingClientRect();
+                    const s = getComputedStyle(el);
+                    return r.width > 0 && r.height > 0 && s.visibility !== 'hidden' && s.display !== 'none';
+                }};
+                let found = null;
+                for (const [platform, sel] of {frameworks}) {{
+                    const el = Array.from(document.querySelectorAll(sel)).find(visible);
+                    if (el) {{ found = {{ el, via: platform }}; break; }}
+                }}
+                if (!found) {{
+                    const banner = /cookie|consent|gdpr|privacy|cmp|tracking/i;
+                    const inBanner = el => {{
+                        for (let n = el; n && n !== document.body; n = n.parentElement) {{
+                            const label = [n.id, typeof n.className === 'string' ? n.className : '', n.getAttribute('aria-label') || ''].join(' ');
+                            if (banner.test(label)) return true;
+                            if ((n.getAttribute('role') === 'dialog' || n.getAttribute('role') === 'alertdialog') && banner.test(n.innerText || '')) return true;
+                        }}
+                        return false;
+                    }};
+                    const buttons = Array.from(document.querySelectorAll('button, a, [role="button"], input[type="button"], input[type="submit"]'))
+                        .filter(el => visible(el) && inBanner(el));
+                    const norm = el => (el.innerText || el.value || el.getAttribute('aria-label') || '').replace(/\s+/g, ' ').trim().toLowerCase();
+                    for (const text of {texts}) {{
+                        const el = buttons.find(b => norm(b) === text);
+                        if (el) {{ found = {{ el, via: 'text' }}; break; }}
+                    }}
+                }}
+                if (!found) {{
+                    const frames = Array.from(document.querySelectorAll('iframe'))
+                        .filter(f => /consent|cmp|cookie|privacy|sp_message/i.test(f.id + ' ' + f.name + ' ' + f.src) && visible(f)).length;
+                    return {{ found: false, frames }};
+                }}
+                const el = found.el;
+                el.scrollIntoView({{ block: 'center', behavior: 'instant' }});
+                await new Promise(r => requestAnimationFrame(() => requestAnimationFrame(r)));
+                const r = el.getBoundingClientRect();
+                window.__causeway_consent = el;
+                return {{
+                    found: true,
+                    via: found.via,
+                    label: (el.innerText || el.value || el.getAttribute('aria-label') || '').replace(/\s+/g, ' ').trim().slice(0, 60),
+                    x: r.left + r.width / 2,
+                    y: r.top + r.height / 2,
+                }};
+            }})()"#,
+            frameworks = serde_json::Value::Array(frameworks),
+            texts = serde_json::to_string(texts).unwrap(),
+        );
 
-                // Enable Runtime domain on the extension connection
-                cdp::send(&ext_conn, "Runtime.enable", serde_json::json!({}))
-                    .await
-                    .map_err(|e| McpError::internal_error(
-                        format!("Failed to enable Runtime on extension: {e}"), None,
-                    ))?;
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Consent banner search failed: {e}"), None))?;
 
-                // Evaluate with userGesture=true so chrome APIs requiring user action work
-                let result = cdp::send(&ext_conn, "Runtime.evaluate", serde_json::json!({
-                    "expression": js,
-                    "returnByValue": true,
-                    "awaitPromise": true,
-                    "userGesture": true,
-                }))
-                .await
-                .map_err(|e| McpError::internal_error(
-                    format!("Extension eval failed: {e}"), None,
-                ))?;
+        if value.get("found").and_then(|v| v.as_bool()) != Some(true) {
+            let frames = value.get("frames").and_then(|v| v.as_u64()).unwrap_or(0);
+            let hint = if frames > 0 {
+                format!(" ({frames} consent-looking iframe(s) present — the banner may live in a cross-origin frame this tool can't reach; try click at coordinates from a screenshot)")
+            } else {
+                String::new()
+            };
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No consent banner {choice} button found{hint}"
+            ))]));
+        }
 
-                // Extract result or error
-                let title = target.get("title").and_then(|v| v.as_str()).unwrap_or("extension");
-                if let Some(exception) = result.get("exceptionDetails") {
-                    let text = exception
-                        .get("exception")
-                        .and_then(|e| e.get("description").or(e.get("value")))
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("Unknown error");
-                    Ok(CallToolResult::success(vec![Content::text(format!(
-                        "JS Error in {title}: {text}"
-                    ))]))
-                } else {
-                    let value = result
-                        .get("result")
-                        .and_then(|r| r.get("value"))
-                        .cloned()
-                        .unwrap_or(serde_json::Value::Null);
-                    let formatted = if value.is_string() {
-                        value.as_str().unwrap().to_owned()
-                    } else {
-                        serde_json::to_string_pretty(&value).unwrap_or_else(|_| format!("{value}"))
-                    };
-                    Ok(CallToolResult::success(vec![Content::text(format!(
-                        "[{title}] {formatted}"
-                    ))]))
-                }
+        let x = value.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let y = value.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let via = value.get("via").and_then(|v| v.as_str()).unwrap_or("text");
+        let label = value.get("label").and_then(|v| v.as_str()).unwrap_or("");
+        self.execute_seq_reconnect(commands::click(x, y))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Click failed: {e}"), None))?;
 
-                // ext_conn drops here — separate connection, doesn't affect the main page connection
+        // Banners usually fade out — give it a moment before checking
+        tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+        let gone = self.eval_value(
+            "(() => { const el = window.__causeway_consent; delete window.__causeway_consent; \
+             if (!el || !el.isConnected) return true; const r = el.getBoundingClientRect(); \
+             return r.width === 0 || r.height === 0 || getComputedStyle(el).visibility === 'hidden'; })()",
+        )
+        .await
+        .ok()
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+        let source = if via == "text" { "matched by button text".to_owned() } else { format!("{via} banner") };
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Clicked \"{label}\" ({source}) at ({x:.0}, {y:.0}) — {}",
+            if gone { "banner dismissed" } else { "button still visible; the banner may need another step (e.g. a second confirm) or a reload" }
+        ))]))
+    }
+
+    #[tool(description = "List the page's iframes: src, name/title, size, visibility, whether it's same-origin (script-accessible) or cross-origin (inaccessible to page JS), the CDP frame id, and a unique selector for the <iframe> element. Check embedded content (payment widgets, maps, ads) before trying to interact with it. Top-level document only.")]
+    async fn list_iframes(&self) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                {unique_selector}
+                return Array.from(document.querySelectorAll('iframe')).map(f => {{
+                    const r = f.getBoundingClientRect();
+                    let sameOrigin = false;
+                    try {{ sameOrigin = !!f.contentDocument; }} catch (e) {{}}
+                    return {{
+                        src: f.src || (f.srcdoc ? '(srcdoc)' : 'about:blank'),
+                        name: f.name || f.title || null,
+                        w: Math.round(r.width),
+                        h: Math.round(r.height),
+                        visible: r.width > 0 && r.height > 0 && getComputedStyle(f).visibility !== 'hidden',
+                        sameOrigin,
+                        selector: uniqueSelector(f),
+                    }};
+                }});
+            }})()"#,
+            unique_selector = JS_UNIQUE_SELECTOR,
+        );
+
+        let frames = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("List iframes failed: {e}"), None))?
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+
+        if frames.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text("No iframes on this page".to_owned())]));
+        }
+
+        let mut output = format!("{} iframe(s):\n", frames.len());
+        for (i, f) in frames.iter().enumerate() {
+            let s = |key: &str| f.get(key).and_then(|v| v.as_str());
+            let n = |key: &str| f.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+            let access = if f.get("sameOrigin").and_then(|v| v.as_bool()).unwrap_or(false) {
+                "same-origin"
+            } else {
+                "cross-origin (inaccessible)"
+            };
+            let hidden = if f.get("visible").and_then(|v| v.as_bool()).unwrap_or(false) { "" } else { ", hidden" };
+            let frame_id = self.iframe_frame_id(i).await.unwrap_or_else(|| "?".to_owned());
+            output.push_str(&format!(
+                "[{i}] {} — {}×{}{hidden}, {access}\n    frame: {frame_id}",
+                s("src").unwrap_or(""),
+                n("w"),
+                n("h"),
+            ));
+            if let Some(name) = s("name") {
+                output.push_str(&format!(", name: {name}"));
             }
-            _ => Err(McpError::invalid_params(
-                format!("Unknown action: {action}. Use 'list' or 'eval'."),
-                None,
-            )),
+            if let Some(selector) = s("selector") {
+                output.push_str(&format!(", selector: {selector}"));
+            }
+            output.push('\n');
         }
+
+        Ok(CallToolResult::success(vec![Content::text(output.trim_end().to_owned())]))
     }
 
-    #[tool(description = "Inspect the DOM tree starting from a CSS selector. Returns a compact structural view with tag names, key attributes (id, class, href, type, name, value, role, aria-label), and truncated text content. Essential for understanding page structure without screenshots.")]
-    async fn inspect(
+    #[tool(description = "Get an element's position and size: viewport-relative (getBoundingClientRect) and page-absolute (plus scroll offset), and whether it's fully, partially, or not in the viewport. Answers \"is this element where I think it is\" for layout debugging and before coordinate clicks.")]
+    async fn get_rect(
         &self,
-        Parameters(InspectParams { selector, max_depth }): Parameters<InspectParams>,
+        Parameters(GetRectParams { selector }): Parameters<GetRectParams>,
     ) -> Result<CallToolResult, McpError> {
-        let sel = selector.as_deref().unwrap_or("body");
-        let depth = max_depth.unwrap_or(4);
+        let js = format!(
+            r#"(() => {{
+                const el = document.querySelector({sel});
+                if (!el) return null;
+                const r = el.getBoundingClientRect();
+                const vw = window.innerWidth, vh = window.innerHeight;
+                const sx = window.scrollX, sy = window.scrollY;
+                const visW = Math.max(0, Math.min(r.right, vw) - Math.max(r.left, 0));
+                const visH = Math.max(0, Math.min(r.bottom, vh) - Math.max(r.top, 0));
+                const area = r.width * r.height;
+                const visible = area > 0 ? (visW * visH) / area : 0;
+                const round = n => Math.round(n * 10) / 10;
+                return {{
+                    x: round(r.left), y: round(r.top), w: round(r.width), h: round(r.height),
+                    px: round(r.left + sx), py: round(r.top + sy),
+                    sx: round(sx), sy: round(sy), vw, vh,
+                    visible: Math.round(visible * 100),
+                }};
+            }})()"#,
+            sel = serde_json::to_string(&selector).unwrap()
+        );
+
+        let v = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get rect failed: {e}"), None))?;
+
+        if v.is_null() {
+            return Err(McpError::invalid_params(format!("Element not found: {selector}"), None));
+        }
+        let n = |key: &str| v.get(key).and_then(|x| x.as_f64()).unwrap_or(0.0);
+
+        let visible = n("visible");
+        let in_viewport = if n("w") == 0.0 || n("h") == 0.0 {
+            "zero-size (not rendered or collapsed)".to_owned()
+        } else if visible >= 100.0 {
+            "fully in viewport".to_owned()
+        } else if visible > 0.0 {
+            format!("partially in viewport ({visible}% visible)")
+        } else {
+            "outside viewport".to_owned()
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "{selector}\nViewport: x={} y={} w={} h={}\nPage: x={} y={} (scroll {}, {})\nViewport size: {}x{} — {in_viewport}",
+            n("x"), n("y"), n("w"), n("h"),
+            n("px"), n("py"), n("sx"), n("sy"),
+            n("vw"), n("vh"),
+        ))]))
+    }
 
+    #[tool(description = "Map visible text to targets in one call: every visible element with its own (direct) text, as text → unique CSS selector, tag, and position, in reading order. Keeps the most prominent entries (larger, higher, in-viewport first) up to limit. Feed the selectors to click/read_text instead of querying repeatedly.")]
+    async fn map_text(
+        &self,
+        Parameters(MapTextParams { limit, viewport_only }): Parameters<MapTextParams>,
+    ) -> Result<CallToolResult, McpError> {
         let js = format!(
             r#"(() => {{
-                const ATTRS = ['id','class','href','src','type','name','value','role','aria-label','placeholder','action','method','for','alt','title'];
-                const MAX_TEXT = 80;
-                const MAX_CHILDREN = 50;
+                {unique_selector}
+                const LIMIT = {limit};
+                const VIEWPORT_ONLY = {viewport_only};
+                const vw = window.innerWidth, vh = window.innerHeight;
+                const SKIP = new Set(['SCRIPT', 'STYLE', 'NOSCRIPT', 'TEMPLATE', 'HEAD', 'TITLE']);
+                const candidates = [];
+                for (const el of document.body ? document.body.querySelectorAll('*') : []) {{
+                    if (SKIP.has(el.tagName)) continue;
+                    let text = '';
+                    for (const n of el.childNodes) if (n.nodeType === 3) text += n.textContent;
+                    text = text.trim().replace(/\s+/g, ' ');
+                    if (!text) continue;
+                    const r = el.getBoundingClientRect();
+                    if (r.width < 1 || r.height < 1) continue;
+                    const inView = r.bottom > 0 && r.right > 0 && r.top < vh && r.left < vw;
+                    if (VIEWPORT_ONLY && !inView) continue;
+                    const style = getComputedStyle(el);
+                    if (style.visibility === 'hidden' || style.opacity === '0') continue;
+                    const top = r.top + window.scrollY;
+                    // Bigger, higher, and on-screen text ranks first
+                    const score = r.width * r.height * (inView ? 2 : 1) / (1 + Math.max(0, top) / 1000);
+                    candidates.push({{ el, text, r, top, score }});
+                }}
+                const total = candidates.length;
+                const picked = candidates.sort((a, b) => b.score - a.score).slice(0, LIMIT);
+                picked.sort((a, b) => (a.top - b.top) || (a.r.left - b.r.left));
+                return {{
+                    total,
+                    items: picked.map(c => ({{
+                        text: c.text.length > 80 ? c.text.slice(0, 80) + '…' : c.text,
+                        tag: c.el.tagName.toLowerCase(),
+                        selector: uniqueSelector(c.el),
+                        x: Math.round(c.r.left), y: Math.round(c.r.top),
+                        w: Math.round(c.r.width), h: Math.round(c.r.height),
+                    }})),
+                }};
+            }})()"#,
+            unique_selector = JS_UNIQUE_SELECTOR,
+            limit = limit.unwrap_or(100).min(500),
+            viewport_only = viewport_only.unwrap_or(false),
+        );
 
-                function walk(el, depth, maxDepth) {{
-                    if (depth > maxDepth) return '  '.repeat(depth) + '...';
-                    const tag = el.tagName.toLowerCase();
-                    let attrs = '';
-                    for (const a of ATTRS) {{
-                        const v = el.getAttribute(a);
-                        if (v) attrs += ' ' + a + '="' + v.substring(0, 60) + '"';
-                    }}
-                    const indent = '  '.repeat(depth);
-                    let result = indent + '<' + tag + attrs + '>';
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Map text failed: {e}"), None))?;
+        let total = value.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
+        let items = value.get("items").and_then(|v| v.as_array()).cloned().unwrap_or_default();
 
-                    const children = el.children;
-                    const textContent = el.childNodes.length === 1 && el.childNodes[0].nodeType === 3
-                        ? el.childNodes[0].textContent.trim() : null;
+        if items.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text("No visible text found".to_owned())]));
+        }
 
-                    if (textContent && textContent.length > 0) {{
-                        const t = textContent.length > MAX_TEXT
-                            ? textContent.substring(0, MAX_TEXT) + '...' : textContent;
-                        result += t + '</' + tag + '>';
-                        return result;
-                    }}
+        let output = items
+            .iter()
+            .map(|item| {
+                let text = item.get("text").and_then(|v| v.as_str()).unwrap_or("");
+                let tag = item.get("tag").and_then(|v| v.as_str()).unwrap_or("?");
+                let selector = item.get("selector").and_then(|v| v.as_str()).unwrap_or("(no unique selector)");
+                let n = |k: &str| item.get(k).and_then(|v| v.as_i64()).unwrap_or(0);
+                format!(
+                    "\"{text}\" → {selector} <{tag}> @{},{} {}x{}",
+                    n("x"), n("y"), n("w"), n("h")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
 
-                    if (children.length === 0) {{
-                        const t = el.textContent.trim();
-                        if (t.length > 0) {{
-                            const truncated = t.length > MAX_TEXT ? t.substring(0, MAX_TEXT) + '...' : t;
-                            result += truncated + '</' + tag + '>';
-                        }} else {{
-                            result = indent + '<' + tag + attrs + ' />';
-                        }}
-                        return result;
-                    }}
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "{} of {total} text element(s), in reading order (viewport coordinates):\n{output}",
+            items.len()
+        ))]))
+    }
 
-                    result += '\n';
-                    const len = Math.min(children.length, MAX_CHILDREN);
-                    for (let i = 0; i < len; i++) {{
-                        result += walk(children[i], depth + 1, maxDepth) + '\n';
-                    }}
-                    if (children.length > MAX_CHILDREN) {{
-                        result += indent + '  ... +' + (children.length - MAX_CHILDREN) + ' more\n';
-                    }}
-                    result += indent + '</' + tag + '>';
-                    return result;
+    #[tool(description = "List all links (<a href>) on the page with absolute URLs, visible text, and whether each is external (different origin). Deduplicated by URL and capped. Better than query_elements for crawling — no relative-URL confusion.")]
+    async fn get_links(
+        &self,
+        Parameters(GetLinksParams { limit, external_only }): Parameters<GetLinksParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                const MAX = {limit};
+                const seen = new Set();
+                const links = [];
+                let total = 0;
+                for (const a of document.querySelectorAll('a[href]')) {{
+                    let url;
+                    try {{ url = new URL(a.getAttribute('href'), location.href); }} catch (e) {{ continue; }}
+                    if (!/^https?:$/.test(url.protocol)) continue;
+                    url.hash = '';
+                    const href = url.href;
+                    if (seen.has(href)) continue;
+                    seen.add(href);
+                    const external = url.origin !== location.origin;
+                    if ({external_only} && !external) continue;
+                    total++;
+                    if (links.length >= MAX) continue;
+                    const text = (a.innerText || a.getAttribute('aria-label') || a.title || '').trim().replace(/\s+/g, ' ').substring(0, 80);
+                    links.push({{ url: href, text, external }});
                 }}
+                return {{ total, links }};
+            }})()"#,
+            limit = limit.unwrap_or(200),
+            external_only = external_only.unwrap_or(false),
+        );
 
-                const root = document.querySelector({sel});
-                if (!root) return null;
-                return walk(root, 0, {depth});
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get links failed: {e}"), None))?;
+        let total = value.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
+        let links = value.get("links").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+
+        if links.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text("No links found".to_owned())]));
+        }
+
+        let output = links
+            .iter()
+            .map(|l| {
+                let url = l.get("url").and_then(|v| v.as_str()).unwrap_or("");
+                let text = l.get("text").and_then(|v| v.as_str()).unwrap_or("");
+                let ext = if l.get("external").and_then(|v| v.as_bool()).unwrap_or(false) { " [external]" } else { "" };
+                format!("{url}{ext} — {text}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "{total} unique link(s), showing {}:\n{output}",
+            links.len()
+        ))]))
+    }
+
+    #[tool(description = "List <img> elements with resolved src, alt text, natural and displayed size, and whether each loaded. Broken images are listed first. For content audits and catching broken images that screenshots miss.")]
+    async fn get_images(
+        &self,
+        Parameters(GetImagesParams { limit }): Parameters<GetImagesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                const MAX = {limit};
+                const imgs = Array.from(document.images).map(img => {{
+                    const rect = img.getBoundingClientRect();
+                    return {{
+                        src: img.currentSrc || img.src,
+                        alt: img.hasAttribute('alt') ? img.alt : null,
+                        natural: img.naturalWidth + 'x' + img.naturalHeight,
+                        displayed: Math.round(rect.width) + 'x' + Math.round(rect.height),
+                        loaded: img.complete && img.naturalWidth > 0,
+                        pending: !img.complete,
+                    }};
+                }});
+                imgs.sort((a, b) => a.loaded - b.loaded);
+                return {{ total: imgs.length, broken: imgs.filter(i => !i.loaded && !i.pending).length, images: imgs.slice(0, MAX) }};
             }})()"#,
-            sel = serde_json::to_string(sel).unwrap(),
-            depth = depth
+            limit = limit.unwrap_or(100),
         );
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let value = self.eval_value(&js)
             .await
-            .map_err(|e| McpError::internal_error(format!("Inspect failed: {e}"), None))?;
+            .map_err(|e| McpError::internal_error(format!("Get images failed: {e}"), None))?;
+        let total = value.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
+        let broken = value.get("broken").and_then(|v| v.as_u64()).unwrap_or(0);
+        let images = value.get("images").and_then(|v| v.as_array()).cloned().unwrap_or_default();
 
-        let value = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .and_then(|v| v.as_str());
+        if images.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text("No images found".to_owned())]));
+        }
 
-        match value {
-            Some(tree) => {
-                let truncated = if tree.len() > 15000 {
-                    format!("{}...\n\n[Truncated — {} total chars]", &tree[..15000], tree.len())
+        let output = images
+            .iter()
+            .map(|img| {
+                let src = img.get("src").and_then(|v| v.as_str()).unwrap_or("");
+                let alt = match img.get("alt").and_then(|v| v.as_str()) {
+                    Some(a) => format!("\"{a}\""),
+                    None => "(no alt)".to_owned(),
+                };
+                let natural = img.get("natural").and_then(|v| v.as_str()).unwrap_or("?");
+                let displayed = img.get("displayed").and_then(|v| v.as_str()).unwrap_or("?");
+                let status = if img.get("loaded").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    "ok"
+                } else if img.get("pending").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    "LOADING"
                 } else {
-                    tree.to_owned()
+                    "BROKEN"
                 };
-                Ok(CallToolResult::success(vec![Content::text(truncated)]))
-            }
-            None => Err(McpError::invalid_params(
-                format!("Element not found: {sel}"),
-                None,
-            )),
-        }
+                format!("[{status}] {src} alt={alt} natural={natural} displayed={displayed}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "{total} image(s), {broken} broken, showing {}:\n{output}",
+            images.len()
+        ))]))
     }
 
-    #[tool(description = "Identify the DOM element at specific x/y coordinates. Returns the element and its parent chain with tag, classes, id, role, and text. Use after a screenshot to discover what's at a specific spot — especially useful for custom/obfuscated UIs where CSS selectors are unknown.")]
-    async fn point_inspect(
+    #[tool(description = "Extract a <table> as JSON: headers plus rows as objects (header → cell text). Uses <thead> (or a leading row of <th> cells) for headers, expands colspan best-effort, and ignores nested tables. Errors if the selector doesn't match a table.")]
+    async fn extract_table(
         &self,
-        Parameters(PointInspectParams { x, y }): Parameters<PointInspectParams>,
+        Parameters(ExtractTableParams { selector, max_rows }): Parameters<ExtractTableParams>,
     ) -> Result<CallToolResult, McpError> {
         let js = format!(
             r#"(() => {{
-                const ATTRS = ['id','class','role','type','name','href','aria-label','data-testid','tabindex','onclick'];
-                const el = document.elementFromPoint({x}, {y});
-                if (!el) return null;
-
-                function describe(node) {{
-                    const tag = node.tagName.toLowerCase();
-                    let attrs = {{}};
-                    for (const a of ATTRS) {{
-                        const v = node.getAttribute(a);
-                        if (v && v.length > 0) attrs[a] = v.substring(0, 120);
+                const table = document.querySelector({sel});
+                if (!table) return {{ error: 'not_found' }};
+                if (table.tagName !== 'TABLE') return {{ error: 'not_table', tag: table.tagName.toLowerCase() }};
+                const MAX = {max_rows};
+                const cellsOf = (row) => {{
+                    const out = [];
+                    for (const cell of row.cells) {{
+                        const text = (cell.innerText || '').trim().replace(/\s+/g, ' ');
+                        const span = Math.max(1, Math.min(cell.colSpan || 1, 50));
+                        for (let i = 0; i < span; i++) out.push(text);
                     }}
-                    const text = (node.textContent || '').trim();
-                    const rect = node.getBoundingClientRect();
-                    return {{
-                        tag,
-                        attrs,
-                        text: text.substring(0, 100),
-                        bounds: {{ x: Math.round(rect.x), y: Math.round(rect.y), w: Math.round(rect.width), h: Math.round(rect.height) }}
-                    }};
-                }}
-
-                const chain = [];
-                let cur = el;
-                for (let i = 0; i < 6 && cur && cur !== document.documentElement; i++) {{
-                    chain.push(describe(cur));
-                    cur = cur.parentElement;
+                    return out;
+                }};
+                // table.rows covers thead/tbody/tfoot of this table only — nested tables excluded
+                const rows = Array.from(table.rows);
+                let headerRows = table.tHead ? Array.from(table.tHead.rows) : [];
+                if (!headerRows.length && rows.length && Array.from(rows[0].cells).every(c => c.tagName === 'TH')) {{
+                    headerRows = [rows[0]];
                 }}
-                return chain;
+                let headers = headerRows.length ? cellsOf(headerRows[headerRows.length - 1]) : [];
+                const body = rows.filter(r => !headerRows.includes(r));
+                const width = Math.max(headers.length, ...body.map(r => cellsOf(r).length), 0);
+                // Fill blank/missing/duplicate headers so every key is unique. A Set and a
+                // null-prototype row object keep names like "constructor" or "__proto__" plain keys.
+                const used = new Set();
+                headers = Array.from({{ length: width }}, (_, i) => {{
+                    const base = headers[i] || ('column_' + (i + 1));
+                    let h = base;
+                    for (let n = 2; used.has(h); n++) h = base + '_' + n;
+                    used.add(h);
+                    return h;
+                }});
+                const data = body.slice(0, MAX).map(r => {{
+                    const cells = cellsOf(r);
+                    const obj = Object.create(null);
+                    headers.forEach((h, i) => {{ obj[h] = cells[i] !== undefined ? cells[i] : ''; }});
+                    return obj;
+                }});
+                return {{ headers, total_rows: body.length, rows: data }};
             }})()"#,
-            x = x,
-            y = y,
+            sel = serde_json::to_string(&selector).unwrap(),
+            max_rows = max_rows.unwrap_or(500),
         );
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let value = self.eval_value(&js)
             .await
-            .map_err(|e| McpError::internal_error(format!("Point inspect failed: {e}"), None))?;
+            .map_err(|e| McpError::internal_error(format!("Extract table failed: {e}"), None))?;
 
-        let value = result
-            .get("result")
-            .and_then(|r| r.get("value"));
-
-        match value {
-            Some(serde_json::Value::Null) | None => {
-                Ok(CallToolResult::success(vec![Content::text(
-                    format!("No element found at ({x}, {y})")
-                )]))
+        match value.get("error").and_then(|v| v.as_str()) {
+            Some("not_found") => {
+                return Err(McpError::invalid_params(format!("Element not found: {selector}"), None));
             }
-            Some(chain) => {
-                let formatted = serde_json::to_string_pretty(chain).unwrap_or_else(|_| chain.to_string());
-                Ok(CallToolResult::success(vec![Content::text(formatted)]))
+            Some("not_table") => {
+                let tag = value.get("tag").and_then(|v| v.as_str()).unwrap_or("?");
+                return Err(McpError::invalid_params(
+                    format!("'{selector}' matched a <{tag}>, not a <table>"),
+                    None,
+                ));
             }
+            _ => {}
         }
+
+        let output = serde_json::to_string_pretty(&value)
+            .unwrap_or_else(|_| format!("{value:?}"));
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[tool(description = "Get the declared CSS styles for an element — shows the actual authored values (rem, var(), %, etc.) not just resolved pixels. Resolves CSS variable references to their declared values. Use this instead of getComputedStyle when you need the real design tokens.")]
-    async fn inspect_styles(
-        &self,
-        Parameters(InspectStylesParams { selector, properties }): Parameters<InspectStylesParams>,
-    ) -> Result<CallToolResult, McpError> {
-        let default_props = vec![
-            "font-size", "font-weight", "font-family", "line-height", "letter-spacing",
-            "color", "background-color", "background",
-            "padding", "padding-top", "padding-right", "padding-bottom", "padding-left",
-            "margin", "margin-top", "margin-right", "margin-bottom", "margin-left",
-            "width", "max-width", "min-width", "height", "max-height", "min-height",
-            "display", "position", "gap", "border-radius",
-            "border", "border-top", "border-bottom", "border-left", "border-right",
-            "box-shadow", "text-align", "text-transform", "opacity",
-        ];
+    #[tool(description = "Get page metadata as JSON: <meta> tags (name/property/http-equiv → content, e.g. og:title, description, twitter:card), canonical URL, favicon URL, and document language. For link-preview and SEO checks without hand-written evaluate_js.")]
+    async fn get_metadata(&self) -> Result<CallToolResult, McpError> {
+        let js = r#"(() => {
+            const meta = {};
+            for (const m of document.querySelectorAll('meta')) {
+                const key = m.getAttribute('property') || m.getAttribute('name') || m.getAttribute('http-equiv') || m.getAttribute('itemprop');
+                if (m.hasAttribute('charset')) { meta['charset'] = m.getAttribute('charset'); continue; }
+                if (!key || !m.hasAttribute('content')) continue;
+                const content = m.getAttribute('content');
+                // Repeated keys (og:image, article:tag) collect into an array
+                if (key in meta) {
+                    meta[key] = Array.isArray(meta[key]) ? [...meta[key], content] : [meta[key], content];
+                } else {
+                    meta[key] = content;
+                }
+            }
+            const canonical = document.querySelector('link[rel="canonical"]');
+            const icon = document.querySelector('link[rel~="icon"]') || document.querySelector('link[rel="apple-touch-icon"]');
+            return {
+                title: document.title || null,
+                lang: document.documentElement.lang || null,
+                canonical: canonical ? canonical.href : null,
+                favicon: icon ? icon.href : new URL('/favicon.ico', location.href).href,
+                meta,
+            };
+        })()"#;
 
-        let props_json = match &properties {
-            Some(p) => serde_json::to_string(p).unwrap(),
-            None => serde_json::to_string(&default_props).unwrap(),
-        };
+        let value = self.eval_value(js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get metadata failed: {e}"), None))?;
 
-        let js = format!(
-            r#"(() => {{
-                const el = document.querySelector({sel});
-                if (!el) return {{ error: "Element not found" }};
+        let output = serde_json::to_string_pretty(&value)
+            .unwrap_or_else(|_| format!("{value:?}"));
 
-                const props = {props};
-                const computed = getComputedStyle(el);
-                const results = [];
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Get the page's language and encoding as JSON: <html lang>, the declared <meta charset> vs. the document.characterSet actually used, the dir attribute, the computed text direction, and navigator.language. For localization checks.")]
+    async fn get_page_language(&self) -> Result<CallToolResult, McpError> {
+        let js = r#"(() => {
+            const root = document.documentElement;
+            const charsetMeta = document.querySelector('meta[charset]');
+            const httpEquiv = document.querySelector('meta[http-equiv="Content-Type" i]');
+            const declared = charsetMeta ? charsetMeta.getAttribute('charset')
+                : httpEquiv ? ((httpEquiv.getAttribute('content') || '').match(/charset=([^;\s]+)/i) || [])[1] || null
+                : null;
+            return {
+                lang: root.lang || null,
+                content_language: (document.querySelector('meta[http-equiv="Content-Language" i]') || {}).content || null,
+                declared_charset: declared,
+                character_set: document.characterSet,
+                dir: root.getAttribute('dir') || (document.body && document.body.getAttribute('dir')) || null,
+                computed_direction: getComputedStyle(document.body || root).direction,
+                browser_language: navigator.language,
+            };
+        })()"#;
+
+        let value = self.eval_value(js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get page language failed: {e}"), None))?;
+
+        let output = serde_json::to_string_pretty(&value)
+            .unwrap_or_else(|_| format!("{value:?}"));
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Get the site's favicon as an image. Uses the largest <link rel=\"icon\"> the page declares, falling back to /favicon.ico. Fetched from inside the page (with its cookies), or through the browser's network stack when CORS blocks that. Reports clearly when the site has no favicon.")]
+    async fn get_favicon(&self) -> Result<CallToolResult, McpError> {
+        let js = r#"(async () => {
+            const size = l => Math.max(0, ...(l.sizes ? Array.from(l.sizes) : []).map(s => s === 'any' ? 1e6 : parseInt(s, 10) || 0));
+            const links = Array.from(document.querySelectorAll('link[rel]'))
+                .filter(l => l.href && l.rel.toLowerCase().split(/\s+/).includes('icon'))
+                .sort((a, b) => size(b) - size(a));
+            const declared = links.length > 0;
+            const url = declared ? links[0].href : new URL('/favicon.ico', location.href).href;
+            try {
+                const r = await fetch(url, { credentials: 'include' });
+                if (!r.ok) return { url, declared, status: r.status };
+                const blob = await r.blob();
+                const dataUrl = await new Promise((resolve, reject) => {
+                    const reader = new FileReader();
+                    reader.onload = () => resolve(reader.result);
+                    reader.onerror = () => reject(reader.error);
+                    reader.readAsDataURL(blob);
+                });
+                return { url, declared, mime: blob.type, data: dataUrl.slice(dataUrl.indexOf(',') + 1) };
+            } catch (e) {
+                return { url, declared, error: String(e) };
+            }
+        })()"#;
+
+        let value = self.eval_value(js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Favicon lookup failed: {e}"), None))?;
+
+        let url = value.get("url").and_then(|v| v.as_str()).unwrap_or("").to_owned();
+        let source = if value.get("declared").and_then(|v| v.as_bool()).unwrap_or(false) {
+            "declared <link rel=icon>"
+        } else {
+            "default /favicon.ico"
+        };
+        let not_found = |detail: String| Ok(CallToolResult::success(vec![Content::text(format!(
+            "No favicon — {source} at {url} {detail}"
+        ))]));
+
+        if let Some(status) = value.get("status").and_then(|v| v.as_u64()) {
+            return not_found(format!("returned HTTP {status}"));
+        }
 
-                // Collect all matched CSS rules for this element
-                const ruleMap = new Map();
-                for (const sheet of document.styleSheets) {{
-                    try {{
-                        for (const rule of sheet.cssRules || []) {{
-                            if (rule.selectorText && el.matches(rule.selectorText)) {{
-                                for (const prop of props) {{
-                                    const val = rule.style.getPropertyValue(prop);
-                                    if (val) ruleMap.set(prop, {{ value: val.trim(), selector: rule.selectorText }});
-                                }}
-                            }}
-                        }}
-                    }} catch (e) {{ /* cross-origin sheets */ }}
-                }}
+        let (mime, data) = match (value.get("mime").and_then(|v| v.as_str()), value.get("data").and_then(|v| v.as_str())) {
+            (Some(mime), Some(data)) => (mime.to_owned(), data.to_owned()),
+            // In-page fetch failed (usually CORS on a CDN-hosted icon) — load it through the browser,
+            // which keeps the page's proxy and network rather than reaching out from this process
+            _ => {
+                let http = url.starts_with("http://") || url.starts_with("https://");
+                if !http || check_domain_policy(&self.browser_config, &url).is_err() {
+                    return not_found("could not be fetched".to_owned());
+                }
+                match self.load_network_resource(&url).await {
+                    Ok((status, _, _)) if !(200..300).contains(&status) => {
+                        return not_found(format!("returned HTTP {status}"));
+                    }
+                    Ok((_, mime, data)) => (mime, data),
+                    Err(e) => return not_found(format!("could not be fetched: {e}")),
+                }
+            }
+        };
 
-                // Also check inline styles
-                for (const prop of props) {{
-                    const inline = el.style.getPropertyValue(prop);
-                    if (inline) ruleMap.set(prop, {{ value: inline.trim(), selector: "inline" }});
-                }}
+        // Servers often answer a missing /favicon.ico with an HTML error page and 200
+        if !mime.starts_with("image/") || data.is_empty() {
+            let shown = if mime.is_empty() { "no content type" } else { mime.as_str() };
+            return not_found(format!("is not an image ({shown})"));
+        }
 
-                // Resolve var() references
-                function resolveVar(value) {{
-                    const varMatch = value.match(/var\(--([^,)]+)/);
-                    if (!varMatch) return null;
-                    const varName = '--' + varMatch[1].trim();
-                    // Walk up the tree to find where the variable is defined
-                    let node = el;
-                    while (node) {{
-                        const val = getComputedStyle(node).getPropertyValue(varName).trim();
-                        if (val) return {{ varName, resolved: val }};
-                        node = node.parentElement;
-                    }}
-                    return null;
-                }}
+        let size_kb = data.len() * 3 / 4 / 1024;
+        Ok(CallToolResult::success(vec![
+            Content::image(data, mime.clone()),
+            Content::text(format!("Favicon from {source}: {url} ({mime}, {size_kb}KB)")),
+        ]))
+    }
 
-                for (const prop of props) {{
-                    const rule = ruleMap.get(prop);
-                    const computedVal = computed.getPropertyValue(prop).trim();
-                    if (!rule && !computedVal) continue;
+    #[tool(description = "Get the Largest Contentful Paint: the element (tag + selector), its rendered size, the LCP time in ms since navigation start, and the image URL if it's an image. The first call installs an observer as an init script so later page loads are measured from their very start; the current page is read from the browser's buffered LCP entries.")]
+    async fn get_lcp(&self) -> Result<CallToolResult, McpError> {
+        if !self.lcp_observer.swap(true, std::sync::atomic::Ordering::Relaxed) {
+            if let Err(e) = self.execute_reconnect(commands::add_init_script(JS_LCP_OBSERVER)).await {
+                self.lcp_observer.store(false, std::sync::atomic::Ordering::Relaxed);
+                return Err(McpError::internal_error(format!("Install LCP observer failed: {e}"), None));
+            }
+        }
 
-                    const entry = {{ property: prop, computed: computedVal }};
-                    if (rule) {{
-                        entry.declared = rule.value;
-                        entry.from = rule.selector;
-                        const resolved = resolveVar(rule.value);
-                        if (resolved) {{
-                            entry.variable = resolved.varName;
-                            entry.resolvedVar = resolved.resolved;
-                        }}
+        let js = format!(
+            r#"(async () => {{
+                {unique_selector}
+                let entry = window.__causeway_lcp;
+                let source = 'init_script';
+                if (!entry) {{
+                    source = 'buffered';
+                    if (typeof PerformanceObserver === 'undefined'
+                        || !(PerformanceObserver.supportedEntryTypes || []).includes('largest-contentful-paint')) {{
+                        return {{ supported: false }};
                     }}
-                    results.push(entry);
+                    entry = await new Promise(resolve => {{
+                        const observer = new PerformanceObserver(list => {{
+                            const entries = list.getEntries();
+                            observer.disconnect();
+                            resolve(entries[entries.length - 1] || null);
+                        }});
+                        observer.observe({{ type: 'largest-contentful-paint', buffered: true }});
+                        setTimeout(() => {{ observer.disconnect(); resolve(null); }}, 200);
+                    }});
                 }}
-
-                // Get element info
-                const tag = el.tagName.toLowerCase();
-                const cls = el.className ? '.' + el.className.split(/\s+/).join('.') : '';
-                const id = el.id ? '#' + el.id : '';
-
-                return {{ element: tag + id + cls, results }};
+                if (!entry) return {{ supported: true, found: false }};
+                const el = entry.element;
+                return {{
+                    supported: true,
+                    found: true,
+                    source,
+                    time_ms: Math.round(entry.renderTime || entry.loadTime || entry.startTime),
+                    size: entry.size,
+                    url: entry.url || null,
+                    element: el ? {{ tag: el.tagName.toLowerCase(), selector: el.isConnected ? uniqueSelector(el) : null }} : null,
+                }};
             }})()"#,
-            sel = serde_json::to_string(&selector).unwrap(),
-            props = props_json,
+            unique_selector = JS_UNIQUE_SELECTOR,
         );
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let value = self.eval_value(&js)
             .await
-            .map_err(|e| McpError::internal_error(format!("Style inspection failed: {e}"), None))?;
-
-        let val = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .ok_or_else(|| McpError::internal_error("No result from style inspection".to_owned(), None))?;
+            .map_err(|e| McpError::internal_error(format!("Get LCP failed: {e}"), None))?;
 
-        if let Some(err) = val.get("error").and_then(|v| v.as_str()) {
-            return Err(McpError::invalid_params(err.to_owned(), None));
+        if value.get("supported").and_then(|v| v.as_bool()) == Some(false) {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Largest Contentful Paint is not supported in this browser".to_owned(),
+            )]));
+        }
+        if value.get("found").and_then(|v| v.as_bool()) != Some(true) {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No LCP entry yet — the page may not have painted content, or this is a non-HTML page. The observer is now installed; reload to measure from the start.".to_owned(),
+            )]));
         }
 
-        let element = val.get("element").and_then(|v| v.as_str()).unwrap_or("?");
-        let results = val.get("results").and_then(|v| v.as_array());
+        let output = serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string());
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
 
-        let mut output = format!("Styles for: {element}\n\n");
+    #[tool(description = "Get the page's heading outline: every h1–h6 (and role=\"heading\" with aria-level) in document order, indented by level. Flags skipped levels (e.g. h1 → h3), a missing or repeated h1, and empty headings — common accessibility problems. A fast structural overview lighter than inspect.")]
+    async fn get_outline(&self) -> Result<CallToolResult, McpError> {
+        let js = r#"(() => {
+            const nodes = document.querySelectorAll('h1, h2, h3, h4, h5, h6, [role="heading"]');
+            return Array.from(nodes).map(el => {
+                const aria = parseInt(el.getAttribute('aria-level'), 10);
+                const tagLevel = /^H[1-6]$/.test(el.tagName) ? parseInt(el.tagName[1], 10) : 2;
+                const style = getComputedStyle(el);
+                return {
+                    level: aria >= 1 ? aria : tagLevel,
+                    text: (el.innerText || el.textContent || '').replace(/\s+/g, ' ').trim().slice(0, 120),
+                    hidden: style.display === 'none' || style.visibility === 'hidden' || el.closest('[hidden], [aria-hidden="true"]') !== null,
+                };
+            });
+        })()"#;
 
-        if let Some(entries) = results {
-            for entry in entries {
-                let prop = entry.get("property").and_then(|v| v.as_str()).unwrap_or("?");
-                let computed = entry.get("computed").and_then(|v| v.as_str()).unwrap_or("");
-                let declared = entry.get("declared").and_then(|v| v.as_str());
-                let from = entry.get("from").and_then(|v| v.as_str());
-                let var_name = entry.get("variable").and_then(|v| v.as_str());
-                let resolved = entry.get("resolvedVar").and_then(|v| v.as_str());
+        let value = self.eval_value(js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get outline failed: {e}"), None))?;
+        let headings = value.as_array().cloned().unwrap_or_default();
+        if headings.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No headings on this page".to_owned(),
+            )]));
+        }
 
-                if let Some(decl) = declared {
-                    output.push_str(&format!("{prop}: {decl}"));
-                    if let (Some(vn), Some(rv)) = (var_name, resolved) {
-                        output.push_str(&format!("  →  {vn}: {rv}"));
-                    }
-                    output.push_str(&format!("  (computed: {computed})"));
-                    if let Some(f) = from {
-                        output.push_str(&format!("  [{f}]"));
-                    }
-                } else {
-                    output.push_str(&format!("{prop}: {computed}"));
-                }
-                output.push('\n');
+        let mut lines = Vec::with_capacity(headings.len());
+        let mut issues = Vec::new();
+        let mut previous = 0u64;
+        let mut h1_count = 0;
+        for heading in &headings {
+            let level = heading.get("level").and_then(|v| v.as_u64()).unwrap_or(2).clamp(1, 6);
+            let text = heading.get("text").and_then(|v| v.as_str()).unwrap_or("");
+            let hidden = heading.get("hidden").and_then(|v| v.as_bool()).unwrap_or(false);
+            if level == 1 {
+                h1_count += 1;
+            }
+
+            let mut notes = Vec::new();
+            if previous > 0 && level > previous + 1 {
+                notes.push(format!("skipped h{}", (previous + 1..level).map(|l| l.to_string()).collect::<Vec<_>>().join(", h")));
+                issues.push(format!("h{previous} → h{level} skips a level before \"{text}\""));
+            }
+            if text.is_empty() {
+                notes.push("empty".to_owned());
+                issues.push(format!("Empty h{level}"));
+            }
+            if hidden {
+                notes.push("hidden".to_owned());
             }
+            let indent = "  ".repeat(level as usize - 1);
+            let shown = if text.is_empty() { "(empty)" } else { text };
+            let suffix = if notes.is_empty() { String::new() } else { format!("  [{}]", notes.join(", ")) };
+            lines.push(format!("{indent}h{level} {shown}{suffix}"));
+            previous = level;
+        }
+        match h1_count {
+            0 => issues.insert(0, "No h1 on the page".to_owned()),
+            1 => {}
+            n => issues.insert(0, format!("{n} h1 headings — usually there should be one")),
+        }
+
+        let mut output = format!("{} heading(s):\n{}", headings.len(), lines.join("\n"));
+        if !issues.is_empty() {
+            output.push_str(&format!("\n\n{} issue(s):\n  {}", issues.len(), issues.join("\n  ")));
         }
 
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[tool(description = "Find all elements matching a CSS selector and return their tag, text content, key attributes, and count. Useful for finding interactive elements, links, buttons, form fields, etc.")]
-    async fn query_elements(
+    #[tool(description = "Search the whole document with CDP's native DOM search (DOM.performSearch). Accepts plain text, a CSS selector, or XPath — CDP detects which. Reaches into shadow DOM and matches text nodes. Complements query_elements.")]
+    async fn search_dom(
         &self,
-        Parameters(QueryElementsParams { selector }): Parameters<QueryElementsParams>,
+        Parameters(SearchDomParams { query, limit }): Parameters<SearchDomParams>,
     ) -> Result<CallToolResult, McpError> {
-        let js = format!(
-            r#"(() => {{
-                const els = document.querySelectorAll({sel});
-                const results = [];
-                const MAX = 50;
-                for (let i = 0; i < Math.min(els.length, MAX); i++) {{
-                    const el = els[i];
-                    const rect = el.getBoundingClientRect();
-                    const entry = {{
-                        index: i,
-                        tag: el.tagName.toLowerCase(),
-                        text: el.textContent.trim().substring(0, 100),
-                        id: el.id || undefined,
-                        class: el.className || undefined,
-                        href: el.getAttribute('href') || undefined,
-                        type: el.getAttribute('type') || undefined,
-                        name: el.getAttribute('name') || undefined,
-                        value: el.value || undefined,
-                        visible: rect.width > 0 && rect.height > 0,
-                    }};
-                    // Remove undefined keys
-                    Object.keys(entry).forEach(k => entry[k] === undefined && delete entry[k]);
-                    results.push(entry);
-                }}
-                return {{ total: els.length, shown: Math.min(els.length, MAX), elements: results }};
-            }})()"#,
-            sel = serde_json::to_string(&selector).unwrap()
-        );
+        // performSearch only returns nodes the client knows about — request the document first
+        self.execute_reconnect(commands::get_document())
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get document failed: {e}"), None))?;
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let search = self.execute_reconnect(commands::perform_search(&query))
             .await
-            .map_err(|e| McpError::internal_error(format!("Query failed: {e}"), None))?;
+            .map_err(|e| McpError::internal_error(format!("DOM search failed: {e}"), None))?;
 
-        let value = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .cloned()
-            .unwrap_or(serde_json::Value::Null);
+        let search_id = search
+            .get("searchId")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| McpError::internal_error("No searchId returned".to_owned(), None))?
+            .to_owned();
+        let total = search.get("resultCount").and_then(|v| v.as_u64()).unwrap_or(0);
+        let shown = total.min(limit.unwrap_or(50));
+
+        let mut lines = Vec::new();
+        if shown > 0 {
+            let results = self.execute_reconnect(commands::get_search_results(&search_id, 0, shown)).await;
+            let node_ids: Vec<i64> = results
+                .as_ref()
+                .ok()
+                .and_then(|r| r.get("nodeIds"))
+                .and_then(|v| v.as_array())
+                .map(|a| a.iter().filter_map(|v| v.as_i64()).collect())
+                .unwrap_or_default();
+
+            for (i, node_id) in node_ids.iter().enumerate() {
+                let Ok(desc) = self.execute_reconnect(commands::describe_node(*node_id)).await else {
+                    continue;
+                };
+                let Some(node) = desc.get("node") else { continue };
+                lines.push(format!("{}. {}", i + 1, describe_dom_node(node)));
+            }
+        }
 
-        let output = serde_json::to_string_pretty(&value)
-            .unwrap_or_else(|_| format!("{value:?}"));
+        // Always release the search session
+        let _ = self.execute_reconnect(commands::discard_search_results(&search_id)).await;
 
-        Ok(CallToolResult::success(vec![Content::text(output)]))
+        if total == 0 {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No matches for: {query}"
+            ))]));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "{total} match(es), showing {}:\n{}",
+            lines.len(),
+            lines.join("\n")
+        ))]))
+    }
+
+    #[tool(description = "Count open tabs and show which one Causeway is attached to — a cheap check for branching, without list_tabs' full listing.")]
+    async fn tab_count(&self) -> Result<CallToolResult, McpError> {
+        let result = self.exec_with_reconnect("Target.getTargets", serde_json::json!({}))
+            .await
+            .map_err(|e| McpError::internal_error(format!("List targets failed: {e}"), None))?;
+        let pages: Vec<serde_json::Value> = result
+            .get("targetInfos")
+            .and_then(|v| v.as_array())
+            .map(|targets| {
+                targets.iter()
+                    .filter(|t| t.get("type").and_then(|v| v.as_str()) == Some("page"))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Attached target if the connection knows it, else the pinned tab, else match on URL
+        let attached = match self.live.target_id().await {
+            Some(id) => Some(id),
+            None => self.sticky_target.lock().await.clone(),
+        };
+        let current_url = match attached {
+            Some(_) => None,
+            None => self.eval_value("window.location.href")
+                .await
+                .ok()
+                .and_then(|v| v.as_str().map(|s| s.to_owned())),
+        };
+        let active = pages.iter().find(|t| {
+            let id = t.get("targetId").and_then(|v| v.as_str());
+            match &attached {
+                Some(attached) => id == Some(attached.as_str()),
+                None => current_url.is_some() && t.get("url").and_then(|v| v.as_str()) == current_url.as_deref(),
+            }
+        });
+
+        let active = match active {
+            Some(t) => {
+                let id = t.get("targetId").and_then(|v| v.as_str()).unwrap_or("?");
+                let title = t.get("title").and_then(|v| v.as_str()).unwrap_or("(untitled)");
+                format!("attached to [{id}] {title}")
+            }
+            None => "attached tab unknown".to_owned(),
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "{} tab(s) — {active}",
+            pages.len()
+        ))]))
     }
 
     #[tool(description = "List all open browser tabs with their titles, URLs, and target IDs.")]
     async fn list_tabs(&self) -> Result<CallToolResult, McpError> {
+        if self.browser_config.ws_url.is_some() {
+            return Err(McpError::internal_error(
+                "list_tabs needs /json discovery, which is unavailable when ws_url is configured".to_owned(),
+                None,
+            ));
+        }
         let url = format!("http://localhost:{}/json", self.port);
         let client = reqwest::Client::new();
 
@@ -2552,11 +6833,15 @@ impl CausewayServer {
             .await
             .map_err(|e| McpError::internal_error(format!("Failed to parse tabs: {e}"), None))?;
 
-        // Get current page URL to mark the active CDP tab
-        let current_url = self.execute_reconnect(commands::evaluate("window.location.href"))
-            .await
-            .ok()
-            .and_then(|r| r.get("result")?.get("value")?.as_str().map(|s| s.to_owned()));
+        // Mark the attached tab by target ID; fall back to URL when the connection doesn't know it
+        let attached = self.live.target_id().await;
+        let current_url = match attached {
+            Some(_) => None,
+            None => self.eval_value("window.location.href")
+                .await
+                .ok()
+                .and_then(|v| v.as_str().map(|s| s.to_owned())),
+        };
 
         let mut output = String::new();
         for target in &targets {
@@ -2564,7 +6849,10 @@ impl CausewayServer {
                 let id = target.get("id").and_then(|v| v.as_str()).unwrap_or("?");
                 let title = target.get("title").and_then(|v| v.as_str()).unwrap_or("(untitled)");
                 let tab_url = target.get("url").and_then(|v| v.as_str()).unwrap_or("?");
-                let active = current_url.as_deref() == Some(tab_url);
+                let active = match &attached {
+                    Some(attached) => attached == id,
+                    None => current_url.as_deref() == Some(tab_url),
+                };
                 let marker = if active { " *" } else { "" };
                 output.push_str(&format!("[{id}]{marker} {title}\n  {tab_url}\n\n"));
             }
@@ -2598,7 +6886,19 @@ impl CausewayServer {
     /// Execute a CDP command (built by commands.rs) with reconnect on failure.
     async fn execute_reconnect(&self, command: (&str, serde_json::Value)) -> Result<serde_json::Value, cdp::CdpError> {
         let (method, params) = command;
-        self.exec_with_reconnect(method, params).await
+        let result = self.exec_with_reconnect(method, params).await?;
+        // A throw or rejection is an error for every caller, not a missing value
+        if method == "Runtime.evaluate" {
+            cdp::check_exception(result)
+        } else {
+            Ok(result)
+        }
+    }
+
+    /// Evaluate with reconnect and return the unwrapped value (Null for undefined).
+    /// JS exceptions come back as CdpError::JsException — see cdp::eval_value.
+    async fn eval_value(&self, expression: &str) -> Result<serde_json::Value, cdp::CdpError> {
+        self.execute_reconnect(commands::evaluate(expression)).await.map(cdp::take_value)
     }
 
     /// Execute a CDP command sequence with reconnect on failure.
@@ -2618,11 +6918,149 @@ impl CausewayServer {
         }
     }
 
+    /// Execute a command sequence, pausing a random 30-120ms between commands when human_like is set.
+    /// Falls through to execute_seq_reconnect otherwise, so the default path stays fast and deterministic.
+    async fn execute_seq_paced(&self, commands: Vec<(&'static str, serde_json::Value)>, human_like: bool) -> Result<serde_json::Value, cdp::CdpError> {
+        if !human_like {
+            return self.execute_seq_reconnect(commands).await;
+        }
+        use rand::Rng;
+        let mut last_result = serde_json::Value::Null;
+        for (i, command) in commands.into_iter().enumerate() {
+            if i > 0 {
+                let pause = rand::rng().random_range(30u64..=120);
+                tokio::time::sleep(std::time::Duration::from_millis(pause)).await;
+            }
+            last_result = self.execute_reconnect(command).await?;
+        }
+        Ok(last_result)
+    }
+
+    /// read_form with values_only: a { name: value } object, typed per control kind.
+    async fn read_form_values(&self, sel: &str) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(() => {{
+                const container = document.querySelector({sel});
+                if (!container) return {{ error: "Container not found" }};
+                const fields = container.querySelectorAll('input, select, textarea, [contenteditable="true"]');
+                const skip = ['submit', 'button', 'reset', 'image'];
+                const values = {{}};
+                const checkboxCount = {{}};
+                for (const el of fields) {{
+                    if (el.type === 'checkbox' && el.name) checkboxCount[el.name] = (checkboxCount[el.name] || 0) + 1;
+                }}
+                for (const el of fields) {{
+                    if (skip.includes(el.type)) continue;
+                    const key = el.name ? '[name="' + el.name.replace(/["\\]/g, '\\$&') + '"]'
+                        : el.id ? '#' + CSS.escape(el.id) : null;
+                    if (!key) continue;
+                    if (el.isContentEditable && el.tagName !== 'INPUT' && el.tagName !== 'TEXTAREA') {{
+                        values[key] = el.textContent;
+                    }} else if (el.type === 'checkbox') {{
+                        if ((checkboxCount[el.name] || 1) > 1) {{
+                            values[key] = values[key] || [];
+                            if (el.checked) values[key].push(el.value);
+                        }} else {{
+                            values[key] = el.checked;
+                        }}
+                    }} else if (el.type === 'radio') {{
+                        if (el.checked) values[key] = el.value;
+                        else if (!(key in values)) values[key] = null;
+                    }} else if (el.tagName === 'SELECT' && el.multiple) {{
+                        values[key] = Array.from(el.selectedOptions).map(o => o.value);
+                    }} else if (el.type === 'file') {{
+                        values[key] = Array.from(el.files || []).map(f => f.name);
+                    }} else {{
+                        values[key] = el.value;
+                    }}
+                }}
+                return {{ values }};
+            }})()"#,
+            sel = serde_json::to_string(sel).unwrap()
+        );
+
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Read form failed: {e}"), None))?;
+
+        if let Some(err) = value.get("error").and_then(|e| e.as_str()) {
+            return Err(McpError::invalid_params(err.to_owned(), None));
+        }
+        let values = value.get("values").cloned().unwrap_or_else(|| serde_json::json!({}));
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&values).unwrap_or_else(|_| values.to_string())
+        )]))
+    }
+
+    /// Wait for document.fonts.ready, capped at 5s so a stuck font load can't hang a screenshot.
+    async fn await_fonts(&self) {
+        let _ = self.execute_reconnect(commands::evaluate(
+            "document.fonts ? Promise.race([document.fonts.ready, new Promise(r => setTimeout(r, 5000))]).then(() => document.fonts.status) : 'unsupported'",
+        )).await;
+    }
+
+    /// Capture a page-coordinate region as WebP, cascading quality [80,50,30,15] until under
+    /// CLIP_MAX_BASE64 and scaling so the output stays within 2000px. Returns the last attempt
+    /// even if still over the cap — callers decide how to report that.
+    async fn capture_clip(&self, x: f64, y: f64, w: f64, h: f64, dpr: f64) -> Result<String, McpError> {
+        const MAX_DIM: f64 = 2000.0;
+        let qualities: &[u8] = &[80, 50, 30, 15];
+        let mut final_data = String::new();
+
+        let max_output_dim = w.max(h) * dpr;
+        let scale = if max_output_dim > MAX_DIM { MAX_DIM / max_output_dim } else { 1.0 };
+
+        for &q in qualities {
+            let screenshot_result = self.execute_reconnect(
+                commands::capture_screenshot("webp", Some(q), Some((x, y, w, h, scale)), true),
+            )
+            .await
+            .map_err(|e| McpError::internal_error(format!("Screenshot failed: {e}"), None))?;
+
+            let data = screenshot_result
+                .get("data")
+                .and_then(|d| d.as_str())
+                .ok_or_else(|| McpError::internal_error("No screenshot data returned".to_owned(), None))?;
+
+            final_data = data.to_owned();
+            if final_data.len() <= CLIP_MAX_BASE64 { break; }
+        }
+        Ok(final_data)
+    }
+
+    /// WebSocket ping interval for new connections; None when disabled (ping_interval_secs = 0).
+    fn keepalive(&self) -> Option<std::time::Duration> {
+        match self.browser_config.ping_interval_secs {
+            0 => None,
+            secs => Some(std::time::Duration::from_secs(secs)),
+        }
+    }
+
+    /// Resolve a selector to the center of its first visible, in-viewport match (scrolling it into view).
+    async fn element_center(&self, selector: &str) -> Result<(f64, f64), McpError> {
+        let js = js_find_visible_element(selector);
+        let coords = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
+
+        if coords.is_null() {
+            return Err(McpError::invalid_params(
+                format!("No visible, in-viewport element found for: {selector}"),
+                None,
+            ));
+        }
+
+        Ok((
+            coords.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0),
+            coords.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        ))
+    }
+
     /// Snapshot URL + title before a click action, for wait_for_navigation to compare against.
     async fn snapshot_pre_nav(&self) {
         let js = r#"JSON.stringify({ url: location.href, title: document.title })"#;
-        if let Ok(result) = self.execute_reconnect(commands::evaluate(js)).await {
-            if let Some(raw) = result.get("result").and_then(|r| r.get("value")).and_then(|v| v.as_str()) {
+        if let Ok(result) = self.eval_value(js).await {
+            if let Some(raw) = result.as_str() {
                 if let Ok(val) = serde_json::from_str::<serde_json::Value>(raw) {
                     let url = val.get("url").and_then(|v| v.as_str()).unwrap_or("").to_owned();
                     let title = val.get("title").and_then(|v| v.as_str()).unwrap_or("").to_owned();
@@ -2637,13 +7075,19 @@ impl CausewayServer {
     /// Guarded: only one reconnect runs at a time. Concurrent callers wait and
     /// share the result (the second caller finds a fresh connection already swapped in).
     async fn try_reconnect(&self) -> Result<(), String> {
+        self.run_reconnect(false).await
+    }
+
+    /// Reconnect body behind the guard. `force` skips the health check, for manual recovery
+    /// when the socket is alive but the session is wedged.
+    async fn run_reconnect(&self, force: bool) -> Result<(), String> {
         let _guard = self.reconnect_guard.lock().await;
 
         // Check if another caller already reconnected while we waited for the guard
-        if self.live.get().await.is_some() {
+        if !force && self.live.get().await.is_some() {
             // Quick health check — if the connection is alive, skip reconnect
             if let Some(conn) = self.live.get().await {
-                if cdp::send(&*conn, "Runtime.evaluate", serde_json::json!({"expression": "1"})).await.is_ok() {
+                if cdp::eval_value(&conn, "1").await.is_ok() {
                     tracing::debug!("Reconnect skipped — connection already restored by another caller");
                     return Ok(());
                 }
@@ -2651,6 +7095,19 @@ impl CausewayServer {
         }
 
         tracing::info!("Attempting CDP reconnect...");
+
+        // Explicit ws_url: no /json discovery and no relaunch — just redial the known socket
+        if let Some(ws_url) = self.browser_config.ws_url.as_deref() {
+            let new_conn = cdp::connect_to_target(ws_url, self.keepalive())
+                .await
+                .map_err(|e| format!("Reconnect to configured ws_url failed: {e}"))?;
+            self.live.swap(new_conn).await;
+            self.resubscribe_events().await;
+            self.restore_session_state().await;
+            tracing::info!("CDP reconnected to {ws_url}");
+            return Ok(());
+        }
+
         let sticky = self.sticky_target.lock().await.clone();
 
         // Try finding an existing target first
@@ -2672,32 +7129,577 @@ impl CausewayServer {
             }
         };
 
-        let new_conn = cdp::connect_to_target(&ws_url)
+        // A session of a shared browser gets a tab of its own rather than the first one found
+        let ws_url = match &self.session_resources {
+            Some(resources) if self.sticky_target.lock().await.is_none() => {
+                match self.open_session_tab(resources).await {
+                    Ok(url) => url,
+                    Err(e) => {
+                        tracing::warn!("Could not open a tab for this session, sharing {ws_url}: {e}");
+                        ws_url
+                    }
+                }
+            }
+            _ => ws_url,
+        };
+
+        let new_conn = cdp::connect_to_target(&ws_url, self.keepalive())
             .await
             .map_err(|e| format!("Reconnect failed: {e}"))?;
         self.live.swap(new_conn).await;
         self.resubscribe_events().await;
+        self.restore_session_state().await;
         tracing::info!("CDP reconnected to {ws_url}");
         Ok(())
     }
 
-    /// Reconnect CDP to a specific target by ID.
-    async fn reconnect_to_target(&self, target_id: &str) -> Result<(), McpError> {
-        let ws_url = crate::browser::find_target_ws_url(self.port, Some(target_id))
+    /// Open a blank tab over the browser endpoint, record it for closing when the session ends,
+    /// and pin it as the sticky target. Returns its WebSocket URL.
+    async fn open_session_tab(&self, resources: &SessionResources) -> Result<String, String> {
+        let browser_ws = crate::browser::browser_ws_url(self.port).await.map_err(|e| e.to_string())?;
+        let conn = cdp::connect(&browser_ws).await.map_err(|e| e.to_string())?;
+        let result = cdp::send(&conn, "Target.createTarget", serde_json::json!({ "url": "about:blank" }))
+            .await
+            .map_err(|e| e.to_string())?;
+        let target_id = result
+            .get("targetId")
+            .and_then(|v| v.as_str())
+            .ok_or("No targetId returned")?
+            .to_owned();
+        resources.tabs.lock().unwrap_or_else(|e| e.into_inner()).push(target_id.clone());
+
+        // /json can trail createTarget by a moment
+        let mut last_error = String::new();
+        for _ in 0..10 {
+            match crate::browser::find_target_ws_url(self.port, Some(&target_id)).await {
+                Ok(url) => {
+                    tracing::info!("Opened session tab {target_id}");
+                    *self.sticky_target.lock().await = Some(target_id);
+                    return Ok(url);
+                }
+                Err(e) => last_error = e.to_string(),
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        Err(last_error)
+    }
+
+    /// Reconnect CDP to a specific target by ID.
+    async fn reconnect_to_target(&self, target_id: &str) -> Result<(), McpError> {
+        if self.browser_config.ws_url.is_some() {
+            return Err(McpError::internal_error(
+                "Switching targets needs /json discovery, which is unavailable when ws_url is configured".to_owned(),
+                None,
+            ));
+        }
+        let ws_url = crate::browser::find_target_ws_url(self.port, Some(target_id))
+            .await
+            .map_err(|e| McpError::internal_error(
+                format!("Could not find WebSocket URL for target {target_id}: {e}"),
+                None,
+            ))?;
+        let new_conn = cdp::connect_to_target(&ws_url, self.keepalive())
+            .await
+            .map_err(|e| McpError::internal_error(
+                format!("Failed to connect to target {target_id}: {e}"),
+                None,
+            ))?;
+        self.live.swap(new_conn).await;
+        self.resubscribe_events().await;
+        self.restore_session_state().await;
+        Ok(())
+    }
+
+    /// Capture the viewport for screenshot: to save_path at full resolution, or inline as WebP,
+    /// scaled to fit 2000px (when `fit`) and stepped down in quality until under the response size limit.
+    async fn capture_viewport(
+        &self,
+        wait_for_fonts: Option<bool>,
+        save_path: Option<String>,
+        fit: bool,
+    ) -> Result<CallToolResult, McpError> {
+        let save_format = match &save_path {
+            Some(path) => {
+                let format = screenshot_format_for_path(path)?;
+                prepare_save_path(path)?;
+                Some(format)
+            }
+            None => None,
+        };
+        if wait_for_fonts.unwrap_or(false) {
+            self.await_fonts().await;
+        }
+
+        // Saving to disk skips the size cascade — no response payload to keep small
+        if let (Some(save_path), Some(format)) = (save_path, save_format) {
+            let result = self.execute_reconnect(commands::capture_screenshot(format, Some(90), None, false))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Screenshot failed: {e}"), None))?;
+            let data = result
+                .get("data")
+                .and_then(|d| d.as_str())
+                .ok_or_else(|| McpError::internal_error("No screenshot data returned".to_owned(), None))?;
+            let size = write_base64_capture(&save_path, data)?;
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Saved screenshot ({size} bytes, {format}) to: {save_path}"
+            ))]));
+        }
+
+        // Cascade down quality until under 5MB base64 (~3.75MB decoded)
+        const MAX_BASE64: usize = 5_000_000;
+        const MAX_DIM: f64 = 2000.0;
+        let qualities: &[u8] = &[80, 50, 30, 15];
+
+        // Get viewport dimensions + devicePixelRatio (actual image pixels = logical × DPR)
+        let dims_result = self.eval_value(
+            "JSON.stringify({ w: window.innerWidth, h: window.innerHeight, dpr: window.devicePixelRatio })"
+        ).await.ok();
+        let (vw, vh, dpr) = dims_result
+            .as_ref()
+            .and_then(|v| v.as_str())
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+            .map(|v| (
+                v.get("w").and_then(|n| n.as_f64()).unwrap_or(1280.0),
+                v.get("h").and_then(|n| n.as_f64()).unwrap_or(800.0),
+                v.get("dpr").and_then(|n| n.as_f64()).unwrap_or(1.0),
+            ))
+            .unwrap_or((1280.0, 800.0, 1.0));
+
+        // Actual output pixels = logical × DPR. Scale down if that exceeds 2000.
+        let max_output_dim = vw.max(vh) * dpr;
+        let scale = if fit && max_output_dim > MAX_DIM { MAX_DIM / max_output_dim } else { 1.0 };
+
+        let mut final_data = String::new();
+        let mut used_quality = 80u8;
+        for &q in qualities {
+            let clip = if scale < 1.0 { Some((0.0, 0.0, vw, vh, scale)) } else { None };
+
+            let result = self.execute_reconnect(commands::capture_screenshot("webp", Some(q), clip, false))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Screenshot failed: {e}"), None))?;
+
+            let data = result
+                .get("data")
+                .and_then(|d| d.as_str())
+                .ok_or_else(|| McpError::internal_error("No screenshot data returned".to_owned(), None))?;
+
+            final_data = data.to_owned();
+            used_quality = q;
+            if final_data.len() <= MAX_BASE64 {
+                break;
+            }
+            tracing::info!("Screenshot {:.1}MB at quality {q}, retrying lower", final_data.len() as f64 / 1_000_000.0);
+        }
+
+        let size_kb = final_data.len() / 1024;
+
+        if final_data.len() > MAX_BASE64 {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Screenshot too large even at lowest quality ({size_kb}KB base64). Use accessibility_snapshot or read_page instead."
+            ))]));
+        }
+
+        let scale_note = if scale < 1.0 {
+            format!(" @{:.0}% ({}×{})", scale * 100.0, (vw * dpr * scale) as u32, (vh * dpr * scale) as u32)
+        } else {
+            String::new()
+        };
+
+        Ok(CallToolResult::success(vec![
+            Content::image(final_data, "image/webp"),
+            Content::text(format!("{size_kb}KB (q{used_quality}){scale_note}")),
+        ]))
+    }
+
+    /// Swap the frozen-clock init script for one pinned at `now_ms`. Returns the new identifier.
+    async fn register_clock_script(
+        &self,
+        previous: Option<&FrozenClock>,
+        now_ms: f64,
+    ) -> Result<Option<String>, cdp::CdpError> {
+        if let Some(identifier) = previous.and_then(|c| c.cdp_identifier.as_deref()) {
+            self.execute_reconnect(commands::remove_init_script(identifier)).await?;
+        }
+        let result = self.execute_reconnect(commands::add_init_script(&js_fake_clock(now_ms))).await?;
+        Ok(result.get("identifier").and_then(|v| v.as_str()).map(|s| s.to_owned()))
+    }
+
+    /// Attach to another tab for a one-off read, as a flat session on our own connection — the
+    /// sticky target and event subscriptions are left alone. The tab's URL is checked against
+    /// the domain policy first. Call SideTab::detach when done.
+    async fn attach_side_tab(&self, target_id: &str) -> Result<SideTab, McpError> {
+        let not_found = || McpError::invalid_params(
+            format!("No tab with target ID {target_id} — use list_tabs for current IDs"),
+            None,
+        );
+        let result = self.exec_with_reconnect("Target.getTargetInfo", serde_json::json!({ "targetId": target_id }))
+            .await
+            .map_err(|e| match e {
+                cdp::CdpError::ResponseError { .. } => not_found(),
+                e => McpError::internal_error(format!("Failed to look up tab {target_id}: {e}"), None),
+            })?;
+        let info = result.get("targetInfo").ok_or_else(not_found)?;
+        if info.get("type").and_then(|v| v.as_str()) != Some("page") {
+            return Err(not_found());
+        }
+        let url = info.get("url").and_then(|v| v.as_str()).unwrap_or("");
+        check_domain_policy(&self.browser_config, url).map_err(|msg| McpError::invalid_params(msg, None))?;
+
+        // The session only exists on the socket that attached it, so hold on to this connection
+        let conn = self.live.get().await.ok_or(McpError::internal_error("Not connected", None))?;
+        let result = cdp::send(&conn, "Target.attachToTarget", serde_json::json!({ "targetId": target_id, "flatten": true }))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to attach to tab {target_id}: {e}"), None))?;
+        let session_id = result
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| McpError::internal_error(format!("Failed to attach to tab {target_id}: no sessionId returned"), None))?
+            .to_owned();
+        Ok(SideTab { conn, session_id, target_id: target_id.to_owned() })
+    }
+
+    /// Open a temporary connection to the browser endpoint (/json/version). Browser-wide Target
+    /// calls — creating, listing, and disposing browser contexts — are refused on page sessions.
+    async fn connect_browser(&self) -> Result<cdp::CdpConnection, McpError> {
+        if self.browser_config.ws_url.is_some() {
+            return Err(McpError::internal_error(
+                "Browser contexts need the browser endpoint from /json/version, which is unavailable when ws_url is configured".to_owned(),
+                None,
+            ));
+        }
+        if self.live.get().await.is_none() {
+            self.try_reconnect().await.map_err(|msg| McpError::internal_error(msg, None))?;
+        }
+        let ws_url = crate::browser::browser_ws_url(self.port)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Could not find the browser endpoint: {e}"), None))?;
+        cdp::connect(&ws_url)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to connect to the browser endpoint: {e}"), None))
+    }
+
+    /// The Fetch.enable command for current state, or None when nothing needs interception.
+    /// Proxy credentials need every request paused (auth challenges only surface on paused
+    /// requests), so they widen the pattern to "*"; rules still pick their own matches.
+    /// A domain policy pauses every document request so in-page navigations are checked too.
+    async fn fetch_enable_command(&self) -> Option<(&'static str, serde_json::Value)> {
+        let proxy_auth = self.browser_config.proxy.as_deref()
+            .map(|p| crate::config::parse_proxy(p).credentials.is_some())
+            .unwrap_or(false);
+        if proxy_auth {
+            return Some(commands::fetch_enable(&["*".to_owned()], false, true));
+        }
+        let guard_documents = !self.browser_config.allowed_domains.is_empty()
+            || !self.browser_config.blocked_domains.is_empty();
+        let patterns: Vec<String> = self.intercept_rules.lock().await.iter().map(|r| r.pattern.clone()).collect();
+        if patterns.is_empty() && !guard_documents {
+            None
+        } else {
+            Some(commands::fetch_enable(&patterns, guard_documents, false))
+        }
+    }
+
+    /// Dispose incognito contexts we created that no longer have any page. Returns the disposed IDs.
+    async fn dispose_empty_contexts(&self) -> Vec<String> {
+        // Snapshot the IDs and release the lock — the wait and the CDP calls below are slow
+        let candidates = self.browser_contexts.lock().await.clone();
+        if candidates.is_empty() {
+            return Vec::new();
+        }
+        // closeTarget returns before the target is torn down
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        let Some(conn) = self.live.get().await else { return Vec::new() };
+        let Ok(result) = cdp::send(&conn, "Target.getTargets", serde_json::json!({})).await else {
+            return Vec::new();
+        };
+        let targets = result.get("targetInfos").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        let in_use = |context: &str| {
+            targets.iter().any(|t| {
+                t.get("type").and_then(|v| v.as_str()) == Some("page")
+                    && t.get("browserContextId").and_then(|v| v.as_str()) == Some(context)
+            })
+        };
+
+        let empty: Vec<String> = candidates.into_iter().filter(|context| !in_use(context)).collect();
+        // Only claim contexts still tracked — a concurrent call may have disposed some already
+        let disposed: Vec<String> = {
+            let mut contexts = self.browser_contexts.lock().await;
+            let disposed: Vec<String> = empty.into_iter().filter(|context| contexts.contains(context)).collect();
+            contexts.retain(|context| !disposed.contains(context));
+            disposed
+        };
+
+        dispose_browser_contexts(self.port, &disposed).await;
+        disposed
+    }
+
+    /// Dispose every incognito context this server created. Called when the transport shuts
+    /// down, since the browser keeps contexts after the connection that created them is gone.
+    pub async fn shutdown(&self) {
+        let contexts = std::mem::take(&mut *self.browser_contexts.lock().await);
+        dispose_browser_contexts(self.port, &contexts).await;
+    }
+
+    /// Visible page text as trimmed, non-empty lines, plus the key its snapshot is stored under.
+    async fn page_text_lines(&self) -> Result<(String, Vec<String>), McpError> {
+        let result = self.eval_value("document.body ? document.body.innerText : ''")
+            .await
+            .map_err(|e| McpError::internal_error(format!("Read page failed: {e}"), None))?;
+        let text = result
+            .as_str()
+            .unwrap_or("");
+        let lines = text
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .map(|l| l.to_owned())
+            .collect();
+        let key = match self.live.target_id().await {
+            Some(id) => id,
+            None => self.sticky_target.lock().await.clone().unwrap_or_default(),
+        };
+        Ok((key, lines))
+    }
+
+    /// shadowRootType ("open", "closed", "user-agent") of the first author shadow root on an element, via CDP.
+    async fn shadow_root_type(&self, selector: &str) -> Option<String> {
+        let js = format!("document.querySelector({})", serde_json::to_string(selector).unwrap());
+        let object_id = self.execute_reconnect(commands::evaluate_ref(&js))
+            .await
+            .ok()?
+            .get("result")?
+            .get("objectId")?
+            .as_str()?
+            .to_owned();
+        let described = self.execute_reconnect(commands::describe_object_node(&object_id)).await.ok()?;
+        described
+            .get("node")?
+            .get("shadowRoots")?
+            .as_array()?
+            .iter()
+            .filter_map(|r| r.get("shadowRootType").and_then(|v| v.as_str()))
+            .find(|t| *t != "user-agent")
+            .map(|t| t.to_owned())
+    }
+
+    /// CDP frame id of the index-th <iframe> in the top document, via DOM.describeNode on the element.
+    async fn iframe_frame_id(&self, index: usize) -> Option<String> {
+        let js = format!("document.querySelectorAll('iframe')[{index}]");
+        let object_id = self.execute_reconnect(commands::evaluate_ref(&js))
+            .await
+            .ok()?
+            .get("result")?
+            .get("objectId")?
+            .as_str()?
+            .to_owned();
+        let described = self.execute_reconnect(commands::describe_object_node(&object_id)).await.ok()?;
+        described
+            .get("node")?
+            .get("frameId")?
+            .as_str()
+            .map(|id| id.to_owned())
+    }
+
+    /// Load a URL through the browser's network stack as the page's main frame
+    /// (Network.loadNetworkResource): same proxy, cookies, and network as the page, without CORS.
+    /// Returns the HTTP status, content type, and base64 body.
+    async fn load_network_resource(&self, url: &str) -> Result<(u16, String, String), String> {
+        use base64::Engine;
+        const MAX_BYTES: usize = 2_000_000;
+
+        let frame_id = self.exec_with_reconnect("Page.getFrameTree", serde_json::json!({}))
+            .await
+            .map_err(|e| e.to_string())?
+            .pointer("/frameTree/frame/id")
+            .and_then(|v| v.as_str())
+            .ok_or("no main frame")?
+            .to_owned();
+        let result = self.exec_with_reconnect("Network.loadNetworkResource", serde_json::json!({
+            "frameId": frame_id,
+            "url": url,
+            "options": { "disableCache": false, "includeCredentials": true },
+        }))
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let resource = result.get("resource").cloned().unwrap_or_default();
+        let status = resource.get("httpStatusCode").and_then(|v| v.as_f64()).unwrap_or(0.0) as u16;
+        if !resource.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
+            if status > 0 {
+                return Ok((status, String::new(), String::new()));
+            }
+            return Err(resource.get("netErrorName").and_then(|v| v.as_str()).unwrap_or("load failed").to_owned());
+        }
+        let mime = resource.get("headers")
+            .and_then(|h| h.as_object())
+            .and_then(|h| h.iter().find(|(name, _)| name.eq_ignore_ascii_case("content-type")))
+            .and_then(|(_, v)| v.as_str())
+            .map(|v| v.split(';').next().unwrap_or(v).trim().to_owned())
+            .unwrap_or_default();
+        let Some(stream) = resource.get("stream").and_then(|v| v.as_str()) else {
+            return Ok((status, mime, String::new()));
+        };
+
+        let engine = base64::engine::general_purpose::STANDARD;
+        let mut body = Vec::new();
+        let read = loop {
+            let chunk = match self.exec_with_reconnect("IO.read", serde_json::json!({ "handle": stream })).await {
+                Ok(chunk) => chunk,
+                Err(e) => break Err(e.to_string()),
+            };
+            let data = chunk.get("data").and_then(|v| v.as_str()).unwrap_or("");
+            if chunk.get("base64Encoded").and_then(|v| v.as_bool()).unwrap_or(false) {
+                match engine.decode(data) {
+                    Ok(bytes) => body.extend(bytes),
+                    Err(e) => break Err(e.to_string()),
+                }
+            } else {
+                body.extend_from_slice(data.as_bytes());
+            }
+            if body.len() > MAX_BYTES {
+                break Err(format!("larger than {}MB", MAX_BYTES / 1_000_000));
+            }
+            if chunk.get("eof").and_then(|v| v.as_bool()).unwrap_or(true) {
+                break Ok(());
+            }
+        };
+        let _ = self.exec_with_reconnect("IO.close", serde_json::json!({ "handle": stream })).await;
+        read?;
+        Ok((status, mime, engine.encode(&body)))
+    }
+
+    /// Shared body of go_offline / go_online.
+    async fn set_offline(&self, offline: bool) -> Result<CallToolResult, McpError> {
+        self.execute_reconnect(commands::emulate_offline(offline))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Network emulation failed: {e}"), None))?;
+        self.offline.store(offline, std::sync::atomic::Ordering::Relaxed);
+
+        let on_line = self.eval_value("navigator.onLine")
+            .await
+            .ok()
+            .and_then(|v| v.as_bool());
+        let state = if offline { "offline" } else { "online" };
+        let output = match on_line {
+            Some(v) => format!("Browser is now {state} (navigator.onLine = {v})"),
+            None => format!("Browser is now {state}"),
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    /// Push the current intercept patterns to Fetch.enable, or disable Fetch when nothing needs it.
+    async fn apply_fetch_patterns(&self) -> Result<(), cdp::CdpError> {
+        let command = self.fetch_enable_command().await.unwrap_or_else(commands::fetch_disable);
+        self.execute_reconnect(command).await.map(|_| ())
+    }
+
+    /// Re-apply per-session state that CDP forgets when the connection changes
+    /// (reconnect, tab switch). Best-effort: failures are logged, not surfaced.
+    async fn restore_session_state(&self) {
+        let Some(conn) = self.live.get().await else { return };
+
+        let (method, params) = commands::set_download_behavior(self.browser_config.download_dir.as_deref());
+        if let Err(e) = cdp::send(&conn, method, params).await {
+            tracing::warn!("Failed to enable download events: {e}");
+        }
+
+        // Grants die with the browser process, so re-grant on every connection
+        if !self.browser_config.grant_permissions.is_empty() {
+            let (method, params) = commands::grant_permissions(
+                &self.browser_config.grant_permissions,
+                self.browser_config.grant_permissions_origin.as_deref(),
+            );
+            if let Err(e) = cdp::send(&conn, method, params).await {
+                tracing::warn!("Failed to grant configured permissions: {e}");
+            }
+        }
+
+        if let Some((method, params)) = self.fetch_enable_command().await {
+            if let Err(e) = cdp::send(&conn, method, params).await {
+                tracing::warn!("Failed to re-enable request interception: {e}");
+            }
+        }
+
+        if self.offline.load(std::sync::atomic::Ordering::Relaxed) {
+            let (method, params) = commands::emulate_offline(true);
+            if let Err(e) = cdp::send(&conn, method, params).await {
+                tracing::warn!("Failed to restore offline mode: {e}");
+            }
+        }
+
+        if self.javascript_disabled.load(std::sync::atomic::Ordering::Relaxed) {
+            let (method, params) = commands::set_script_execution_disabled(true);
+            if let Err(e) = cdp::send(&conn, method, params).await {
+                tracing::warn!("Failed to restore disabled JavaScript: {e}");
+            }
+        }
+
+        if self.lcp_observer.load(std::sync::atomic::Ordering::Relaxed) {
+            let (method, params) = commands::add_init_script(JS_LCP_OBSERVER);
+            if let Err(e) = cdp::send(&conn, method, params).await {
+                tracing::warn!("Failed to re-register LCP observer: {e}");
+            }
+        }
+
+        if let Some(clock) = self.frozen_clock.lock().await.as_mut() {
+            let js = js_fake_clock(clock.now_ms);
+            let (method, params) = commands::add_init_script(&js);
+            match cdp::send(&conn, method, params).await {
+                Ok(result) => {
+                    clock.cdp_identifier = result.get("identifier").and_then(|v| v.as_str()).map(|s| s.to_owned());
+                }
+                Err(e) => tracing::warn!("Failed to re-register frozen clock: {e}"),
+            }
+            let _ = cdp::eval_value(&conn, &js).await;
+        }
+
+        // Persistent stylesheets: re-register for new documents and make sure the current one has them
+        let mut sheets = self.injected_css.lock().await;
+        for sheet in sheets.iter_mut().filter(|s| s.cdp_identifier.is_some()) {
+            let js = js_inject_css(&sheet.id, &sheet.css);
+            let (method, params) = commands::add_init_script(&js);
+            match cdp::send(&conn, method, params).await {
+                Ok(result) => {
+                    if let Some(identifier) = result.get("identifier").and_then(|v| v.as_str()) {
+                        sheet.cdp_identifier = Some(identifier.to_owned());
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to re-register stylesheet {}: {e}", sheet.id),
+            }
+            let _ = cdp::eval_value(&conn, &js).await;
+        }
+        drop(sheets);
+
+        let mut scripts = self.init_scripts.lock().await;
+        for script in scripts.iter_mut() {
+            let (method, params) = commands::add_init_script(&script.source);
+            match cdp::send(&conn, method, params).await {
+                Ok(result) => {
+                    if let Some(identifier) = result.get("identifier").and_then(|v| v.as_str()) {
+                        script.cdp_identifier = identifier.to_owned();
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to re-register init script {}: {e}", script.id),
+            }
+        }
+    }
+
+    #[tool(description = "Force a fresh CDP connection without restarting the server. Re-attaches to the current tab if it still exists (otherwise the first available page, relaunching the browser if needed), re-enables domains, and restores init scripts and interception. Use when tools hang or return stale results.")]
+    async fn reconnect(&self) -> Result<CallToolResult, McpError> {
+        self.run_reconnect(true)
             .await
-            .map_err(|e| McpError::internal_error(
-                format!("Could not find WebSocket URL for target {target_id}: {e}"),
-                None,
-            ))?;
-        let new_conn = cdp::connect_to_target(&ws_url)
+            .map_err(|msg| McpError::internal_error(msg, None))?;
+
+        let url = self.eval_value("window.location.href")
             .await
-            .map_err(|e| McpError::internal_error(
-                format!("Failed to connect to target {target_id}: {e}"),
-                None,
-            ))?;
-        self.live.swap(new_conn).await;
-        self.resubscribe_events().await;
-        Ok(())
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_owned()))
+            .unwrap_or_else(|| "(unknown)".to_owned());
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Reconnected to: {url}"
+        ))]))
     }
 
     #[tool(description = "Switch to a browser tab by its target ID (from list_tabs).")]
@@ -2726,14 +7728,74 @@ impl CausewayServer {
         ))]))
     }
 
+    #[tool(description = "Screenshot another tab by target ID (from list_tabs) without switching to it. Attaches to that tab over the current connection, captures, and detaches — the current tab and its session are untouched. Tabs on blocked domains are refused. Background tabs that the browser has stopped painting may time out.")]
+    async fn screenshot_tab(
+        &self,
+        Parameters(ScreenshotTabParams { target_id, save_path }): Parameters<ScreenshotTabParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let save_format = match &save_path {
+            Some(path) => {
+                let format = screenshot_format_for_path(path)?;
+                prepare_save_path(path)?;
+                Some(format)
+            }
+            None => None,
+        };
+        let tab = self.attach_side_tab(&target_id).await?;
+        let result = screenshot_side_tab(&tab, save_path.zip(save_format)).await;
+        tab.detach().await;
+        result
+    }
+
+    #[tool(description = "Read another tab's visible text (document.body.innerText) by target ID (from list_tabs) without switching to it. Attaches to it over the current connection, reads, and detaches — the current tab and its session are untouched. Tabs on blocked domains are refused. Good for polling a background status page.")]
+    async fn read_tab_text(
+        &self,
+        Parameters(ReadTabTextParams { target_id, max_chars }): Parameters<ReadTabTextParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let max_chars = max_chars.unwrap_or(10000);
+        let tab = self.attach_side_tab(&target_id).await?;
+
+        // Truncate in the page so the slice lands on a character boundary
+        let js = format!(
+            r#"(() => {{
+                const text = document.body ? document.body.innerText : '';
+                return {{ title: document.title, url: location.href, text: text.slice(0, {max_chars}), total: text.length }};
+            }})()"#
+        );
+        let value = tokio::time::timeout(SIDE_TAB_TIMEOUT, tab.eval_value(&js)).await;
+        tab.detach().await;
+        let value = value
+            .map_err(|_| McpError::internal_error(format!("Reading tab {target_id} timed out"), None))?
+            .map_err(|e| McpError::internal_error(format!("Reading tab {target_id} failed: {e}"), None))?;
+
+        let title = value.get("title").and_then(|v| v.as_str()).unwrap_or("");
+        let url = value.get("url").and_then(|v| v.as_str()).unwrap_or("");
+        let text = value.get("text").and_then(|v| v.as_str()).unwrap_or("");
+        let total = value.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
+
+        let mut output = format!("Tab {target_id}: {title}\nURL: {url}\n\n{}", if text.is_empty() { "(empty page)" } else { text });
+        if total as usize > max_chars {
+            output.push_str(&format!("...\n\n[Truncated — {total} total characters]"));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
     #[tool(description = "Open a new browser tab, optionally with a URL.")]
     async fn new_tab(
         &self,
         Parameters(NewTabParams { url }): Parameters<NewTabParams>,
     ) -> Result<CallToolResult, McpError> {
+        if self.browser_config.ws_url.is_some() {
+            return Err(McpError::internal_error(
+                "new_tab needs /json discovery, which is unavailable when ws_url is configured".to_owned(),
+                None,
+            ));
+        }
+        let target_url = url.as_deref().unwrap_or("about:blank");
+        check_domain_policy(&self.browser_config, target_url).map_err(|msg| McpError::invalid_params(msg, None))?;
         // Already opening a new tab — no need for first-navigate to open another
         self.first_navigate.store(false, std::sync::atomic::Ordering::Relaxed);
-        let target_url = url.as_deref().unwrap_or("about:blank");
 
         let conn = self.live.get().await.ok_or(McpError::internal_error("Not connected", None))?;
         let result = cdp::send(
@@ -2776,13 +7838,384 @@ impl CausewayServer {
         .await
         .map_err(|e| McpError::internal_error(format!("Close tab failed: {e}"), None))?;
 
+        let disposed = self.dispose_empty_contexts().await;
+        let suffix = if disposed.is_empty() {
+            String::new()
+        } else {
+            format!(" (disposed empty incognito context {})", disposed.join(", "))
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Closed tab {target_id}{suffix}"
+        ))]))
+    }
+
+    #[tool(description = "Open a tab in a fresh incognito browser context (Target.createBrowserContext) and switch to it. Each context has its own cookies, storage, and cache, so isolated sessions can run side by side in one browser. The context is disposed when its last tab is closed with close_tab.")]
+    async fn new_incognito_tab(
+        &self,
+        Parameters(NewIncognitoTabParams { url }): Parameters<NewIncognitoTabParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if self.browser_config.ws_url.is_some() {
+            return Err(McpError::internal_error(
+                "new_incognito_tab needs /json discovery, which is unavailable when ws_url is configured".to_owned(),
+                None,
+            ));
+        }
+        let target_url = url.as_deref().unwrap_or("about:blank");
+        check_domain_policy(&self.browser_config, target_url).map_err(|msg| McpError::invalid_params(msg, None))?;
+        self.first_navigate.store(false, std::sync::atomic::Ordering::Relaxed);
+
+        let browser = self.connect_browser().await?;
+        let result = cdp::send(&browser, "Target.createBrowserContext", serde_json::json!({ "disposeOnDetach": false }))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Create browser context failed: {e}"), None))?;
+        let context_id = result
+            .get("browserContextId")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| McpError::internal_error("No browserContextId returned".to_owned(), None))?
+            .to_owned();
+
+        let result = cdp::send(
+            &browser,
+            "Target.createTarget",
+            serde_json::json!({ "url": target_url, "browserContextId": &context_id }),
+        )
+        .await;
+        let target_id = result.as_ref().ok()
+            .and_then(|r| r.get("targetId"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_owned());
+        let Some(target_id) = target_id else {
+            // Don't leave an empty context behind
+            let _ = cdp::send(&browser, "Target.disposeBrowserContext", serde_json::json!({ "browserContextId": &context_id })).await;
+            let reason = result.err().map(|e| e.to_string()).unwrap_or_else(|| "no targetId returned".to_owned());
+            return Err(McpError::internal_error(format!("New incognito tab failed: {reason}"), None));
+        };
+        self.browser_contexts.lock().await.push(context_id.clone());
+
+        // Give the new tab a moment to register its debug endpoint
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+        self.reconnect_to_target(&target_id).await?;
+        *self.sticky_target.lock().await = Some(target_id.clone());
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Opened and switched to incognito tab [{target_id}] in context {context_id}: {target_url}"
+        ))]))
+    }
+
+    #[tool(description = "List browser contexts with their tabs: the default context plus incognito contexts (marked if created by new_incognito_tab). Tab IDs work with switch_tab and close_tab.")]
+    async fn list_contexts(&self) -> Result<CallToolResult, McpError> {
+        let result = self.exec_with_reconnect("Target.getTargets", serde_json::json!({}))
+            .await
+            .map_err(|e| McpError::internal_error(format!("List targets failed: {e}"), None))?;
+        let targets = result.get("targetInfos").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+
+        // With a fixed ws_url there is no browser endpoint — only our own contexts can be labelled
+        let extra_contexts: Vec<String> = if self.browser_config.ws_url.is_some() {
+            Vec::new()
+        } else {
+            let browser = self.connect_browser().await?;
+            cdp::send(&browser, "Target.getBrowserContexts", serde_json::json!({}))
+                .await
+                .map_err(|e| McpError::internal_error(format!("List browser contexts failed: {e}"), None))?
+                .get("browserContextIds")
+                .and_then(|v| v.as_array())
+                .map(|ids| ids.iter().filter_map(|v| v.as_str().map(|s| s.to_owned())).collect())
+                .unwrap_or_default()
+        };
+        let ours = self.browser_contexts.lock().await.clone();
+
+        // Group page targets by context, preserving first-seen order; default context first
+        let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+        for target in &targets {
+            if target.get("type").and_then(|t| t.as_str()) != Some("page") {
+                continue;
+            }
+            let context = target.get("browserContextId").and_then(|v| v.as_str()).unwrap_or("").to_owned();
+            let id = target.get("targetId").and_then(|v| v.as_str()).unwrap_or("?");
+            let title = target.get("title").and_then(|v| v.as_str()).unwrap_or("(untitled)");
+            let tab_url = target.get("url").and_then(|v| v.as_str()).unwrap_or("?");
+            let line = format!("  [{id}] {title}\n    {tab_url}");
+            match groups.iter_mut().find(|(c, _)| *c == context) {
+                Some((_, tabs)) => tabs.push(line),
+                None => groups.push((context, vec![line])),
+            }
+        }
+        for context in extra_contexts.iter().chain(ours.iter()) {
+            if !groups.iter().any(|(c, _)| c == context) {
+                groups.push((context.clone(), Vec::new()));
+            }
+        }
+        // getBrowserContexts lists only non-default contexts — anything else is the default one
+        groups.sort_by_key(|(c, _)| extra_contexts.contains(c) || ours.contains(c));
+
+        let output = groups
+            .iter()
+            .map(|(context, tabs)| {
+                let label = if !extra_contexts.contains(context) && !ours.contains(context) {
+                    "Default context".to_owned()
+                } else if ours.contains(context) {
+                    format!("Incognito context {context} (new_incognito_tab)")
+                } else {
+                    format!("Incognito context {context}")
+                };
+                let body = if tabs.is_empty() { "  (no tabs)".to_owned() } else { tabs.join("\n") };
+                format!("{label} — {} tab(s):\n{body}", tabs.len())
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        Ok(CallToolResult::success(vec![Content::text(if output.is_empty() {
+            "No browser contexts found".to_owned()
+        } else {
+            output
+        })]))
+    }
+
+    #[tool(description = "Register a script that runs before page scripts on every new document (Page.addScriptToEvaluateOnNewDocument). Useful for stubbing analytics or defining test helpers. Persists across navigations, reconnects, and tab switches. Returns an ID for remove_init_script.")]
+    async fn add_init_script(
+        &self,
+        Parameters(AddInitScriptParams { source }): Parameters<AddInitScriptParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self.execute_reconnect(commands::add_init_script(&source))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Add init script failed: {e}"), None))?;
+
+        let cdp_identifier = result
+            .get("identifier")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| McpError::internal_error("No script identifier returned".to_owned(), None))?
+            .to_owned();
+
+        let id = format!(
+            "init-{}",
+            self.next_init_script_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        );
+        self.init_scripts.lock().await.push(InitScript { id: id.clone(), source, cdp_identifier });
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Init script registered: {id} (runs on next navigation)"
+        ))]))
+    }
+
+    #[tool(description = "Remove a script registered with add_init_script. Takes effect on the next navigation; already-loaded pages keep whatever it defined.")]
+    async fn remove_init_script(
+        &self,
+        Parameters(RemoveInitScriptParams { id }): Parameters<RemoveInitScriptParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let identifier = self.init_scripts.lock().await
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.cdp_identifier.clone())
+            .ok_or_else(|| McpError::invalid_params(format!("No init script with ID: {id}"), None))?;
+
+        self.execute_reconnect(commands::remove_init_script(&identifier))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Remove init script failed: {e}"), None))?;
+        // Only forget it once the browser has dropped it, so a failed removal can be retried
+        self.init_scripts.lock().await.retain(|s| s.id != id);
+
         Ok(CallToolResult::success(vec![Content::text(format!(
-            "Closed tab {target_id}"
+            "Init script removed: {id}"
+        ))]))
+    }
+
+    #[tool(description = "Freeze the page clock for deterministic testing of time-dependent UI (clocks, countdowns, token expiry). Overrides Date, Date.now, and performance.now on the current page and every new document (init script), so time stands still until advance_time. setTimeout/setInterval keep running on real time. Persists across navigations and reconnects until unfreeze_time.")]
+    async fn freeze_time(
+        &self,
+        Parameters(FreezeTimeParams { time }): Parameters<FreezeTimeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let now_ms = match time.as_deref().map(str::trim) {
+            None | Some("") => std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as f64)
+                .unwrap_or(0.0),
+            Some(t) => match t.parse::<f64>() {
+                Ok(ms) => ms,
+                Err(_) => {
+                    let js = format!("Date.parse({})", serde_json::to_string(t).unwrap());
+                    self.eval_value(&js)
+                        .await
+                        .map_err(|e| McpError::internal_error(format!("Freeze time failed: {e}"), None))?
+                        .as_f64()
+                        .ok_or_else(|| McpError::invalid_params(
+                            format!("Unrecognized time: {t} (use ISO 8601 or epoch milliseconds)"),
+                            None,
+                        ))?
+                }
+            },
+        };
+        // f64 parsing accepts "inf", "NaN", and "1e999"; JS Dates only span ±8.64e15 ms
+        if !now_ms.is_finite() || now_ms.abs() > 8.64e15 {
+            return Err(McpError::invalid_params(
+                format!("Time out of range: {} (use ISO 8601 or epoch milliseconds)", time.unwrap_or_default()),
+                None,
+            ));
+        }
+
+        let mut frozen = self.frozen_clock.lock().await;
+        let cdp_identifier = self.register_clock_script(frozen.as_ref(), now_ms)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Freeze time failed: {e}"), None))?;
+        self.eval_value(&js_fake_clock(now_ms))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Freeze time failed: {e}"), None))?;
+        *frozen = Some(FrozenClock { now_ms, cdp_identifier });
+
+        let iso = self.eval_value(&format!("new Date({}).toISOString()", serde_json::json!(now_ms)))
+            .await
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_owned()))
+            .unwrap_or_else(|| format!("{now_ms} ms"));
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Time frozen at {iso} (current page and future documents). Use advance_time to step forward, unfreeze_time to restore."
+        ))]))
+    }
+
+    #[tool(description = "Move the clock frozen by freeze_time forward. Timers (setTimeout/setInterval) that come due within the span fire in order, each seeing the clock at its due time, so countdowns and expiry logic run as if that much time passed.")]
+    async fn advance_time(
+        &self,
+        Parameters(AdvanceTimeParams { ms }): Parameters<AdvanceTimeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut frozen = self.frozen_clock.lock().await;
+        let Some(clock) = frozen.as_mut() else {
+            return Err(McpError::invalid_params(
+                "Time is not frozen — call freeze_time first".to_owned(),
+                None,
+            ));
+        };
+
+        // A page loaded before the script was registered (or a switched tab) may lack the clock
+        let js = format!(
+            r#"(() => {{
+                if (!window.__causeway_clock) {fake_clock};
+                return window.__causeway_clock.advance({ms});
+            }})()"#,
+            fake_clock = js_fake_clock(clock.now_ms),
+        );
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Advance time failed: {e}"), None))?;
+        let now_ms = value.get("now").and_then(|v| v.as_f64()).unwrap_or(clock.now_ms + ms as f64);
+        let fired = value.get("fired").and_then(|v| v.as_u64()).unwrap_or(0);
+        let pending = value.get("pending").and_then(|v| v.as_u64()).unwrap_or(0);
+
+        // New documents should start from the advanced time, not the original freeze point
+        clock.cdp_identifier = self.register_clock_script(Some(&*clock), now_ms)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Advance time failed: {e}"), None))?;
+        clock.now_ms = now_ms;
+
+        let iso = self.eval_value("new Date().toISOString()")
+            .await
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_owned()))
+            .unwrap_or_else(|| format!("{now_ms} ms"));
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Advanced {ms}ms — clock now {iso}. Fired {fired} timer(s); {pending} still pending."
+        ))]))
+    }
+
+    #[tool(description = "Undo freeze_time: restore the real Date, performance.now, and timers on the current page and stop installing the fake clock on new documents.")]
+    async fn unfreeze_time(&self) -> Result<CallToolResult, McpError> {
+        let Some(clock) = self.frozen_clock.lock().await.take() else {
+            return Ok(CallToolResult::success(vec![Content::text("Time is not frozen".to_owned())]));
+        };
+
+        if let Some(identifier) = &clock.cdp_identifier {
+            self.execute_reconnect(commands::remove_init_script(identifier))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Unfreeze time failed: {e}"), None))?;
+        }
+        self.eval_value("(() => { if (window.__causeway_clock) window.__causeway_clock.release(); return true; })()")
+            .await
+            .map_err(|e| McpError::internal_error(format!("Unfreeze time failed: {e}"), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            "Time unfrozen — the page clock runs in real time again".to_owned(),
+        )]))
+    }
+
+    #[tool(description = "Add a stylesheet to the page and return its ID for remove_css. Use to redact sensitive data before screenshots (e.g. \"[data-sensitive] { filter: blur(8px) !important; }\") or for visual tweaks. With persist=true it is re-applied on every navigation, reconnect, and tab switch.")]
+    async fn inject_css(
+        &self,
+        Parameters(InjectCssParams { css, persist }): Parameters<InjectCssParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let id = format!("css-{}", self.next_css_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+        let js = js_inject_css(&id, &css);
+
+        self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Inject CSS failed: {e}"), None))?;
+
+        let persist = persist.unwrap_or(false);
+        let cdp_identifier = if persist {
+            let result = self.execute_reconnect(commands::add_init_script(&js))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Persist CSS failed: {e}"), None))?;
+            result.get("identifier").and_then(|v| v.as_str()).map(|s| s.to_owned())
+        } else {
+            None
+        };
+        self.injected_css.lock().await.push(InjectedCss { id: id.clone(), css, cdp_identifier });
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Stylesheet injected: {id}{}",
+            if persist { " (persists across navigations and reconnects)" } else { " (current page only)" }
+        ))]))
+    }
+
+    #[tool(description = "Remove a stylesheet added with inject_css, from the current page and (if persistent) from future documents.")]
+    async fn remove_css(
+        &self,
+        Parameters(RemoveCssParams { id }): Parameters<RemoveCssParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let identifier = self.injected_css.lock().await
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.cdp_identifier.clone())
+            .ok_or_else(|| McpError::invalid_params(format!("No injected stylesheet with ID: {id}"), None))?;
+
+        if let Some(identifier) = &identifier {
+            self.execute_reconnect(commands::remove_init_script(identifier))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Remove CSS failed: {e}"), None))?;
+        }
+        // Only forget it once the browser has dropped it, so a failed removal can be retried
+        self.injected_css.lock().await.retain(|s| s.id != id);
+        let js = format!(
+            "(() => {{ const s = document.getElementById({}); if (s) s.remove(); return !!s; }})()",
+            serde_json::to_string(&format!("causeway-{id}")).unwrap()
+        );
+        let was_present = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Remove CSS failed: {e}"), None))?
+            .as_bool()
+            .unwrap_or(false);
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Stylesheet removed: {id}{}",
+            if was_present { "" } else { " (it was no longer on the current page)" }
         ))]))
     }
 
     // ---- Batch 1: New interaction tools ----
 
+    #[tool(description = "Check whether a JavaScript dialog (alert, confirm, prompt, or beforeunload) is currently open. Returns its type and message. Use handle_dialog to answer it.")]
+    async fn get_pending_dialog(&self) -> Result<CallToolResult, McpError> {
+        let dialog = self.pending_dialog.lock().await.clone();
+        let text = match dialog {
+            Some(d) if d.dialog_type == "prompt" => format!(
+                "Pending {} dialog: \"{}\" (default: \"{}\")",
+                d.dialog_type, d.message, d.default_prompt
+            ),
+            Some(d) => format!("Pending {} dialog: \"{}\"", d.dialog_type, d.message),
+            None => "No dialog pending".to_owned(),
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
     #[tool(description = "Handle a browser dialog (alert, confirm, prompt, or beforeunload). Use this when a dialog is blocking the page.")]
     async fn handle_dialog(
         &self,
@@ -2835,13 +8268,11 @@ impl CausewayServer {
         Parameters(DoubleClickParams { selector }): Parameters<DoubleClickParams>,
     ) -> Result<CallToolResult, McpError> {
         let js = js_find_visible_element(&selector);
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let coords = self.eval_value(&js)
             .await
             .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
-
-        let coords = result.get("result").and_then(|r| r.get("value"));
         match coords {
-            Some(v) if !v.is_null() => {
+            v if !v.is_null() => {
                 let x = v.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
                 let y = v.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
                 self.execute_seq_reconnect(commands::double_click(x, y))
@@ -2867,12 +8298,13 @@ impl CausewayServer {
         // Resolve "from" coordinates
         let (fx, fy) = if let Some(sel) = &from_selector {
             let js = js_find_visible_element(sel);
-            let result = self.execute_reconnect(commands::evaluate(&js))
+            let v = self.eval_value(&js)
                 .await
                 .map_err(|e| McpError::internal_error(format!("Failed to find from element: {e}"), None))?;
-            let v = result.get("result").and_then(|r| r.get("value"))
-                .filter(|v| !v.is_null())
-                .ok_or_else(|| McpError::invalid_params(format!("No visible element for from_selector: {sel}"), None))?;
+
+            if v.is_null() {
+                return Err(McpError::invalid_params(format!("No visible element for from_selector: {sel}"), None));
+            }
             (
                 v.get("x").and_then(|x| x.as_f64()).unwrap_or(0.0),
                 v.get("y").and_then(|y| y.as_f64()).unwrap_or(0.0),
@@ -2887,12 +8319,13 @@ impl CausewayServer {
         // Resolve "to" coordinates
         let (tx, ty) = if let Some(sel) = &to_selector {
             let js = js_find_visible_element(sel);
-            let result = self.execute_reconnect(commands::evaluate(&js))
+            let v = self.eval_value(&js)
                 .await
                 .map_err(|e| McpError::internal_error(format!("Failed to find to element: {e}"), None))?;
-            let v = result.get("result").and_then(|r| r.get("value"))
-                .filter(|v| !v.is_null())
-                .ok_or_else(|| McpError::invalid_params(format!("No visible element for to_selector: {sel}"), None))?;
+
+            if v.is_null() {
+                return Err(McpError::invalid_params(format!("No visible element for to_selector: {sel}"), None));
+            }
             (
                 v.get("x").and_then(|x| x.as_f64()).unwrap_or(0.0),
                 v.get("y").and_then(|y| y.as_f64()).unwrap_or(0.0),
@@ -2904,27 +8337,93 @@ impl CausewayServer {
             )
         };
 
-        let drag_steps = steps.unwrap_or(10);
-        self.execute_seq_reconnect(commands::drag(fx, fy, tx, ty, drag_steps))
+        let drag_steps = steps.unwrap_or(10);
+        self.execute_seq_reconnect(commands::drag(fx, fy, tx, ty, drag_steps))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Drag failed: {e}"), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Dragged from ({fx:.0}, {fy:.0}) to ({tx:.0}, {ty:.0})"
+        ))]))
+    }
+
+    #[tool(description = "Set the browser viewport size. Useful for testing responsive layouts or ensuring consistent screenshots.")]
+    async fn set_viewport(
+        &self,
+        Parameters(SetViewportParams { width, height }): Parameters<SetViewportParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.execute_reconnect(commands::set_viewport(width, height))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Set viewport failed: {e}"), None))?;
+        *self.device_metrics.lock().await = Some(DeviceMetrics { width, height, scale: 1.0, mobile: false });
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Viewport set to {width}x{height}"
+        ))]))
+    }
+
+    #[tool(description = "Enable or disable JavaScript for the page (Emulation.setScriptExecutionDisabled), to test no-JS fallbacks and progressive enhancement. Applies from the next load — pass reload=true to see the effect now. While disabled, tools built on in-page JavaScript (click_text, read_form, get_value, wait_for_condition, ...) may fail or see the no-JS page. Re-enabling also needs a reload before page scripts run again. Persists across navigations until re-enabled.")]
+    async fn set_javascript_enabled(
+        &self,
+        Parameters(SetJavascriptEnabledParams { enabled, reload }): Parameters<SetJavascriptEnabledParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.execute_reconnect(commands::set_script_execution_disabled(!enabled))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Set JavaScript state failed: {e}"), None))?;
+        self.javascript_disabled.store(!enabled, std::sync::atomic::Ordering::Relaxed);
+
+        let state = if enabled { "enabled" } else { "disabled" };
+        let output = if reload.unwrap_or(false) {
+            self.execute_reconnect(commands::reload(false))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Reload failed: {e}"), None))?;
+            // Page.reload returns before the load — give it the usual settle time
+            tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+            format!("JavaScript {state} — page reloaded")
+        } else {
+            format!("JavaScript {state} — takes effect on the next load (pass reload=true to reload now)")
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Set the page zoom level via CSS zoom on the root element. Layout reflows like browser zoom (Ctrl +/-) — media queries and element sizes respond — unlike pinch zoom which only magnifies. Resets on navigation. Returns the applied factor.")]
+    async fn set_zoom(
+        &self,
+        Parameters(SetZoomParams { factor }): Parameters<SetZoomParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if !(0.1..=10.0).contains(&factor) {
+            return Err(McpError::invalid_params(
+                format!("Zoom factor must be between 0.1 and 10, got {factor}"),
+                None,
+            ));
+        }
+
+        let result = self.execute_reconnect(commands::set_css_zoom(factor))
             .await
-            .map_err(|e| McpError::internal_error(format!("Drag failed: {e}"), None))?;
+            .map_err(|e| McpError::internal_error(format!("Set zoom failed: {e}"), None))?;
+
+        let applied = cdp::take_value(result)
+            .as_f64()
+            .unwrap_or(factor);
 
         Ok(CallToolResult::success(vec![Content::text(format!(
-            "Dragged from ({fx:.0}, {fy:.0}) to ({tx:.0}, {ty:.0})"
+            "Zoom set to {applied} ({:.0}%)", applied * 100.0
         ))]))
     }
 
-    #[tool(description = "Set the browser viewport size. Useful for testing responsive layouts or ensuring consistent screenshots.")]
-    async fn set_viewport(
-        &self,
-        Parameters(SetViewportParams { width, height }): Parameters<SetViewportParams>,
-    ) -> Result<CallToolResult, McpError> {
-        self.execute_reconnect(commands::set_viewport(width, height))
+    #[tool(description = "Get the current page zoom level (CSS zoom on the root element, as set by set_zoom). 1 = 100%.")]
+    async fn get_zoom(&self) -> Result<CallToolResult, McpError> {
+        let result = self.execute_reconnect(commands::get_css_zoom())
             .await
-            .map_err(|e| McpError::internal_error(format!("Set viewport failed: {e}"), None))?;
+            .map_err(|e| McpError::internal_error(format!("Get zoom failed: {e}"), None))?;
+
+        let factor = cdp::take_value(result)
+            .as_f64()
+            .unwrap_or(1.0);
 
         Ok(CallToolResult::success(vec![Content::text(format!(
-            "Viewport set to {width}x{height}"
+            "Zoom: {factor} ({:.0}%)", factor * 100.0
         ))]))
     }
 
@@ -2952,6 +8451,34 @@ impl CausewayServer {
         Ok(CallToolResult::success(vec![Content::text(truncated)]))
     }
 
+    #[tool(description = "Capture a full DOM + layout snapshot via DOMSnapshot.captureSnapshot. Returns the raw structured result as JSON (documents, layout boxes, string table) for offline analysis or scraping. Distinct from inspect (human-readable) and get_html (markup). Large pages are truncated at max_chars.")]
+    async fn capture_dom_snapshot(
+        &self,
+        Parameters(CaptureDomSnapshotParams { computed_styles, include_dom_rects, max_chars }): Parameters<CaptureDomSnapshotParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Enable DOMSnapshot domain (idempotent)
+        let _ = self.execute_reconnect(commands::enable_dom_snapshot()).await;
+
+        let styles = computed_styles.unwrap_or_default();
+        let result = self.execute_reconnect(commands::capture_dom_snapshot(&styles, include_dom_rects.unwrap_or(true)))
+            .await
+            .map_err(|e| McpError::internal_error(format!("DOM snapshot failed: {e}"), None))?;
+
+        let json = serde_json::to_string(&result)
+            .map_err(|e| McpError::internal_error(format!("Failed to serialize snapshot: {e}"), None))?;
+
+        let cap = max_chars.unwrap_or(100_000);
+        let output = if json.len() > cap {
+            let mut end = cap;
+            while !json.is_char_boundary(end) { end -= 1; }
+            format!("{}...\n\n[Truncated — {} total chars; JSON is incomplete. Raise max_chars or request fewer computed_styles]", &json[..end], json.len())
+        } else {
+            json
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
     // ---- Batch 2: Event buffering tools ----
 
     #[tool(description = "Read buffered console messages from the page. Includes console.log, warn, error, etc. Messages accumulate since last navigation or clear.")]
@@ -2986,7 +8513,7 @@ impl CausewayServer {
         ))]))
     }
 
-    #[tool(description = "List buffered network requests captured since last navigation. Shows method, URL, and HTTP status. Optionally filter by URL substring.")]
+    #[tool(description = "List buffered network requests captured since last navigation. Shows request ID, method, URL, and HTTP status. Optionally filter by URL substring. Pass the request ID to get_response_body to read a response.")]
     async fn list_network_requests(
         &self,
         Parameters(ListNetworkRequestsParams { url_filter, clear }): Parameters<ListNetworkRequestsParams>,
@@ -3014,7 +8541,7 @@ impl CausewayServer {
             .iter()
             .map(|e| {
                 let status = e.status.map(|s| format!("{s}")).unwrap_or_else(|| "pending".to_owned());
-                format!("{} {} [{status}]", e.method, e.url)
+                format!("({}) {} {} [{status}]", e.request_id, e.method, e.url)
             })
             .collect::<Vec<_>>()
             .join("\n");
@@ -3026,6 +8553,265 @@ impl CausewayServer {
         ))]))
     }
 
+    #[tool(description = "Get the response body of a captured network request by its request ID (from list_network_requests). Bodies are only kept while the browser holds the resource — read them soon after the request, before navigating away or reloading. Binary bodies are returned base64-encoded.")]
+    async fn get_response_body(
+        &self,
+        Parameters(GetResponseBodyParams { request_id, max_chars }): Parameters<GetResponseBodyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self.execute_reconnect(commands::get_response_body(&request_id))
+            .await
+            .map_err(|e| match e {
+                cdp::CdpError::ResponseError { message, .. } => McpError::invalid_params(
+                    format!("Response body for {request_id} is not available ({message}). Bodies are evicted on navigation/reload and are missing for redirects and still-loading requests."),
+                    None,
+                ),
+                other => McpError::internal_error(format!("Get response body failed: {other}"), None),
+            })?;
+
+        let body = result.get("body").and_then(|v| v.as_str()).unwrap_or("");
+        let base64 = result.get("base64Encoded").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let cap = max_chars.unwrap_or(20_000);
+        let shown = if body.len() > cap {
+            let mut end = cap;
+            while !body.is_char_boundary(end) { end -= 1; }
+            format!("{}...\n\n[Truncated — {} total chars]", &body[..end], body.len())
+        } else {
+            body.to_owned()
+        };
+
+        let header = if base64 {
+            format!("Response body for {request_id} (base64, {} chars):", body.len())
+        } else {
+            format!("Response body for {request_id} ({} chars):", body.len())
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(format!("{header}\n{shown}"))]))
+    }
+
+    #[tool(description = "Wait for one specific network response — e.g. the POST /api/save after clicking submit. Matches the URL by substring, or as a glob if the pattern contains * or ?. Optionally filter by method and status, and include the body. Only responses arriving after the call starts are seen, so start it before (or in parallel with) the action for very fast endpoints.")]
+    async fn wait_for_response(
+        &self,
+        Parameters(WaitForResponseParams { url_pattern, method, status, include_body, max_chars, timeout_ms }): Parameters<WaitForResponseParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let timeout = timeout_ms.unwrap_or(10000);
+        let is_glob = url_pattern.contains(['*', '?']);
+        let url_matches = |url: &str| if is_glob { wildcard_match(&url_pattern, url) } else { url.contains(url_pattern.as_str()) };
+
+        let mut receiver = {
+            let conn = self.live.get().await.ok_or(McpError::internal_error("Not connected", None))?;
+            cdp::subscribe_events(&conn)
+        };
+
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(timeout);
+        // requestId → method, from requestWillBeSent (responseReceived doesn't carry the method)
+        let mut methods: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+        let mut matched: Option<(String, String, u16, String)> = None;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() { break; }
+            let event = match tokio::time::timeout(remaining, receiver.recv()).await {
+                Ok(Ok(event)) => event,
+                Ok(Err(tokio::sync::broadcast::error::RecvError::Lagged(_))) => continue,
+                Ok(Err(tokio::sync::broadcast::error::RecvError::Closed)) => {
+                    return Err(McpError::internal_error("Connection closed while waiting for response".to_owned(), None));
+                }
+                Err(_) => break,
+            };
+            let request_id = event.params.get("requestId").and_then(|v| v.as_str()).unwrap_or("");
+
+            match event.method.as_str() {
+                "Network.requestWillBeSent" if matched.is_none() => {
+                    if let Some(m) = event.params.get("request").and_then(|r| r.get("method")).and_then(|v| v.as_str()) {
+                        methods.insert(request_id.to_owned(), m.to_owned());
+                    }
+                }
+                "Network.responseReceived" if matched.is_none() => {
+                    let response = event.params.get("response");
+                    let url = response.and_then(|r| r.get("url")).and_then(|v| v.as_str()).unwrap_or("");
+                    let code = response.and_then(|r| r.get("status")).and_then(|v| v.as_u64()).unwrap_or(0) as u16;
+                    if !url_matches(url) || status.is_some_and(|s| s != code) {
+                        continue;
+                    }
+                    let req_method = match methods.get(request_id) {
+                        Some(m) => m.clone(),
+                        None => self.network_log.lock().await.iter().rev()
+                            .find(|e| e.request_id == request_id)
+                            .map(|e| e.method.clone())
+                            .unwrap_or_default(),
+                    };
+                    if method.as_deref().is_some_and(|m| !m.eq_ignore_ascii_case(&req_method)) {
+                        continue;
+                    }
+                    matched = Some((request_id.to_owned(), url.to_owned(), code, req_method));
+                    if !include_body.unwrap_or(false) { break; }
+                }
+                // Body is only readable once loading finishes
+                "Network.loadingFinished" | "Network.loadingFailed"
+                    if matched.as_ref().is_some_and(|(id, ..)| id == request_id) => break,
+                _ => {}
+            }
+        }
+
+        let Some((request_id, url, code, req_method)) = matched else {
+            let filters = [
+                method.map(|m| format!("method {m}")),
+                status.map(|s| format!("status {s}")),
+            ].into_iter().flatten().collect::<Vec<_>>();
+            let filters = if filters.is_empty() { String::new() } else { format!(" ({})", filters.join(", ")) };
+            return Err(McpError::internal_error(
+                format!("Timeout: no response matching '{url_pattern}'{filters} within {timeout}ms"),
+                None,
+            ));
+        };
+
+        let mut contents = vec![Content::text(format!("{code} {req_method} {url} ({request_id})"))];
+        if include_body.unwrap_or(false) {
+            match self.get_response_body(Parameters(GetResponseBodyParams { request_id, max_chars })).await {
+                Ok(body) => contents.extend(body.content),
+                Err(e) => contents.push(Content::text(e.message.to_string())),
+            }
+        }
+
+        Ok(CallToolResult::success(contents))
+    }
+
+    #[tool(description = "Mock responses for requests whose URL matches a pattern (* and ? wildcards, e.g. \"*/api/users*\"). Matching requests never reach the network — they are fulfilled with the given status, headers, and body. Mocking the same pattern again replaces it. Persists across navigations and reconnects until unmock.")]
+    async fn mock_response(
+        &self,
+        Parameters(MockResponseParams { url_pattern, status, headers, body, content_type }): Parameters<MockResponseParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let status = status.unwrap_or(200);
+        let mut header_list: Vec<(String, String)> = headers.unwrap_or_default().into_iter().collect();
+        if !header_list.iter().any(|(k, _)| k.eq_ignore_ascii_case("content-type")) {
+            let ct = content_type.unwrap_or_else(|| "application/json".to_owned());
+            header_list.push(("Content-Type".to_owned(), ct));
+        }
+        let rule = InterceptRule {
+            pattern: url_pattern.clone(),
+            action: InterceptAction::Mock { status, headers: header_list, body: body.unwrap_or_default() },
+        };
+
+        {
+            let mut rules = self.intercept_rules.lock().await;
+            rules.retain(|r| r.pattern != url_pattern);
+            rules.push(rule);
+        }
+
+        self.apply_fetch_patterns()
+            .await
+            .map_err(|e| McpError::internal_error(format!("Enable interception failed: {e}"), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Mocking {url_pattern} → {status}"
+        ))]))
+    }
+
+    #[tool(description = "Remove a mock added with mock_response. Omit url_pattern to remove all mocks.")]
+    async fn unmock(
+        &self,
+        Parameters(UnmockParams { url_pattern }): Parameters<UnmockParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let removed = {
+            let mut rules = self.intercept_rules.lock().await;
+            let before = rules.len();
+            rules.retain(|r| {
+                let is_mock = matches!(r.action, InterceptAction::Mock { .. });
+                !(is_mock && url_pattern.as_deref().map(|p| r.pattern == p).unwrap_or(true))
+            });
+            before - rules.len()
+        };
+
+        if removed == 0 {
+            return Ok(CallToolResult::success(vec![Content::text(match &url_pattern {
+                Some(p) => format!("No mock for {p}"),
+                None => "No mocks to remove".to_owned(),
+            })]));
+        }
+
+        self.apply_fetch_patterns()
+            .await
+            .map_err(|e| McpError::internal_error(format!("Update interception failed: {e}"), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Removed {removed} mock(s)"
+        ))]))
+    }
+
+    #[tool(description = "Fail requests whose URL matches any of the patterns (* and ? wildcards) with a network error, as if the server were unreachable. Simulates third-party outages or kills slow non-essential requests (analytics, ads). Shares interception with mock_response — the first matching rule wins. Undo with unfail_requests.")]
+    async fn fail_requests(
+        &self,
+        Parameters(FailRequestsParams { url_patterns, reason }): Parameters<FailRequestsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        const REASONS: &[&str] = &[
+            "Failed", "Aborted", "TimedOut", "AccessDenied", "ConnectionClosed", "ConnectionReset",
+            "ConnectionRefused", "ConnectionAborted", "ConnectionFailed", "NameNotResolved",
+            "InternetDisconnected", "AddressUnreachable", "BlockedByClient", "BlockedByResponse",
+        ];
+        let reason = reason.unwrap_or_else(|| "BlockedByClient".to_owned());
+        if !REASONS.contains(&reason.as_str()) {
+            return Err(McpError::invalid_params(
+                format!("Unknown error reason \"{reason}\". Use one of: {}", REASONS.join(", ")),
+                None,
+            ));
+        }
+        if url_patterns.is_empty() {
+            return Err(McpError::invalid_params("url_patterns must not be empty".to_owned(), None));
+        }
+
+        {
+            let mut rules = self.intercept_rules.lock().await;
+            rules.retain(|r| !url_patterns.contains(&r.pattern));
+            for pattern in &url_patterns {
+                rules.push(InterceptRule {
+                    pattern: pattern.clone(),
+                    action: InterceptAction::Fail { reason: reason.clone() },
+                });
+            }
+        }
+
+        self.apply_fetch_patterns()
+            .await
+            .map_err(|e| McpError::internal_error(format!("Enable interception failed: {e}"), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Failing {} pattern(s) with {reason}: {}",
+            url_patterns.len(),
+            url_patterns.join(", ")
+        ))]))
+    }
+
+    #[tool(description = "Stop failing requests added with fail_requests. Omit url_patterns to remove all failure rules.")]
+    async fn unfail_requests(
+        &self,
+        Parameters(UnfailRequestsParams { url_patterns }): Parameters<UnfailRequestsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let removed = {
+            let mut rules = self.intercept_rules.lock().await;
+            let before = rules.len();
+            rules.retain(|r| {
+                let is_fail = matches!(r.action, InterceptAction::Fail { .. });
+                !(is_fail && url_patterns.as_ref().map(|ps| ps.contains(&r.pattern)).unwrap_or(true))
+            });
+            before - rules.len()
+        };
+
+        if removed == 0 {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No matching failure rules".to_owned(),
+            )]));
+        }
+
+        self.apply_fetch_patterns()
+            .await
+            .map_err(|e| McpError::internal_error(format!("Update interception failed: {e}"), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Removed {removed} failure rule(s)"
+        ))]))
+    }
+
     // ---- File download ----
 
     #[tool(description = "Download a file from a URL and save it to a local path. Works for images, documents, or any publicly accessible file. Automatically forwards browser cookies for authenticated downloads.")]
@@ -3033,6 +8819,7 @@ impl CausewayServer {
         &self,
         Parameters(DownloadFileParams { url, save_path }): Parameters<DownloadFileParams>,
     ) -> Result<CallToolResult, McpError> {
+        check_domain_policy(&self.browser_config, &url).map_err(|msg| McpError::invalid_params(msg, None))?;
         // Forward browser cookies for the target URL (enables authenticated downloads)
         let cookie_header = self.exec_with_reconnect(
             "Network.getCookies",
@@ -3110,11 +8897,23 @@ impl CausewayServer {
 
     // ---- Batch 3: Screenshots, PDF, metrics, storage, device emulation ----
 
-    #[tool(description = "Take a screenshot of a specific element by CSS selector. Returns the cropped image as base64 PNG.")]
+    #[tool(description = "Take a screenshot of a specific element by CSS selector. Returns the cropped image as base64 PNG, or writes it to save_path and returns the path.")]
     async fn element_screenshot(
         &self,
-        Parameters(ElementScreenshotParams { selector }): Parameters<ElementScreenshotParams>,
+        Parameters(ElementScreenshotParams { selector, wait_for_fonts, save_path }): Parameters<ElementScreenshotParams>,
     ) -> Result<CallToolResult, McpError> {
+        let save_format = match &save_path {
+            Some(path) => {
+                let format = screenshot_format_for_path(path)?;
+                prepare_save_path(path)?;
+                Some(format)
+            }
+            None => None,
+        };
+        if wait_for_fonts.unwrap_or(false) {
+            self.await_fonts().await;
+        }
+
         let js = format!(
             r#"(async () => {{
                 const el = document.querySelector({sel});
@@ -3133,15 +8932,13 @@ impl CausewayServer {
             sel = serde_json::to_string(&selector).unwrap()
         );
 
-        let result = self.execute_reconnect(commands::evaluate(&js))
+        let clip = self.eval_value(&js)
             .await
             .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
 
-        let clip = result
-            .get("result")
-            .and_then(|r| r.get("value"))
-            .filter(|v| !v.is_null())
-            .ok_or_else(|| McpError::invalid_params(format!("Element not found: {selector}"), None))?;
+        if clip.is_null() {
+            return Err(McpError::invalid_params(format!("Element not found: {selector}"), None));
+        }
 
         let x = clip.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
         let y = clip.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
@@ -3149,37 +8946,23 @@ impl CausewayServer {
         let h = clip.get("height").and_then(|v| v.as_f64()).unwrap_or(0.0);
         let dpr = clip.get("dpr").and_then(|v| v.as_f64()).unwrap_or(1.0);
 
-        const MAX_BASE64: usize = 5_000_000;
-        const MAX_DIM: f64 = 2000.0;
-        let qualities: &[u8] = &[80, 50, 30, 15];
-        let mut final_data = String::new();
-
-        let max_output_dim = w.max(h) * dpr;
-        let scale = if max_output_dim > MAX_DIM { MAX_DIM / max_output_dim } else { 1.0 };
-
-        for &q in qualities {
-            let screenshot_result = self.exec_with_reconnect(
-                "Page.captureScreenshot",
-                serde_json::json!({
-                    "format": "webp",
-                    "quality": q,
-                    "captureBeyondViewport": true,
-                    "clip": { "x": x, "y": y, "width": w, "height": h, "scale": scale },
-                }),
-            )
-            .await
-            .map_err(|e| McpError::internal_error(format!("Screenshot failed: {e}"), None))?;
-
-            let data = screenshot_result
+        if let (Some(save_path), Some(format)) = (save_path, save_format) {
+            let result = self.execute_reconnect(commands::capture_screenshot(format, Some(90), Some((x, y, w, h, 1.0)), true))
+                .await
+                .map_err(|e| McpError::internal_error(format!("Screenshot failed: {e}"), None))?;
+            let data = result
                 .get("data")
                 .and_then(|d| d.as_str())
                 .ok_or_else(|| McpError::internal_error("No screenshot data returned".to_owned(), None))?;
-
-            final_data = data.to_owned();
-            if final_data.len() <= MAX_BASE64 { break; }
+            let size = write_base64_capture(&save_path, data)?;
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Saved screenshot of '{selector}' ({size} bytes, {format}) to: {save_path}"
+            ))]));
         }
 
-        if final_data.len() > MAX_BASE64 {
+        let final_data = self.capture_clip(x, y, w, h, dpr).await?;
+
+        if final_data.len() > CLIP_MAX_BASE64 {
             return Ok(CallToolResult::success(vec![Content::text(format!(
                 "Element screenshot too large even at lowest quality ({:.1}MB). Try a smaller element or use read_text.",
                 final_data.len() as f64 / 1_000_000.0
@@ -3192,11 +8975,141 @@ impl CausewayServer {
         )]))
     }
 
+    #[tool(description = "Find the element click_text would match for some text, and return a unique CSS selector for it (id, data-testid/name/aria-label, or an nth-of-type path). Reuse the selector with click, type_text, read_text, etc. — faster and unambiguous on later calls. Returns null if no stable selector can be built.")]
+    async fn get_selector_for_text(
+        &self,
+        Parameters(ClickTextParams { text, tag }): Parameters<ClickTextParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = js_find_text_element_opts(&text, tag.as_deref().unwrap_or("*"), true);
+
+        let found = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
+
+        if found.is_null() {
+            return Err(McpError::invalid_params(
+                format!("No visible element found containing text: \"{text}\""),
+                None,
+            ));
+        }
+
+        let matched = found.get("matched").and_then(|v| v.as_str()).unwrap_or(&text);
+        match found.get("selector").and_then(|v| v.as_str()) {
+            Some(selector) => Ok(CallToolResult::success(vec![Content::text(format!(
+                "Selector: {selector}\nMatched: \"{matched}\""
+            ))])),
+            None => Ok(CallToolResult::success(vec![Content::text(format!(
+                "Selector: null (no stable unique selector for \"{matched}\" — keep using click_text)"
+            ))])),
+        }
+    }
+
+    #[tool(description = "Screenshot just the region around the first visible element containing some text (same matching as click_text), with padding. Returns the cropped image plus the matched text. For checking that a specific label or button renders correctly without a full-page screenshot.")]
+    async fn screenshot_text(
+        &self,
+        Parameters(ScreenshotTextParams { text, tag, padding }): Parameters<ScreenshotTextParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = js_find_text_element(&text, tag.as_deref().unwrap_or("*"));
+
+        let found = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to find element: {e}"), None))?;
+
+        if found.is_null() {
+            return Err(McpError::invalid_params(
+                format!("No visible element found containing text: \"{text}\""),
+                None,
+            ));
+        }
+
+        let matched = found.get("matched").and_then(|v| v.as_str()).unwrap_or(&text).to_owned();
+        let bx = found.get("box");
+        let field = |k: &str| bx.and_then(|b| b.get(k)).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let pad = padding.unwrap_or(16.0).max(0.0);
+        let x = (field("x") - pad).max(0.0);
+        let y = (field("y") - pad).max(0.0);
+        let w = field("width") + pad * 2.0;
+        let h = field("height") + pad * 2.0;
+
+        let dpr = self.eval_value("window.devicePixelRatio")
+            .await
+            .ok()
+            .and_then(|v| v.as_f64())
+            .unwrap_or(1.0);
+
+        let data = self.capture_clip(x, y, w, h, dpr).await?;
+        if data.len() > CLIP_MAX_BASE64 {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Region around \"{matched}\" too large even at lowest quality ({:.1}MB). Try a smaller padding or use read_text.",
+                data.len() as f64 / 1_000_000.0
+            ))]));
+        }
+
+        Ok(CallToolResult::success(vec![
+            Content::image(data, "image/webp"),
+            Content::text(format!("Matched \"{matched}\" ({w:.0}×{h:.0} region)")),
+        ]))
+    }
+
+    #[tool(description = "Wait for a browser download (e.g. started by clicking a download link) to finish. Returns the file path and size. Each completed download is reported once, so calling again waits for the next one. Set download_dir in config to get full paths.")]
+    async fn wait_for_download(
+        &self,
+        Parameters(WaitForDownloadParams { timeout_ms }): Parameters<WaitForDownloadParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Make sure download events are flowing (lazy connect may not have happened yet)
+        if self.live.get().await.is_none() {
+            self.try_reconnect().await.map_err(|msg| McpError::internal_error(msg, None))?;
+        }
+
+        let timeout = timeout_ms.unwrap_or(30000);
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(timeout);
+
+        loop {
+            {
+                let mut list = self.downloads.lock().await;
+                if let Some(entry) = list.iter_mut().find(|d| !d.reported && d.state != "inProgress") {
+                    entry.reported = true;
+                    if entry.state == "canceled" {
+                        return Err(McpError::internal_error(
+                            format!("Download canceled: {} ({})", entry.suggested_filename, entry.url),
+                            None,
+                        ));
+                    }
+                    let path = entry.file_path.clone().unwrap_or_else(|| {
+                        match self.browser_config.download_dir.as_deref() {
+                            Some(dir) => std::path::Path::new(dir).join(&entry.suggested_filename).display().to_string(),
+                            None => format!("{} (in the browser's download folder)", entry.suggested_filename),
+                        }
+                    });
+                    return Ok(CallToolResult::success(vec![Content::text(format!(
+                        "Download completed: {path} ({} bytes)\nSource: {}",
+                        entry.received_bytes, entry.url
+                    ))]));
+                }
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                let in_progress = self.downloads.lock().await.iter().filter(|d| d.state == "inProgress").count();
+                let detail = if in_progress > 0 {
+                    format!("{in_progress} download(s) still in progress")
+                } else {
+                    "no download started".to_owned()
+                };
+                return Err(McpError::internal_error(
+                    format!("Timeout waiting for download after {timeout}ms ({detail})"),
+                    None,
+                ));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        }
+    }
+
     #[tool(description = "Save the current page as a PDF file. Renders the full page with print styles applied.")]
     async fn save_pdf(
         &self,
         Parameters(SavePdfParams { save_path }): Parameters<SavePdfParams>,
     ) -> Result<CallToolResult, McpError> {
+        prepare_save_path(&save_path)?;
         let result = self.execute_reconnect(commands::print_to_pdf())
             .await
             .map_err(|e| McpError::internal_error(format!("PDF generation failed: {e}"), None))?;
@@ -3206,24 +9119,50 @@ impl CausewayServer {
             .and_then(|d| d.as_str())
             .ok_or_else(|| McpError::internal_error("No PDF data returned".to_owned(), None))?;
 
-        use base64::Engine;
-        let bytes = base64::engine::general_purpose::STANDARD
-            .decode(data)
-            .map_err(|e| McpError::internal_error(format!("Failed to decode PDF: {e}"), None))?;
+        let size = write_base64_capture(&save_path, data)?;
 
-        let size = bytes.len();
-        let path = std::path::Path::new(&save_path);
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)
-                .map_err(|e| McpError::internal_error(format!("Failed to create directory: {e}"), None))?;
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Saved PDF ({size} bytes) to: {save_path}"
+        ))]))
+    }
+
+    #[tool(description = "Capture the rendered page as MHTML — a single-file archive with images and CSS inlined, unlike get_html. Saves to save_path, or returns the text inline (size-capped). Prefer save_path: MHTML is often several MB.")]
+    async fn capture_mhtml(
+        &self,
+        Parameters(CaptureMhtmlParams { save_path, max_chars }): Parameters<CaptureMhtmlParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(path) = &save_path {
+            prepare_save_path(path)?;
         }
+        let result = self.execute_reconnect(commands::capture_snapshot("mhtml"))
+            .await
+            .map_err(|e| McpError::internal_error(format!("MHTML capture failed: {e}"), None))?;
 
-        std::fs::write(&save_path, &bytes)
-            .map_err(|e| McpError::internal_error(format!("Failed to write PDF: {e}"), None))?;
+        let data = result
+            .get("data")
+            .and_then(|d| d.as_str())
+            .ok_or_else(|| McpError::internal_error("No MHTML data returned".to_owned(), None))?;
+
+        let size = data.len();
+        if let Some(save_path) = save_path {
+            std::fs::write(&save_path, data)
+                .map_err(|e| McpError::internal_error(format!("Failed to write MHTML: {e}"), None))?;
+
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Saved MHTML ({size} bytes) to: {save_path}"
+            ))]));
+        }
+
+        let cap = max_chars.unwrap_or(50_000);
+        let output = if size > cap {
+            let mut end = cap;
+            while !data.is_char_boundary(end) { end -= 1; }
+            format!("{}...\n\n[Truncated — {size} total chars. Pass save_path to get the full archive]", &data[..end])
+        } else {
+            data.to_owned()
+        };
 
-        Ok(CallToolResult::success(vec![Content::text(format!(
-            "Saved PDF ({size} bytes) to: {save_path}"
-        ))]))
+        Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
     #[tool(description = "Get browser performance metrics: DOM node count, JS heap size, layout count, and more. Useful for diagnosing performance issues.")]
@@ -3261,6 +9200,229 @@ impl CausewayServer {
         ))]))
     }
 
+    #[tool(description = "Get page load timings for the current document: TTFB, DOMContentLoaded, load, first paint, first contentful paint, largest contentful paint, cumulative layout shift, transfer size, and JS heap usage. Read from the Navigation/Paint Timing APIs. Use get_page_metrics for raw browser counters.")]
+    async fn get_performance_metrics(&self) -> Result<CallToolResult, McpError> {
+        let js = r#"(async () => {
+            const observed = (type) => new Promise(resolve => {
+                try {
+                    const po = new PerformanceObserver(list => resolve(list.getEntries()));
+                    po.observe({ type, buffered: true });
+                    setTimeout(() => { po.disconnect(); resolve([]); }, 100);
+                } catch (e) { resolve([]); }
+            });
+            const nav = performance.getEntriesByType('navigation')[0];
+            const paint = Object.fromEntries(performance.getEntriesByType('paint').map(p => [p.name, p.startTime]));
+            const lcp = await observed('largest-contentful-paint');
+            const shifts = await observed('layout-shift');
+            const round = (v) => v == null ? null : Math.round(v);
+            return {
+                url: location.href,
+                ttfb_ms: nav ? round(nav.responseStart - nav.startTime) : null,
+                dom_content_loaded_ms: nav ? round(nav.domContentLoadedEventEnd - nav.startTime) : null,
+                load_ms: nav && nav.loadEventEnd > 0 ? round(nav.loadEventEnd - nav.startTime) : null,
+                first_paint_ms: round(paint['first-paint']),
+                first_contentful_paint_ms: round(paint['first-contentful-paint']),
+                largest_contentful_paint_ms: lcp.length ? round(lcp[lcp.length - 1].startTime) : null,
+                cumulative_layout_shift: Math.round(shifts.filter(s => !s.hadRecentInput).reduce((a, s) => a + s.value, 0) * 1000) / 1000,
+                transfer_size_kb: nav ? Math.round(nav.transferSize / 1024) : null,
+                resource_count: performance.getEntriesByType('resource').length,
+                js_heap_used_mb: performance.memory ? Math.round(performance.memory.usedJSHeapSize / 1048576 * 10) / 10 : null,
+                js_heap_total_mb: performance.memory ? Math.round(performance.memory.totalJSHeapSize / 1048576 * 10) / 10 : null,
+            };
+        })()"#;
+
+        let result = self.eval_value(js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Get performance metrics failed: {e}"), None))?;
+
+        let value = result
+            .as_object()
+            .ok_or_else(|| McpError::internal_error("No performance data returned".to_owned(), None))?;
+
+        let output: Vec<String> = value
+            .iter()
+            .map(|(k, v)| {
+                let shown = if v.is_null() { "n/a".to_owned() } else if let Some(s) = v.as_str() { s.to_owned() } else { v.to_string() };
+                format!("{k}: {shown}")
+            })
+            .collect();
+
+        Ok(CallToolResult::success(vec![Content::text(output.join("\n"))]))
+    }
+
+    #[tool(description = "Start collecting JS (block-level) and CSS (rule usage) coverage. Exercise the page, then call stop_coverage for per-file used/unused byte counts.")]
+    async fn start_coverage(&self) -> Result<CallToolResult, McpError> {
+        self.execute_seq_reconnect(commands::start_js_coverage())
+            .await
+            .map_err(|e| McpError::internal_error(format!("Start JS coverage failed: {e}"), None))?;
+        self.execute_seq_reconnect(commands::start_css_coverage())
+            .await
+            .map_err(|e| McpError::internal_error(format!("Start CSS coverage failed: {e}"), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            "Coverage started (JS + CSS). Call stop_coverage to collect results.".to_owned(),
+        )]))
+    }
+
+    #[tool(description = "Stop coverage started with start_coverage and report per-file used/unused bytes for JS and CSS, sorted by most unused. Useful for spotting dead code.")]
+    async fn stop_coverage(&self) -> Result<CallToolResult, McpError> {
+        let js = self.execute_reconnect(commands::take_js_coverage())
+            .await
+            .map_err(|e| McpError::internal_error(format!("Collect JS coverage failed (was start_coverage called?): {e}"), None))?;
+        let _ = self.execute_seq_reconnect(commands::stop_js_coverage()).await;
+        let css = self.execute_reconnect(commands::stop_css_coverage())
+            .await
+            .map_err(|e| McpError::internal_error(format!("Collect CSS coverage failed: {e}"), None))?;
+
+        // JS: the first function of each script is the top-level, spanning the whole source
+        let mut js_rows: Vec<(String, u64, u64)> = Vec::new();
+        for script in js.get("result").and_then(|v| v.as_array()).into_iter().flatten() {
+            let url = script.get("url").and_then(|v| v.as_str()).unwrap_or("");
+            if url.is_empty() {
+                continue;
+            }
+            let mut ranges: Vec<(u64, u64, u64)> = script
+                .get("functions")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|f| f.get("ranges")?.as_array())
+                .flatten()
+                .filter_map(|r| Some((
+                    r.get("startOffset")?.as_u64()?,
+                    r.get("endOffset")?.as_u64()?,
+                    r.get("count")?.as_u64()?,
+                )))
+                .collect();
+            let total = ranges.iter().map(|r| r.1).max().unwrap_or(0);
+            let used = covered_bytes(&mut ranges);
+            js_rows.push((url.to_owned(), used, total));
+        }
+
+        // CSS: sum rule lengths per stylesheet
+        let sheets = self.stylesheets.lock().await;
+        let mut css_totals: std::collections::HashMap<String, (u64, u64)> = std::collections::HashMap::new();
+        for rule in css.get("ruleUsage").and_then(|v| v.as_array()).into_iter().flatten() {
+            let id = rule.get("styleSheetId").and_then(|v| v.as_str()).unwrap_or("");
+            let start = rule.get("startOffset").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let end = rule.get("endOffset").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let len = (end - start).max(0.0) as u64;
+            let label = match sheets.get(id).map(|u| u.as_str()) {
+                Some(url) if !url.is_empty() => url.to_owned(),
+                _ => format!("(inline stylesheet {id})"),
+            };
+            let entry = css_totals.entry(label).or_insert((0, 0));
+            if rule.get("used").and_then(|v| v.as_bool()).unwrap_or(false) {
+                entry.0 += len;
+            }
+            entry.1 += len;
+        }
+        drop(sheets);
+        let mut css_rows: Vec<(String, u64, u64)> = css_totals
+            .into_iter()
+            .map(|(label, (used, total))| (label, used, total))
+            .collect();
+
+        let by_unused = |a: &(String, u64, u64), b: &(String, u64, u64)| {
+            (b.2.saturating_sub(b.1)).cmp(&a.2.saturating_sub(a.1))
+        };
+        js_rows.sort_by(by_unused);
+        css_rows.sort_by(by_unused);
+
+        let mut output = format!("JS coverage ({} file(s)):\n", js_rows.len());
+        for (url, used, total) in js_rows.iter().take(50) {
+            output.push_str(&format!("  {}\n", format_coverage_line(url, *used, *total)));
+        }
+        output.push_str(&format!("\nCSS coverage ({} stylesheet(s)):\n", css_rows.len()));
+        for (label, used, total) in css_rows.iter().take(50) {
+            output.push_str(&format!("  {}\n", format_coverage_line(label, *used, *total)));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Grant browser permissions without a prompt (e.g. \"geolocation\", \"notifications\", \"clipboardReadWrite\", \"clipboardSanitizedWrite\", \"camera\", \"microphone\"). Optionally limited to one origin. Grants last until reset_permissions.")]
+    async fn grant_permissions(
+        &self,
+        Parameters(GrantPermissionsParams { permissions, origin }): Parameters<GrantPermissionsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if permissions.is_empty() {
+            return Err(McpError::invalid_params("permissions must not be empty".to_owned(), None));
+        }
+
+        self.execute_reconnect(commands::grant_permissions(&permissions, origin.as_deref()))
+            .await
+            .map_err(|e| McpError::internal_error(format!("Grant permissions failed: {e}"), None))?;
+
+        let scope = origin.clone().unwrap_or_else(|| "all origins".to_owned());
+        self.granted_permissions.lock().await.push(PermissionGrant { origin, permissions: permissions.clone() });
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Granted {} for {scope}",
+            permissions.join(", ")
+        ))]))
+    }
+
+    #[tool(description = "List permissions granted this session with grant_permissions, plus any auto-granted from the grant_permissions config.")]
+    async fn list_permissions(&self) -> Result<CallToolResult, McpError> {
+        let grants = self.granted_permissions.lock().await;
+        let configured = &self.browser_config.grant_permissions;
+        if grants.is_empty() && configured.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No permissions granted this session".to_owned(),
+            )]));
+        }
+
+        let mut lines: Vec<String> = Vec::new();
+        if !configured.is_empty() {
+            lines.push(format!(
+                "{}: {} (config, re-granted on connect)",
+                self.browser_config.grant_permissions_origin.as_deref().unwrap_or("all origins"),
+                configured.join(", ")
+            ));
+        }
+        lines.extend(grants
+            .iter()
+            .map(|g| format!("{}: {}", g.origin.as_deref().unwrap_or("all origins"), g.permissions.join(", "))));
+        let output = lines.join("\n");
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Reset all permission grants back to the browser's default prompting behavior (Browser.resetPermissions). Use before testing permission-prompt flows after earlier grants.")]
+    async fn reset_permissions(&self) -> Result<CallToolResult, McpError> {
+        self.execute_reconnect(commands::reset_permissions())
+            .await
+            .map_err(|e| McpError::internal_error(format!("Reset permissions failed: {e}"), None))?;
+
+        let cleared = std::mem::take(&mut *self.granted_permissions.lock().await);
+        let detail = if cleared.is_empty() {
+            String::new()
+        } else {
+            let list = cleared
+                .iter()
+                .map(|g| format!("{}: {}", g.origin.as_deref().unwrap_or("all origins"), g.permissions.join(", ")))
+                .collect::<Vec<_>>()
+                .join("; ");
+            format!(" (cleared {list})")
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Permissions reset{detail}"
+        ))]))
+    }
+
+    #[tool(description = "Clear the browser's HTTP cache (Network.clearBrowserCache) for all sites, so the next navigation fetches every resource fresh. Leaves cookies and site storage alone — use clear_storage for those.")]
+    async fn clear_cache(&self) -> Result<CallToolResult, McpError> {
+        self.execute_reconnect(commands::clear_browser_cache())
+            .await
+            .map_err(|e| McpError::internal_error(format!("Clear cache failed: {e}"), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            "HTTP cache cleared (cookies and storage untouched)".to_owned(),
+        )]))
+    }
+
     #[tool(description = "Clear browser cache and/or site storage (cookies, localStorage, sessionStorage, IndexedDB, cache storage). Operates on the current page's origin.")]
     async fn clear_storage(
         &self,
@@ -3269,13 +9431,11 @@ impl CausewayServer {
         let mut cleared = Vec::new();
 
         // Get current origin for storage clearing
-        let origin_result = self.execute_reconnect(commands::evaluate("window.location.origin"))
+        let origin_result = self.eval_value("window.location.origin")
             .await
             .ok();
         let origin = origin_result
             .as_ref()
-            .and_then(|r| r.get("result"))
-            .and_then(|r| r.get("value"))
             .and_then(|v| v.as_str())
             .unwrap_or("");
 
@@ -3307,6 +9467,144 @@ impl CausewayServer {
         ))]))
     }
 
+    #[tool(description = "List service worker registrations for the current origin (navigator.serviceWorker.getRegistrations): scope, script URL, and state of the active, waiting, and installing workers, plus whether one controls this page. Stale workers are a common cause of confusing caching between test runs.")]
+    async fn list_service_workers(&self) -> Result<CallToolResult, McpError> {
+        let js = r#"(async () => {
+            if (!('serviceWorker' in navigator)) return { supported: false, origin: location.origin };
+            const worker = w => w ? { script: w.scriptURL, state: w.state } : null;
+            const regs = await navigator.serviceWorker.getRegistrations();
+            return {
+                supported: true,
+                origin: location.origin,
+                controller: navigator.serviceWorker.controller ? navigator.serviceWorker.controller.scriptURL : null,
+                registrations: regs.map(r => ({
+                    scope: r.scope,
+                    active: worker(r.active),
+                    waiting: worker(r.waiting),
+                    installing: worker(r.installing),
+                    update_via_cache: r.updateViaCache,
+                })),
+            };
+        })()"#;
+
+        let value = self.eval_value(js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("List service workers failed: {e}"), None))?;
+
+        let origin = value.get("origin").and_then(|v| v.as_str()).unwrap_or("");
+        if value.get("supported").and_then(|v| v.as_bool()) != Some(true) {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Service workers are unavailable on {origin} (they need a secure context: https or localhost)"
+            ))]));
+        }
+        let registrations = value.get("registrations").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        if registrations.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No service workers registered for {origin}"
+            ))]));
+        }
+
+        let controller = value.get("controller").and_then(|v| v.as_str());
+        let mut lines = vec![format!("{} service worker registration(s) for {origin}:", registrations.len())];
+        for reg in &registrations {
+            lines.push(format!("  scope: {}", reg.get("scope").and_then(|v| v.as_str()).unwrap_or("")));
+            for slot in ["active", "waiting", "installing"] {
+                if let Some(w) = reg.get(slot).filter(|w| !w.is_null()) {
+                    let script = w.get("script").and_then(|v| v.as_str()).unwrap_or("");
+                    let state = w.get("state").and_then(|v| v.as_str()).unwrap_or("");
+                    lines.push(format!("    {slot}: {script} ({state})"));
+                }
+            }
+        }
+        lines.push(match controller {
+            Some(script) => format!("This page is controlled by {script}"),
+            None => "This page is not controlled by a service worker".to_owned(),
+        });
+
+        Ok(CallToolResult::success(vec![Content::text(lines.join("\n"))]))
+    }
+
+    #[tool(description = "Unregister service workers for the current origin so cached responses and fetch handlers from earlier runs stop interfering. Returns the scopes removed. The page stays controlled until it is reloaded; pair with clear_storage (cache_storage) to drop their caches too.")]
+    async fn unregister_service_workers(
+        &self,
+        Parameters(UnregisterServiceWorkersParams { scope }): Parameters<UnregisterServiceWorkersParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let js = format!(
+            r#"(async () => {{
+                if (!('serviceWorker' in navigator)) return {{ supported: false, origin: location.origin }};
+                const filter = {filter};
+                const regs = await navigator.serviceWorker.getRegistrations();
+                const removed = [], failed = [];
+                for (const r of regs) {{
+                    if (filter && !r.scope.includes(filter)) continue;
+                    try {{ (await r.unregister()) ? removed.push(r.scope) : failed.push(r.scope); }}
+                    catch (e) {{ failed.push(r.scope + ' (' + e.message + ')'); }}
+                }}
+                return {{
+                    supported: true,
+                    origin: location.origin,
+                    removed,
+                    failed,
+                    kept: regs.length - removed.length - failed.length,
+                    controlled: !!navigator.serviceWorker.controller,
+                }};
+            }})()"#,
+            filter = serde_json::to_string(&scope).unwrap(),
+        );
+
+        let value = self.eval_value(&js)
+            .await
+            .map_err(|e| McpError::internal_error(format!("Unregister service workers failed: {e}"), None))?;
+
+        let origin = value.get("origin").and_then(|v| v.as_str()).unwrap_or("");
+        if value.get("supported").and_then(|v| v.as_bool()) != Some(true) {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Service workers are unavailable on {origin} — nothing to unregister"
+            ))]));
+        }
+        let scopes = |key: &str| -> Vec<String> {
+            value.get(key)
+                .and_then(|v| v.as_array())
+                .map(|a| a.iter().filter_map(|s| s.as_str().map(|s| s.to_owned())).collect())
+                .unwrap_or_default()
+        };
+        let removed = scopes("removed");
+        let failed = scopes("failed");
+        let kept = value.get("kept").and_then(|v| v.as_u64()).unwrap_or(0);
+
+        if removed.is_empty() && failed.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No matching service workers registered for {origin}{}",
+                if kept > 0 { format!(" ({kept} left untouched by the scope filter)") } else { String::new() }
+            ))]));
+        }
+
+        let mut lines = vec![format!("Unregistered {} service worker(s) for {origin}:", removed.len())];
+        lines.extend(removed.iter().map(|s| format!("  {s}")));
+        if !failed.is_empty() {
+            lines.push(format!("Failed to unregister {}:", failed.len()));
+            lines.extend(failed.iter().map(|s| format!("  {s}")));
+        }
+        if kept > 0 {
+            lines.push(format!("{kept} registration(s) left untouched by the scope filter"));
+        }
+        if value.get("controlled").and_then(|v| v.as_bool()) == Some(true) {
+            lines.push("This page is still controlled until it reloads".to_owned());
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(lines.join("\n"))]))
+    }
+
+    #[tool(description = "Take the page offline (Network.emulateNetworkConditions) — fetches and navigations fail as if the network dropped, and navigator.onLine becomes false. For PWA and offline-UX testing. Undo with go_online.")]
+    async fn go_offline(&self) -> Result<CallToolResult, McpError> {
+        self.set_offline(true).await
+    }
+
+    #[tool(description = "Bring the page back online after go_offline (clears network emulation, no throttling).")]
+    async fn go_online(&self) -> Result<CallToolResult, McpError> {
+        self.set_offline(false).await
+    }
+
     #[tool(description = "Emulate a mobile device or custom viewport with user agent, touch events, and device scale factor. Use device presets or specify custom parameters. Use device='reset' to clear emulation.")]
     async fn emulate_device(
         &self,
@@ -3317,6 +9615,7 @@ impl CausewayServer {
             self.execute_reconnect(commands::clear_device_override())
                 .await
                 .map_err(|e| McpError::internal_error(format!("Clear emulation failed: {e}"), None))?;
+            *self.device_metrics.lock().await = None;
             self.execute_reconnect(commands::set_user_agent(""))
                 .await
                 .map_err(|e| McpError::internal_error(format!("Clear user agent failed: {e}"), None))?;
@@ -3340,6 +9639,7 @@ impl CausewayServer {
                 self.execute_reconnect(commands::emulate_device_metrics(actual_w, actual_h, actual_scale, mobile))
                     .await
                     .map_err(|e| McpError::internal_error(format!("Set device metrics failed: {e}"), None))?;
+                *self.device_metrics.lock().await = Some(DeviceMetrics { width: actual_w, height: actual_h, scale: actual_scale, mobile });
                 self.execute_reconnect(commands::set_user_agent(actual_ua))
                     .await
                     .map_err(|e| McpError::internal_error(format!("Set user agent failed: {e}"), None))?;
@@ -3371,6 +9671,7 @@ impl CausewayServer {
         self.execute_reconnect(commands::emulate_device_metrics(w, h, scale, enable_touch))
             .await
             .map_err(|e| McpError::internal_error(format!("Set device metrics failed: {e}"), None))?;
+        *self.device_metrics.lock().await = Some(DeviceMetrics { width: w, height: h, scale, mobile: enable_touch });
 
         if let Some(ua) = &user_agent {
             self.execute_reconnect(commands::set_user_agent(ua))
@@ -3424,14 +9725,24 @@ impl CausewayServer {
                         McpError::invalid_params(format!("Step {}: click requires \"selector\"", i + 1), None)
                     })?;
                     let js = js_find_visible_element(selector);
-                    let result = self.execute_reconnect(commands::evaluate(&js)).await
-                        .map_err(|e| McpError::internal_error(format!("Step {}: {e}", i + 1), None))?;
-                    let coords = result.get("result").and_then(|r| r.get("value")).filter(|v| !v.is_null())
-                        .ok_or_else(|| McpError::invalid_params(format!("Step {}: no element found for: {selector}", i + 1), None))?;
+                    let wait = step.get("wait_ms").and_then(|v| v.as_u64()).unwrap_or(0);
+                    let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(wait);
+                    let coords = loop {
+                        let result = self.eval_value(&js).await
+                            .map_err(|e| McpError::internal_error(format!("Step {}: {e}", i + 1), None))?;
+                        if !result.is_null() {
+                            break result;
+                        }
+                        if tokio::time::Instant::now() >= deadline {
+                            return Err(McpError::invalid_params(format!("Step {}: no element found for: {selector}", i + 1), None));
+                        }
+                        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                    };
                     let x = coords.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
                     let y = coords.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
                     self.snapshot_pre_nav().await;
-                    self.execute_seq_reconnect(commands::click(x, y)).await
+                    let human = step.get("human_like").and_then(|v| v.as_bool()).unwrap_or(self.browser_config.human_like);
+                    self.execute_seq_paced(commands::click(x, y), human).await
                         .map_err(|e| McpError::internal_error(format!("Step {}: click failed: {e}", i + 1), None))?;
                     format!("Clicked '{selector}' at ({x:.0}, {y:.0})")
                 }
@@ -3440,36 +9751,12 @@ impl CausewayServer {
                         McpError::invalid_params(format!("Step {}: click_text requires \"text\"", i + 1), None)
                     })?;
                     let tag_filter = step.get("tag").and_then(|v| v.as_str()).unwrap_or("*");
-                    let js = format!(
-                        r#"(async () => {{
-                            const searchText = {text}.toLowerCase();
-                            const els = document.querySelectorAll({tag});
-                            const vw = window.innerWidth; const vh = window.innerHeight;
-                            const candidates = [];
-                            for (const el of els) {{
-                                const elText = el.textContent.trim().toLowerCase();
-                                if (!elText.includes(searchText)) continue;
-                                const r = el.getBoundingClientRect();
-                                if (r.width === 0 || r.height === 0) continue;
-                                candidates.push({{ el, len: elText.length }});
-                            }}
-                            candidates.sort((a, b) => a.len - b.len);
-                            for (const {{ el }} of candidates) {{
-                                el.scrollIntoView({{ block: 'center', behavior: 'instant' }});
-                                await new Promise(resolve => requestAnimationFrame(() => requestAnimationFrame(resolve)));
-                                const rect = el.getBoundingClientRect();
-                                const cx = rect.x + rect.width / 2; const cy = rect.y + rect.height / 2;
-                                if (cx >= 0 && cy >= 0 && cx <= vw && cy <= vh) return {{ x: cx, y: cy, matched: el.textContent.trim().substring(0, 80) }};
-                            }}
-                            return null;
-                        }})()"#,
-                        text = serde_json::to_string(text).unwrap(),
-                        tag = serde_json::to_string(tag_filter).unwrap()
-                    );
-                    let result = self.execute_reconnect(commands::evaluate(&js)).await
+                    let js = js_find_text_element(text, tag_filter);
+                    let coords = self.eval_value(&js).await
                         .map_err(|e| McpError::internal_error(format!("Step {}: {e}", i + 1), None))?;
-                    let coords = result.get("result").and_then(|r| r.get("value")).filter(|v| !v.is_null())
-                        .ok_or_else(|| McpError::invalid_params(format!("Step {}: no element with text \"{text}\"", i + 1), None))?;
+                    if coords.is_null() {
+                        return Err(McpError::invalid_params(format!("Step {}: no element with text \"{text}\"", i + 1), None));
+                    }
                     let x = coords.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
                     let y = coords.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
                     let matched = coords.get("matched").and_then(|v| v.as_str()).unwrap_or(text);
@@ -3510,10 +9797,11 @@ impl CausewayServer {
                         text = serde_json::to_string(text).unwrap(),
                         idx = idx,
                     );
-                    let result = self.execute_reconnect(commands::evaluate(&js)).await
+                    let coords = self.eval_value(&js).await
                         .map_err(|e| McpError::internal_error(format!("Step {}: {e}", i + 1), None))?;
-                    let coords = result.get("result").and_then(|r| r.get("value")).filter(|v| !v.is_null())
-                        .ok_or_else(|| McpError::invalid_params(format!("Step {}: no interactive element with text \"{text}\"", i + 1), None))?;
+                    if coords.is_null() {
+                        return Err(McpError::invalid_params(format!("Step {}: no interactive element with text \"{text}\"", i + 1), None));
+                    }
                     if let Some(err) = coords.get("error").and_then(|v| v.as_str()) {
                         return Err(McpError::invalid_params(format!("Step {}: {err}", i + 1), None));
                     }
@@ -3534,13 +9822,14 @@ impl CausewayServer {
                     })?;
                     let should_clear = step.get("clear").and_then(|v| v.as_bool()).unwrap_or(false);
                     let js = js_focus_visible_element(selector, should_clear);
-                    let result = self.execute_reconnect(commands::evaluate(&js)).await
+                    let result = self.eval_value(&js).await
                         .map_err(|e| McpError::internal_error(format!("Step {}: {e}", i + 1), None))?;
-                    let focused = result.get("result").and_then(|r| r.get("value")).and_then(|v| v.as_bool()).unwrap_or(false);
+                    let focused = result.as_bool().unwrap_or(false);
                     if !focused {
                         return Err(McpError::invalid_params(format!("Step {}: element not focusable: {selector}", i + 1), None));
                     }
-                    self.execute_seq_reconnect(commands::type_text(text)).await
+                    let human = step.get("human_like").and_then(|v| v.as_bool()).unwrap_or(self.browser_config.human_like);
+                    self.execute_seq_paced(commands::type_text(text), human).await
                         .map_err(|e| McpError::internal_error(format!("Step {}: type failed: {e}", i + 1), None))?;
                     format!("Typed {} chars into '{selector}'", text.len())
                 }
@@ -3573,9 +9862,9 @@ impl CausewayServer {
                         sel = serde_json::to_string(selector).unwrap(),
                         val = serde_json::to_string(value).unwrap()
                     );
-                    let result = self.execute_reconnect(commands::evaluate(&js)).await
+                    let result = self.eval_value(&js).await
                         .map_err(|e| McpError::internal_error(format!("Step {}: {e}", i + 1), None))?;
-                    let ok = result.get("result").and_then(|r| r.get("value")).and_then(|v| v.as_bool()).unwrap_or(false);
+                    let ok = result.as_bool().unwrap_or(false);
                     if !ok {
                         return Err(McpError::invalid_params(format!("Step {}: select element not found: {selector}", i + 1), None));
                     }
@@ -3598,9 +9887,9 @@ impl CausewayServer {
                     let mut found = false;
                     for _ in 0..max_attempts {
                         let js = format!("document.querySelector({sel}) !== null", sel = serde_json::to_string(selector).unwrap());
-                        let result = self.execute_reconnect(commands::evaluate(&js)).await
+                        let result = self.eval_value(&js).await
                             .map_err(|e| McpError::internal_error(format!("Step {}: {e}", i + 1), None))?;
-                        if result.get("result").and_then(|r| r.get("value")).and_then(|v| v.as_bool()).unwrap_or(false) {
+                        if result.as_bool().unwrap_or(false) {
                             found = true;
                             break;
                         }
@@ -3626,9 +9915,9 @@ impl CausewayServer {
                             sel = serde_json::to_string(container).unwrap(),
                             text = serde_json::to_string(&text.to_lowercase()).unwrap()
                         );
-                        let result = self.execute_reconnect(commands::evaluate(&js)).await
+                        let result = self.eval_value(&js).await
                             .map_err(|e| McpError::internal_error(format!("Step {}: {e}", i + 1), None))?;
-                        if result.get("result").and_then(|r| r.get("value")).and_then(|v| v.as_bool()).unwrap_or(false) {
+                        if result.as_bool().unwrap_or(false) {
                             found = true;
                             break;
                         }
@@ -3643,27 +9932,36 @@ impl CausewayServer {
                     let url = step.get("url").and_then(|v| v.as_str()).ok_or_else(|| {
                         McpError::invalid_params(format!("Step {}: navigate requires \"url\"", i + 1), None)
                     })?;
+                    check_domain_policy(&self.browser_config, url)
+                        .map_err(|msg| McpError::invalid_params(format!("Step {}: {msg}", i + 1), None))?;
                     self.execute_reconnect(commands::navigate(url)).await
                         .map_err(|e| McpError::internal_error(format!("Step {}: navigate failed: {e}", i + 1), None))?;
                     let _ = self.execute_reconnect(commands::evaluate(
                         "new Promise(resolve => { if (document.readyState === 'complete') { resolve(); return; } window.addEventListener('load', () => resolve(), { once: true }); setTimeout(resolve, 8000); })",
                     )).await;
-                    let title = self.execute_reconnect(commands::evaluate("document.title")).await
+                    let title = self.eval_value("document.title").await
                         .ok()
-                        .and_then(|r| r.get("result")?.get("value")?.as_str().map(|s| s.to_owned()))
+                        .and_then(|v| v.as_str().map(|s| s.to_owned()))
                         .unwrap_or_else(|| "(unknown)".to_owned());
                     format!("Navigated to {url} — {title}")
                 }
+                "evaluate_js" if self.browser_config.safe_mode => {
+                    return Err(McpError::invalid_params(
+                        format!("Step {}: evaluate_js is disabled (safe_mode)", i + 1),
+                        None,
+                    ));
+                }
                 "evaluate_js" => {
                     let expression = step.get("expression").and_then(|v| v.as_str()).ok_or_else(|| {
                         McpError::invalid_params(format!("Step {}: evaluate_js requires \"expression\"", i + 1), None)
                     })?;
-                    let result = self.execute_reconnect(commands::evaluate(expression)).await
+                    let value = self.eval_value(expression).await
                         .map_err(|e| McpError::internal_error(format!("Step {}: JS eval failed: {e}", i + 1), None))?;
-                    let value = result.get("result").and_then(|r| r.get("value"));
-                    let display = value.map(|v| {
-                        if let Some(s) = v.as_str() { s.to_owned() } else { v.to_string() }
-                    }).unwrap_or_else(|| "undefined".to_owned());
+                    let display = match value {
+                        serde_json::Value::Null => "undefined".to_owned(),
+                        serde_json::Value::String(s) => s,
+                        v => v.to_string(),
+                    };
                     let truncated = if display.len() > 200 { format!("{}...", &display[..200]) } else { display };
                     format!("JS: {truncated}")
                 }
@@ -3713,10 +10011,11 @@ impl CausewayServer {
                         label = serde_json::to_string(label).unwrap(),
                         force = force_state,
                     );
-                    let result = self.execute_reconnect(commands::evaluate(&js)).await
+                    let val = self.eval_value(&js).await
                         .map_err(|e| McpError::internal_error(format!("Step {}: {e}", i + 1), None))?;
-                    let val = result.get("result").and_then(|r| r.get("value")).filter(|v| !v.is_null())
-                        .ok_or_else(|| McpError::invalid_params(format!("Step {}: no checkbox/radio found for \"{label}\"", i + 1), None))?;
+                    if val.is_null() {
+                        return Err(McpError::invalid_params(format!("Step {}: no checkbox/radio found for \"{label}\"", i + 1), None));
+                    }
                     let found = val.get("found").and_then(|v| v.as_bool()).unwrap_or(false);
                     if !found {
                         return Err(McpError::invalid_params(format!("Step {}: no checkbox/radio found for \"{label}\"", i + 1), None));
@@ -3741,6 +10040,219 @@ impl CausewayServer {
         )]))
     }
 
+    #[tool(description = "Execute an ordered list of tool calls in one round-trip. Each call is { name, params } using the same parameters as calling the tool directly. Stops at the first failure by default (on_error=\"continue\" to keep going). Returns every step's result, including images. Unlike chain, adds no delays and can call any tool.")]
+    async fn batch(
+        &self,
+        Parameters(BatchParams { calls, on_error }): Parameters<BatchParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if calls.is_empty() {
+            return Err(McpError::invalid_params("No calls provided".to_owned(), None));
+        }
+        let continue_on_error = match on_error.as_deref().unwrap_or("stop") {
+            "stop" => false,
+            "continue" => true,
+            other => return Err(McpError::invalid_params(
+                format!("Unknown on_error: \"{other}\". Use \"stop\" or \"continue\"."),
+                None,
+            )),
+        };
+
+        let total = calls.len();
+        let mut contents: Vec<Content> = Vec::new();
+        let mut failures = 0usize;
+        let mut executed = 0usize;
+
+        for (i, call) in calls.into_iter().enumerate() {
+            executed += 1;
+            let params = call.params.unwrap_or_else(|| serde_json::json!({}));
+            match self.dispatch_tool(&call.name, params).await {
+                Ok(result) if !result.is_error.unwrap_or(false) => {
+                    contents.push(Content::text(format!("{}. {}: ok", i + 1, call.name)));
+                    contents.extend(result.content);
+                }
+                Ok(result) => {
+                    failures += 1;
+                    contents.push(Content::text(format!("{}. {}: FAILED", i + 1, call.name)));
+                    contents.extend(result.content);
+                    if !continue_on_error { break; }
+                }
+                Err(e) => {
+                    failures += 1;
+                    contents.push(Content::text(format!("{}. {}: FAILED — {}", i + 1, call.name, e.message)));
+                    if !continue_on_error { break; }
+                }
+            }
+        }
+
+        let summary = if failures == 0 {
+            format!("Batch complete ({executed}/{total} calls succeeded)")
+        } else if executed < total {
+            format!("Batch stopped at call {executed} of {total} ({failures} failed)")
+        } else {
+            format!("Batch finished with {failures} of {total} calls failed")
+        };
+        contents.insert(0, Content::text(summary));
+
+        if failures == 0 {
+            Ok(CallToolResult::success(contents))
+        } else {
+            Ok(CallToolResult::error(contents))
+        }
+    }
+
+    /// Route a batch call to the matching tool implementation.
+    /// `batch` itself is deliberately absent — no nesting.
+    async fn dispatch_tool(&self, name: &str, params: serde_json::Value) -> Result<CallToolResult, McpError> {
+        if self.browser_config.safe_mode && UNSAFE_TOOLS.contains(&name) {
+            return Err(McpError::invalid_params(format!("{name} is disabled (safe_mode)"), None));
+        }
+        match name {
+            "navigate" => self.navigate(batch_params(name, params)?).await,
+            "screenshot" => self.screenshot(batch_params(name, params)?).await,
+            "filmstrip" => self.filmstrip(batch_params(name, params)?).await,
+            "get_status" => self.get_status().await,
+            "get_title" => self.get_title().await,
+            "set_title" => self.set_title(batch_params(name, params)?).await,
+            "extract_content" => self.extract_content(batch_params(name, params)?).await,
+            "read_page" => self.read_page().await,
+            "snapshot_text" => self.snapshot_text().await,
+            "diff_text_since_snapshot" => self.diff_text_since_snapshot(batch_params(name, params)?).await,
+            "read_text" => self.read_text(batch_params(name, params)?).await,
+            "get_cookies" => self.get_cookies(batch_params(name, params)?).await,
+            "set_cookie" => self.set_cookie(batch_params(name, params)?).await,
+            "wait_for_text" => self.wait_for_text(batch_params(name, params)?).await,
+            "upload_file" => self.upload_file(batch_params(name, params)?).await,
+            "evaluate_js" => self.evaluate_js(batch_params(name, params)?).await,
+            "poll_expression" => self.poll_expression(batch_params(name, params)?).await,
+            "click" => self.click(batch_params(name, params)?).await,
+            "click_at" => self.click_at(batch_params(name, params)?).await,
+            "click_text" => self.click_text(batch_params(name, params)?).await,
+            "click_link" => self.click_link(batch_params(name, params)?).await,
+            "type_text" => self.type_text(batch_params(name, params)?).await,
+            "paste_into" => self.paste_into(batch_params(name, params)?).await,
+            "read_form" => self.read_form(batch_params(name, params)?).await,
+            "assert_text" => self.assert_text(batch_params(name, params)?).await,
+            "assert_count" => self.assert_count(batch_params(name, params)?).await,
+            "get_tab_order" => self.get_tab_order(batch_params(name, params)?).await,
+            "get_validation" => self.get_validation(batch_params(name, params)?).await,
+            "fill_form" => self.fill_form(batch_params(name, params)?).await,
+            "is_loading" => self.is_loading().await,
+            "wait_for" => self.wait_for(batch_params(name, params)?).await,
+            "wait_for_condition" => self.wait_for_condition(batch_params(name, params)?).await,
+            "wait_for_animation" => self.wait_for_animation(batch_params(name, params)?).await,
+            "wait_for_images" => self.wait_for_images(batch_params(name, params)?).await,
+            "watch_mutations" => self.watch_mutations(batch_params(name, params)?).await,
+            "scroll" => self.scroll(batch_params(name, params)?).await,
+            "set_instant_scroll" => self.set_instant_scroll(batch_params(name, params)?).await,
+            "detect_bot_wall" => self.detect_bot_wall().await,
+            "get_scroll_info" => self.get_scroll_info().await,
+            "smooth_scroll" => self.smooth_scroll(batch_params(name, params)?).await,
+            "scroll_element" => self.scroll_element(batch_params(name, params)?).await,
+            "tap" => self.tap(batch_params(name, params)?).await,
+            "swipe" => self.swipe(batch_params(name, params)?).await,
+            "pinch" => self.pinch(batch_params(name, params)?).await,
+            "hover" => self.hover(batch_params(name, params)?).await,
+            "move_mouse" => self.move_mouse(batch_params(name, params)?).await,
+            "press_key" => self.press_key(batch_params(name, params)?).await,
+            "hold_key" => self.hold_key(batch_params(name, params)?).await,
+            "get_attribute" => self.get_attribute(batch_params(name, params)?).await,
+            "get_url" => self.get_url().await,
+            "wait_for_navigation" => self.wait_for_navigation(batch_params(name, params)?).await,
+            "back" => self.back().await,
+            "forward" => self.forward().await,
+            "get_value" => self.get_value(batch_params(name, params)?).await,
+            "set_value" => self.set_value(batch_params(name, params)?).await,
+            "set_slider" => self.set_slider(batch_params(name, params)?).await,
+            "set_checked" => self.set_checked(batch_params(name, params)?).await,
+            "select_option" => self.select_option(batch_params(name, params)?).await,
+            "toggle" => self.toggle(batch_params(name, params)?).await,
+            "submit_form" => self.submit_form(batch_params(name, params)?).await,
+            "discover_webmcp_tools" => self.discover_webmcp_tools().await,
+            "extension_eval" => self.extension_eval(batch_params(name, params)?).await,
+            "inspect" => self.inspect(batch_params(name, params)?).await,
+            "point_inspect" => self.point_inspect(batch_params(name, params)?).await,
+            "element_at_point" => self.element_at_point(batch_params(name, params)?).await,
+            "inspect_styles" => self.inspect_styles(batch_params(name, params)?).await,
+            "query_elements" => self.query_elements(batch_params(name, params)?).await,
+            "query_xpath" => self.query_xpath(batch_params(name, params)?).await,
+            "query_shadow" => self.query_shadow(batch_params(name, params)?).await,
+            "find_overlays" => self.find_overlays(batch_params(name, params)?).await,
+            "dismiss_consent" => self.dismiss_consent(batch_params(name, params)?).await,
+            "list_iframes" => self.list_iframes().await,
+            "get_rect" => self.get_rect(batch_params(name, params)?).await,
+            "map_text" => self.map_text(batch_params(name, params)?).await,
+            "get_links" => self.get_links(batch_params(name, params)?).await,
+            "get_images" => self.get_images(batch_params(name, params)?).await,
+            "extract_table" => self.extract_table(batch_params(name, params)?).await,
+            "get_metadata" => self.get_metadata().await,
+            "get_page_language" => self.get_page_language().await,
+            "get_favicon" => self.get_favicon().await,
+            "get_lcp" => self.get_lcp().await,
+            "get_outline" => self.get_outline().await,
+            "search_dom" => self.search_dom(batch_params(name, params)?).await,
+            "tab_count" => self.tab_count().await,
+            "list_tabs" => self.list_tabs().await,
+            "reconnect" => self.reconnect().await,
+            "switch_tab" => self.switch_tab(batch_params(name, params)?).await,
+            "screenshot_tab" => self.screenshot_tab(batch_params(name, params)?).await,
+            "read_tab_text" => self.read_tab_text(batch_params(name, params)?).await,
+            "new_tab" => self.new_tab(batch_params(name, params)?).await,
+            "close_tab" => self.close_tab(batch_params(name, params)?).await,
+            "new_incognito_tab" => self.new_incognito_tab(batch_params(name, params)?).await,
+            "list_contexts" => self.list_contexts().await,
+            "add_init_script" => self.add_init_script(batch_params(name, params)?).await,
+            "remove_init_script" => self.remove_init_script(batch_params(name, params)?).await,
+            "freeze_time" => self.freeze_time(batch_params(name, params)?).await,
+            "advance_time" => self.advance_time(batch_params(name, params)?).await,
+            "unfreeze_time" => self.unfreeze_time().await,
+            "inject_css" => self.inject_css(batch_params(name, params)?).await,
+            "remove_css" => self.remove_css(batch_params(name, params)?).await,
+            "get_pending_dialog" => self.get_pending_dialog().await,
+            "handle_dialog" => self.handle_dialog(batch_params(name, params)?).await,
+            "keyboard_chord" => self.keyboard_chord(batch_params(name, params)?).await,
+            "double_click" => self.double_click(batch_params(name, params)?).await,
+            "drag" => self.drag(batch_params(name, params)?).await,
+            "set_viewport" => self.set_viewport(batch_params(name, params)?).await,
+            "set_javascript_enabled" => self.set_javascript_enabled(batch_params(name, params)?).await,
+            "set_zoom" => self.set_zoom(batch_params(name, params)?).await,
+            "get_zoom" => self.get_zoom().await,
+            "accessibility_snapshot" => self.accessibility_snapshot().await,
+            "capture_dom_snapshot" => self.capture_dom_snapshot(batch_params(name, params)?).await,
+            "get_console_messages" => self.get_console_messages(batch_params(name, params)?).await,
+            "list_network_requests" => self.list_network_requests(batch_params(name, params)?).await,
+            "get_response_body" => self.get_response_body(batch_params(name, params)?).await,
+            "wait_for_response" => self.wait_for_response(batch_params(name, params)?).await,
+            "mock_response" => self.mock_response(batch_params(name, params)?).await,
+            "unmock" => self.unmock(batch_params(name, params)?).await,
+            "fail_requests" => self.fail_requests(batch_params(name, params)?).await,
+            "unfail_requests" => self.unfail_requests(batch_params(name, params)?).await,
+            "download_file" => self.download_file(batch_params(name, params)?).await,
+            "element_screenshot" => self.element_screenshot(batch_params(name, params)?).await,
+            "get_selector_for_text" => self.get_selector_for_text(batch_params(name, params)?).await,
+            "screenshot_text" => self.screenshot_text(batch_params(name, params)?).await,
+            "wait_for_download" => self.wait_for_download(batch_params(name, params)?).await,
+            "save_pdf" => self.save_pdf(batch_params(name, params)?).await,
+            "capture_mhtml" => self.capture_mhtml(batch_params(name, params)?).await,
+            "get_page_metrics" => self.get_page_metrics().await,
+            "get_performance_metrics" => self.get_performance_metrics().await,
+            "start_coverage" => self.start_coverage().await,
+            "stop_coverage" => self.stop_coverage().await,
+            "grant_permissions" => self.grant_permissions(batch_params(name, params)?).await,
+            "list_permissions" => self.list_permissions().await,
+            "reset_permissions" => self.reset_permissions().await,
+            "clear_cache" => self.clear_cache().await,
+            "clear_storage" => self.clear_storage(batch_params(name, params)?).await,
+            "list_service_workers" => self.list_service_workers().await,
+            "unregister_service_workers" => self.unregister_service_workers(batch_params(name, params)?).await,
+            "go_offline" => self.go_offline().await,
+            "go_online" => self.go_online().await,
+            "emulate_device" => self.emulate_device(batch_params(name, params)?).await,
+            "chain" => self.chain(batch_params(name, params)?).await,
+            "batch" => Err(McpError::invalid_params("batch cannot be nested".to_owned(), None)),
+            unknown => Err(McpError::invalid_params(format!("Unknown tool: {unknown}"), None)),
+        }
+    }
+
     // ---- Event subscription (non-tool methods) ----
 
     /// Subscribe to CDP events from the current connection and spawn a collector task.
@@ -3751,18 +10263,42 @@ impl CausewayServer {
             None => return, // No connection yet — events will be subscribed on first connect
         };
         let receiver = cdp::subscribe_events(&*conn);
-        let console_log = self.console_log.clone();
-        let network_log = self.network_log.clone();
-        let pending_dialog = self.pending_dialog.clone();
-        tokio::spawn(Self::run_event_collector(receiver, console_log, network_log, pending_dialog));
+        // Weak so the collector never keeps a swapped-out connection alive
+        let weak_conn = Arc::downgrade(&conn);
+        // Loads started on the old connection will never report stopping on this one,
+        // and its stylesheet IDs mean nothing on the new one
+        self.loading_frames.lock().await.clear();
+        self.stylesheets.lock().await.clear();
+        let state = CollectorState {
+            browser_config: self.browser_config.clone(),
+            console_log: self.console_log.clone(),
+            network_log: self.network_log.clone(),
+            pending_dialog: self.pending_dialog.clone(),
+            stylesheets: self.stylesheets.clone(),
+            intercept_rules: self.intercept_rules.clone(),
+            downloads: self.downloads.clone(),
+            loading_frames: self.loading_frames.clone(),
+            document_responses: self.document_responses.clone(),
+        };
+        tokio::spawn(Self::run_event_collector(receiver, weak_conn, state));
     }
 
     async fn run_event_collector(
         mut receiver: tokio::sync::broadcast::Receiver<cdp::CdpEvent>,
-        console_log: Arc<tokio::sync::Mutex<Vec<ConsoleEntry>>>,
-        network_log: Arc<tokio::sync::Mutex<Vec<NetworkEntry>>>,
-        pending_dialog: Arc<tokio::sync::Mutex<Option<PendingDialog>>>,
+        conn: std::sync::Weak<cdp::CdpConnection>,
+        state: CollectorState,
     ) {
+        let CollectorState {
+            browser_config,
+            console_log,
+            network_log,
+            pending_dialog,
+            stylesheets,
+            intercept_rules,
+            downloads,
+            loading_frames,
+            document_responses,
+        } = state;
         loop {
             match receiver.recv().await {
                 Ok(event) => {
@@ -3842,6 +10378,22 @@ impl CausewayServer {
                                     break;
                                 }
                             }
+                            drop(log);
+                            if event.params.get("type").and_then(|v| v.as_str()) == Some("Document") {
+                                let frame_id = event.params.get("frameId").and_then(|v| v.as_str());
+                                let response = event.params.get("response");
+                                if let (Some(frame_id), Some(response)) = (frame_id, response) {
+                                    let text = |key: &str| response.get(key).and_then(|v| v.as_str()).unwrap_or("").to_owned();
+                                    document_responses.lock().await.insert(frame_id.to_owned(), DocumentResponse {
+                                        loader_id: event.params.get("loaderId").and_then(|v| v.as_str()).unwrap_or("").to_owned(),
+                                        url: text("url"),
+                                        status: status.unwrap_or(0),
+                                        status_text: text("statusText"),
+                                        mime_type: text("mimeType"),
+                                        headers: response.get("headers").cloned().unwrap_or(serde_json::Value::Null),
+                                    });
+                                }
+                            }
                         }
                         "Page.javascriptDialogOpening" => {
                             let dialog_type = event.params
@@ -3859,13 +10411,168 @@ impl CausewayServer {
                                 .and_then(|v| v.as_str())
                                 .unwrap_or("")
                                 .to_owned();
+                            // beforeunload has its own switch so navigation can't stall on "leave site?"
+                            let auto_accept = if dialog_type == "beforeunload" {
+                                browser_config.bypass_beforeunload
+                            } else {
+                                browser_config.auto_accept_dialogs
+                            };
+                            let prompt_text = default_prompt.clone();
                             *pending_dialog.lock().await = Some(PendingDialog {
                                 dialog_type, message, default_prompt,
                             });
+                            // Answer off-task: the response arrives via the reader, not this loop
+                            if auto_accept {
+                                if let Some(conn) = conn.upgrade() {
+                                    tokio::spawn(async move {
+                                        let (method, params) = commands::handle_dialog(true, Some(&prompt_text));
+                                        let _ = cdp::send(&conn, method, params).await;
+                                    });
+                                }
+                            }
                         }
                         "Page.javascriptDialogClosed" => {
                             *pending_dialog.lock().await = None;
                         }
+                        "Page.frameStartedLoading" => {
+                            if let Some(frame_id) = event.params.get("frameId").and_then(|v| v.as_str()) {
+                                loading_frames.lock().await.insert(frame_id.to_owned());
+                            }
+                        }
+                        "Page.frameStoppedLoading" | "Page.frameDetached" => {
+                            if let Some(frame_id) = event.params.get("frameId").and_then(|v| v.as_str()) {
+                                loading_frames.lock().await.remove(frame_id);
+                            }
+                        }
+                        "Fetch.requestPaused" => {
+                            let request_id = event.params
+                                .get("requestId")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("")
+                                .to_owned();
+                            let url = event.params
+                                .get("request")
+                                .and_then(|r| r.get("url"))
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("");
+                            let is_document = event.params.get("resourceType").and_then(|v| v.as_str()) == Some("Document");
+                            let blocked = if is_document {
+                                check_domain_policy(&browser_config, url).err()
+                            } else {
+                                None
+                            };
+                            let action = intercept_rules
+                                .lock()
+                                .await
+                                .iter()
+                                .find(|r| wildcard_match(&r.pattern, url))
+                                .map(|r| r.action.clone());
+                            let command = match (blocked, action) {
+                                (Some(reason), _) => {
+                                    tracing::info!("Blocked navigation: {reason}");
+                                    commands::fetch_fail(&request_id, "BlockedByClient")
+                                }
+                                (None, Some(InterceptAction::Mock { status, headers, body })) => {
+                                    use base64::Engine;
+                                    let encoded = base64::engine::general_purpose::STANDARD.encode(body.as_bytes());
+                                    commands::fetch_fulfill(&request_id, status, &headers, &encoded)
+                                }
+                                (None, Some(InterceptAction::Fail { reason })) => commands::fetch_fail(&request_id, &reason),
+                                // Permitted document, or no rule matches (rule removed mid-flight) — let it through
+                                (None, None) => commands::fetch_continue(&request_id),
+                            };
+                            // A paused request blocks the page until answered — never drop it
+                            if let Some(conn) = conn.upgrade() {
+                                tokio::spawn(async move {
+                                    let (method, params) = command;
+                                    if let Err(e) = cdp::send(&conn, method, params).await {
+                                        tracing::warn!("Failed to answer paused request: {e}");
+                                    }
+                                });
+                            }
+                        }
+                        // Page.* variants are deprecated but still the only ones some builds send to page sessions
+                        "Browser.downloadWillBegin" | "Page.downloadWillBegin" => {
+                            let guid = event.params.get("guid").and_then(|v| v.as_str()).unwrap_or("").to_owned();
+                            let url = event.params.get("url").and_then(|v| v.as_str()).unwrap_or("").to_owned();
+                            let suggested_filename = event.params
+                                .get("suggestedFilename")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("")
+                                .to_owned();
+                            let mut list = downloads.lock().await;
+                            if !list.iter().any(|d| d.guid == guid) {
+                                list.push(DownloadEntry {
+                                    guid, url, suggested_filename,
+                                    state: "inProgress".to_owned(),
+                                    received_bytes: 0,
+                                    file_path: None,
+                                    reported: false,
+                                });
+                            }
+                        }
+                        "Browser.downloadProgress" | "Page.downloadProgress" => {
+                            let guid = event.params.get("guid").and_then(|v| v.as_str()).unwrap_or("");
+                            let state = event.params.get("state").and_then(|v| v.as_str()).unwrap_or("inProgress");
+                            let received = event.params.get("receivedBytes").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                            let file_path = event.params.get("filePath").and_then(|v| v.as_str());
+                            let mut list = downloads.lock().await;
+                            if let Some(entry) = list.iter_mut().find(|d| d.guid == guid) {
+                                entry.state = state.to_owned();
+                                entry.received_bytes = received as u64;
+                                if let Some(path) = file_path {
+                                    entry.file_path = Some(path.to_owned());
+                                }
+                            }
+                        }
+                        "Fetch.authRequired" => {
+                            let request_id = event.params
+                                .get("requestId")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("")
+                                .to_owned();
+                            let is_proxy = event.params
+                                .get("authChallenge")
+                                .and_then(|c| c.get("source"))
+                                .and_then(|v| v.as_str())
+                                == Some("Proxy");
+                            // Only proxy challenges get the configured credentials; servers get the default prompt
+                            let credentials = browser_config.proxy.as_deref()
+                                .filter(|_| is_proxy)
+                                .and_then(|p| crate::config::parse_proxy(p).credentials);
+                            if let Some(conn) = conn.upgrade() {
+                                tokio::spawn(async move {
+                                    let creds = credentials.as_ref().map(|(u, p)| (u.as_str(), p.as_str()));
+                                    let (method, params) = commands::fetch_continue_with_auth(&request_id, creds);
+                                    if let Err(e) = cdp::send(&conn, method, params).await {
+                                        tracing::warn!("Failed to answer auth challenge: {e}");
+                                    }
+                                });
+                            }
+                        }
+                        "CSS.styleSheetAdded" => {
+                            let header = event.params.get("header");
+                            let id = header
+                                .and_then(|h| h.get("styleSheetId"))
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("")
+                                .to_owned();
+                            let url = header
+                                .and_then(|h| h.get("sourceURL"))
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("")
+                                .to_owned();
+                            stylesheets.lock().await.insert(id, url);
+                        }
+                        "CSS.styleSheetRemoved" => {
+                            if let Some(id) = event.params.get("styleSheetId").and_then(|v| v.as_str()) {
+                                stylesheets.lock().await.remove(id);
+                            }
+                        }
+                        // A new top-level document brings its own sheets; the old IDs are dead
+                        "Page.frameNavigated" if event.params.pointer("/frame/parentId").is_none() => {
+                            stylesheets.lock().await.clear();
+                        }
                         _ => {}
                     }
                 }
@@ -3891,3 +10598,65 @@ impl ServerHandler for CausewayServer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_save_paths_rejected() {
+        for path in ["shot.png", "out/shot.png", "./shot.png", "../shot.png"] {
+            assert!(prepare_save_path(path).is_err(), "{path}");
+        }
+    }
+
+    const PAYLOAD: &str = r#""]);alert(1)//"#;
+
+    /// The payload may appear only inside its JSON string literal, never as live JS.
+    fn assert_embedded_as_literal(js: &str) {
+        let literal = serde_json::to_string(PAYLOAD).unwrap();
+        assert!(js.contains(&literal), "payload literal missing from:\n{js}");
+        assert!(!js.replace(&literal, "").contains("alert(1)"), "payload escaped its literal:\n{js}");
+    }
+
+    #[test]
+    fn selector_payload_stays_a_string() {
+        assert_embedded_as_literal(&js_find_visible_element(PAYLOAD));
+    }
+
+    #[test]
+    fn text_and_tag_payloads_stay_strings() {
+        assert_embedded_as_literal(&js_find_text_element(PAYLOAD, "*"));
+        assert_embedded_as_literal(&js_find_text_element("Save", PAYLOAD));
+    }
+
+    #[test]
+    fn css_payload_stays_a_string() {
+        assert_embedded_as_literal(&js_inject_css("css-1", PAYLOAD));
+    }
+
+    #[test]
+    fn fill_form_accepts_read_form_values() {
+        let params: FillFormParams = serde_json::from_value(serde_json::json!({
+            "selector": "form",
+            "fields": {
+                "[name=\"email\"]": "a@example.com",
+                "[name=\"terms\"]": true,
+                "[name=\"toppings\"]": ["ham", "olives"],
+                "[name=\"size\"]": null,
+                "#age": 42,
+            },
+        })).unwrap();
+        assert_eq!(params.fields.len(), 5);
+        assert_eq!(field_text(&params.fields["[name=\"email\"]"]).as_deref(), Some("a@example.com"));
+        assert_eq!(field_text(&params.fields["#age"]).as_deref(), Some("42"));
+        assert_eq!(field_text(&params.fields["[name=\"terms\"]"]), None);
+        assert_eq!(field_text(&params.fields["[name=\"toppings\"]"]), None);
+    }
+
+    #[test]
+    fn fake_clock_never_emits_bare_non_finite() {
+        let js = js_fake_clock(f64::INFINITY);
+        assert!(!js.contains("inf") && !js.contains("NaN"), "non-finite time leaked into:\n{js}");
+    }
+}